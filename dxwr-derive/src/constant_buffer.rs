@@ -0,0 +1,214 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{LitStr, Type};
+
+#[derive(Default)]
+struct FieldAttrs {
+    padding: bool,
+    name: Option<String>,
+    ty: Option<String>,
+}
+
+fn field_attrs(field: &syn::Field) -> syn::Result<FieldAttrs> {
+    let mut ret = FieldAttrs::default();
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("hlsl"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("padding") {
+                ret.padding = true;
+            } else if meta.path.is_ident("name") {
+                ret.name = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("ty") {
+                ret.ty = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else {
+                return Err(meta.error("expected `padding`, `name` or `ty`"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(ret)
+}
+
+fn struct_attrs(input: &syn::DeriveInput) -> syn::Result<(bool, Option<String>)> {
+    let mut cbuffer = false;
+    let mut name = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("hlsl"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("cbuffer") {
+                cbuffer = true;
+            } else if meta.path.is_ident("name") {
+                name = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else {
+                return Err(meta.error("expected `cbuffer` or `name`"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok((cbuffer, name))
+}
+
+fn scalar_name(ty: &Type) -> Option<&'static str> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let ident = path.path.get_ident()?.to_string();
+    let name = match ident.as_str() {
+        "f32" => "float",
+        "i32" => "int",
+        "u32" => "uint",
+        "f64" => "double",
+        "i64" => "int64_t",
+        "u64" => "uint64_t",
+        _ => return None,
+    };
+    Some(name)
+}
+
+fn array_len(len: &syn::Expr) -> syn::Result<usize> {
+    match len {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(n),
+            ..
+        }) => n.base10_parse(),
+        _ => Err(syn::Error::new_spanned(
+            len,
+            "array lengths must be integer literals to be written to HLSL",
+        )),
+    }
+}
+
+fn element_name(ty: &Type) -> syn::Result<String> {
+    if let Some(name) = scalar_name(ty) {
+        return Ok(name.to_string());
+    }
+    match ty {
+        Type::Array(array) => match scalar_name(&array.elem) {
+            Some(name) => match array_len(&array.len)? {
+                n @ 1..=4 => Ok(format!("{name}{n}")),
+                _ => Err(syn::Error::new_spanned(
+                    ty,
+                    "HLSL vectors have 1 to 4 components",
+                )),
+            },
+            None => Err(syn::Error::new_spanned(
+                ty,
+                "nested arrays are not supported; use #[hlsl(ty = \"...\")]",
+            )),
+        },
+        Type::Path(path) => {
+            let segment = path.path.segments.last().unwrap();
+            if segment.ident == "Padded"
+                && let syn::PathArguments::AngleBracketed(args) = &segment.arguments
+                && let Some(syn::GenericArgument::Type(inner)) = args.args.first()
+            {
+                return element_name(inner);
+            }
+            Ok(segment.ident.to_string())
+        }
+        _ => Err(syn::Error::new_spanned(ty, "unsupported field type")),
+    }
+}
+
+fn declaration(ty: &Type) -> syn::Result<(String, String)> {
+    if let Type::Array(array) = ty
+        && scalar_name(&array.elem).is_none()
+    {
+        let len = array_len(&array.len)?;
+        return Ok((element_name(&array.elem)?, format!("[{len}]")));
+    }
+    Ok((element_name(ty)?, String::new()))
+}
+
+pub fn derive(input: &syn::DeriveInput) -> syn::Result<TokenStream> {
+    let fields = crate::named_fields(input)?;
+    let (cbuffer, hlsl_name) = struct_attrs(input)?;
+    let ident = &input.ident;
+    let hlsl_name = hlsl_name.unwrap_or_else(|| ident.to_string());
+    let mut hlsl = format!(
+        "{} {} {{\n",
+        if cbuffer { "cbuffer" } else { "struct" },
+        hlsl_name
+    );
+    let mut checks = vec![];
+    let mut descs = vec![];
+    let mut types = vec![];
+    for field in &fields.named {
+        let attrs = field_attrs(field)?;
+        if attrs.padding {
+            continue;
+        }
+        let member = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let name = attrs.name.unwrap_or_else(|| crate::field_name(field));
+        let (type_name, suffix) = match attrs.ty {
+            Some(ty) => match ty.find('[') {
+                Some(i) => (ty[..i].trim().to_string(), ty[i..].to_string()),
+                None => (ty, String::new()),
+            },
+            None => declaration(ty)?,
+        };
+        hlsl.push_str(&format!("    {type_name} {name}{suffix};\n"));
+        let message = format!(
+            "`{}::{}` does not follow HLSL packing rules; add a #[hlsl(padding)] field before it",
+            ident,
+            crate::field_name(field)
+        );
+        checks.push(quote! {
+            let offset = ::dxwr::layout::place(
+                end,
+                <#ty as ::dxwr::layout::HlslType>::HLSL_SIZE,
+                <#ty as ::dxwr::layout::HlslType>::HLSL_ALIGN,
+            );
+            assert!(::core::mem::offset_of!(#ident, #member) == offset, #message);
+            end = offset + <#ty as ::dxwr::layout::HlslType>::HLSL_SIZE;
+        });
+        descs.push(quote! {
+            ::dxwr::layout::FieldDesc {
+                name: #name,
+                offset: ::core::mem::offset_of!(#ident, #member),
+                size: <#ty as ::dxwr::layout::HlslType>::HLSL_SIZE,
+            }
+        });
+        types.push(ty);
+    }
+    hlsl.push_str("};\n");
+    let size_message = format!("`{ident}` is smaller than its HLSL layout");
+    Ok(quote! {
+        impl ::dxwr::layout::HlslType for #ident {
+            const HLSL_SIZE: usize = {
+                #[allow(unused_mut)]
+                let mut end = 0usize;
+                #(#checks)*
+                assert!(end <= ::core::mem::size_of::<#ident>(), #size_message);
+                end
+            };
+            const HLSL_ALIGN: usize = 16;
+
+            fn declarations(out: &mut ::std::vec::Vec<&'static str>) {
+                #(<#types as ::dxwr::layout::HlslType>::declarations(out);)*
+                let hlsl = <Self as ::dxwr::layout::ConstantBufferLayout>::HLSL;
+                if !out.contains(&hlsl) {
+                    out.push(hlsl);
+                }
+            }
+        }
+
+        impl ::dxwr::layout::HlslArrayElement for #ident {}
+
+        impl ::dxwr::layout::ConstantBufferLayout for #ident {
+            const HLSL: &'static str = #hlsl;
+            const FIELDS: &'static [::dxwr::layout::FieldDesc] = &[#(#descs),*];
+        }
+
+        const _: () = {
+            let _ = <#ident as ::dxwr::layout::HlslType>::HLSL_SIZE;
+        };
+    })
+}
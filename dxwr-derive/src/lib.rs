@@ -0,0 +1,71 @@
+mod constant_buffer;
+mod root_constants;
+mod vertex_layout;
+
+use proc_macro::TokenStream;
+use syn::{DeriveInput, parse_macro_input};
+
+#[proc_macro_derive(ConstantBuffer, attributes(hlsl))]
+pub fn derive_constant_buffer(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    constant_buffer::derive(&input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+#[proc_macro_derive(RootConstants)]
+pub fn derive_root_constants(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    root_constants::derive(&input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+#[proc_macro_derive(VertexLayout, attributes(semantic, format, vertex))]
+pub fn derive_vertex_layout(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    vertex_layout::derive(&input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+fn named_fields(input: &syn::DeriveInput) -> syn::Result<&syn::FieldsNamed> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "generic structs are not supported",
+        ));
+    }
+    let is_repr_c = input.attrs.iter().any(|attr| {
+        let mut found = false;
+        if attr.path().is_ident("repr") {
+            attr.parse_nested_meta(|meta| {
+                found |= meta.path.is_ident("C");
+                Ok(())
+            })
+            .ok();
+        }
+        found
+    });
+    if !is_repr_c {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "the struct must be #[repr(C)]",
+        ));
+    }
+    match &input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(fields),
+            ..
+        }) => Ok(fields),
+        _ => Err(syn::Error::new_spanned(
+            &input.ident,
+            "only structs with named fields are supported",
+        )),
+    }
+}
+
+fn field_name(field: &syn::Field) -> String {
+    let name = field.ident.as_ref().unwrap().to_string();
+    name.strip_prefix("r#").map(str::to_string).unwrap_or(name)
+}
@@ -0,0 +1,81 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+pub fn derive(input: &syn::DeriveInput) -> syn::Result<TokenStream> {
+    let fields = crate::named_fields(input)?;
+    let ident = &input.ident;
+    let types = fields
+        .named
+        .iter()
+        .map(|field| &field.ty)
+        .collect::<Vec<_>>();
+    let field_checks = fields.named.iter().map(|field| {
+        let ty = &field.ty;
+        let message = format!(
+            "`{}::{}` must be made of 32-bit values",
+            ident,
+            crate::field_name(field)
+        );
+        quote! {
+            assert!(
+                ::core::mem::size_of::<#ty>() % 4 == 0
+                    && ::core::mem::align_of::<#ty>() <= 4,
+                #message
+            );
+        }
+    });
+    let align_message = format!("`{ident}` must be 4-byte aligned");
+    let padding_message = format!("`{ident}` must not contain padding");
+    let size_message = format!("`{ident}` exceeds the 64 32-bit value root signature limit");
+    let call = |tr: TokenStream, value: TokenStream| {
+        quote! {
+            impl ::dxwr::#tr for #value {
+                #[inline]
+                fn call(
+                    self,
+                    cmd_list: &::dxwr::d3d12::ID3D12GraphicsCommandList7,
+                    root_parameter_index: u32,
+                    dest_offset_in_32bit_values: u32,
+                ) {
+                    ::dxwr::#tr::call(
+                        <#ident as ::dxwr::layout::RootConstants>::as_32bit_values(&self),
+                        cmd_list,
+                        root_parameter_index,
+                        dest_offset_in_32bit_values,
+                    );
+                }
+            }
+        }
+    };
+    let graphics = call(quote!(SetGraphicsRoot32BitConstants), quote!(#ident));
+    let graphics_ref = call(quote!(SetGraphicsRoot32BitConstants), quote!(&#ident));
+    let compute = call(quote!(SetComputeRoot32BitConstants), quote!(#ident));
+    let compute_ref = call(quote!(SetComputeRoot32BitConstants), quote!(&#ident));
+    Ok(quote! {
+        unsafe impl ::dxwr::layout::RootConstants for #ident {
+            const NUM_32BIT_VALUES: u32 = {
+                #(#field_checks)*
+                assert!(::core::mem::align_of::<#ident>() == 4, #align_message);
+                assert!(
+                    0 #(+ ::core::mem::size_of::<#types>())* == ::core::mem::size_of::<#ident>(),
+                    #padding_message
+                );
+                let n = ::core::mem::size_of::<#ident>() / 4;
+                assert!(
+                    n <= ::dxwr::layout::MAX_ROOT_32BIT_VALUES as usize,
+                    #size_message
+                );
+                n as u32
+            };
+        }
+
+        #graphics
+        #graphics_ref
+        #compute
+        #compute_ref
+
+        const _: () = {
+            let _ = <#ident as ::dxwr::layout::RootConstants>::NUM_32BIT_VALUES;
+        };
+    })
+}
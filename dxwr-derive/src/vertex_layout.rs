@@ -0,0 +1,199 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::parse::ParseStream;
+use syn::{Ident, LitCStr, LitInt, LitStr, Token};
+
+struct Semantic {
+    name: String,
+    index: u32,
+    span: Span,
+}
+
+fn semantic(field: &syn::Field) -> syn::Result<Semantic> {
+    let Some(attr) = field
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("semantic"))
+    else {
+        return Ok(Semantic {
+            name: crate::field_name(field).to_ascii_uppercase(),
+            index: 0,
+            span: field.ident.as_ref().unwrap().span(),
+        });
+    };
+    attr.parse_args_with(|input: ParseStream| {
+        let name = input.parse::<LitStr>()?;
+        let index = match input.parse::<Option<Token![,]>>()? {
+            Some(_) => input.parse::<LitInt>()?.base10_parse()?,
+            None => 0,
+        };
+        if name.value().is_empty() || name.value().contains('\0') {
+            return Err(syn::Error::new_spanned(&name, "invalid semantic name"));
+        }
+        Ok(Semantic {
+            name: name.value(),
+            index,
+            span: name.span(),
+        })
+    })
+}
+
+struct Format {
+    ident: Ident,
+    mask: u8,
+    bits: usize,
+    component_type: Ident,
+}
+
+fn format(field: &syn::Field) -> syn::Result<Option<Format>> {
+    let Some(attr) = field
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("format"))
+    else {
+        return Ok(None);
+    };
+    let ident = attr.parse_args::<Ident>()?;
+    let name = ident.to_string();
+    let name = name
+        .strip_prefix("DXGI_FORMAT_")
+        .unwrap_or(&name)
+        .to_string();
+    let error = || syn::Error::new_spanned(&ident, format!("`{name}` is not a vertex format"));
+    let (channels, kind) = name.split_once('_').ok_or_else(error)?;
+    let component_type = match kind {
+        "FLOAT" | "UNORM" | "SNORM" => "D3D_REGISTER_COMPONENT_FLOAT32",
+        "UINT" => "D3D_REGISTER_COMPONENT_UINT32",
+        "SINT" => "D3D_REGISTER_COMPONENT_SINT32",
+        _ => return Err(error()),
+    };
+    let mut components = 0;
+    let mut bits = 0;
+    let mut chars = channels.chars().peekable();
+    while let Some(c) = chars.next() {
+        let mut digits = String::new();
+        while let Some(d) = chars.next_if(char::is_ascii_digit) {
+            digits.push(d);
+        }
+        match c {
+            'R' | 'G' | 'B' | 'A' => components += 1,
+            'X' => {}
+            _ => return Err(error()),
+        }
+        bits += digits.parse::<usize>().map_err(|_| error())?;
+    }
+    if components == 0 {
+        return Err(error());
+    }
+    Ok(Some(Format {
+        ident: format_ident!("DXGI_FORMAT_{}", name, span = ident.span()),
+        mask: (1u8 << components) - 1,
+        bits,
+        component_type: format_ident!("{}", component_type),
+    }))
+}
+
+fn instance_step_rate(input: &syn::DeriveInput) -> syn::Result<Option<u32>> {
+    let mut instance = false;
+    let mut step_rate = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("vertex"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("instance") {
+                instance = true;
+            } else if meta.path.is_ident("step_rate") {
+                step_rate = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+            } else {
+                return Err(meta.error("expected `instance` or `step_rate`"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(match (instance, step_rate) {
+        (false, None) => None,
+        (_, step_rate) => Some(step_rate.unwrap_or(1)),
+    })
+}
+
+pub fn derive(input: &syn::DeriveInput) -> syn::Result<TokenStream> {
+    let fields = crate::named_fields(input)?;
+    let ident = &input.ident;
+    let mut elements = vec![];
+    let mut checks = vec![];
+    let mut semantics: Vec<(String, u32)> = vec![];
+    for field in &fields.named {
+        let member = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let semantic = semantic(field)?;
+        let key = (semantic.name.to_ascii_uppercase(), semantic.index);
+        if semantics.contains(&key) {
+            return Err(syn::Error::new(
+                semantic.span,
+                format!("`{}{}` is used more than once", key.0, key.1),
+            ));
+        }
+        semantics.push(key);
+        let name = LitCStr::new(
+            &std::ffi::CString::new(semantic.name).unwrap(),
+            semantic.span,
+        );
+        let index = semantic.index;
+        let (format, mask, component_type) = match format(field)? {
+            Some(format) => {
+                let message = format!(
+                    "`{}::{}` does not match the size of `{}`",
+                    ident,
+                    crate::field_name(field),
+                    format.ident
+                );
+                let bits = format.bits;
+                checks.push(quote! {
+                    assert!(::core::mem::size_of::<#ty>() * 8 == #bits, #message);
+                });
+                let Format {
+                    ident: format,
+                    mask,
+                    component_type,
+                    ..
+                } = format;
+                (
+                    quote!(::dxwr::dxgi::#format),
+                    quote!(#mask),
+                    quote!(::dxwr::d3d::#component_type),
+                )
+            }
+            None => (
+                quote!(<#ty as ::dxwr::layout::VertexFormat>::FORMAT),
+                quote!(<#ty as ::dxwr::layout::VertexFormat>::MASK),
+                quote!(<#ty as ::dxwr::layout::VertexFormat>::COMPONENT_TYPE),
+            ),
+        };
+        elements.push(quote! {
+            ::dxwr::layout::VertexElement {
+                semantic_name: #name,
+                semantic_index: #index,
+                format: #format,
+                offset: ::core::mem::offset_of!(#ident, #member) as u32,
+                mask: #mask,
+                component_type: #component_type,
+            }
+        });
+    }
+    let step_rate = match instance_step_rate(input)? {
+        Some(step_rate) => quote!(::core::option::Option::Some(#step_rate)),
+        None => quote!(::core::option::Option::None),
+    };
+    Ok(quote! {
+        impl ::dxwr::layout::VertexLayout for #ident {
+            const ELEMENTS: &'static [::dxwr::layout::VertexElement] = &[#(#elements),*];
+            const INSTANCE_STEP_RATE: ::core::option::Option<u32> = #step_rate;
+        }
+
+        const _: () = {
+            #(#checks)*
+        };
+    })
+}
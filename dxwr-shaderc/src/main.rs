@@ -0,0 +1,261 @@
+use dxwr::dxc::{Arguments, FileSystemIncludeHandler, Library};
+use dxwr::permutation::PermutationSet;
+use dxwr::shader_archive::{Compression, ShaderArchiveWriter};
+use dxwr::shader_cache::ShaderCache;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+type Error = Box<dyn std::error::Error>;
+
+const USAGE: &str =
+    "usage: dxwr-shaderc [-o <archive>] [--cache <dir>] [--compress] <manifest.toml>";
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Manifest {
+    output: Option<PathBuf>,
+    cache: Option<PathBuf>,
+    #[serde(default, rename = "shader")]
+    shaders: Vec<ShaderEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ShaderEntry {
+    name: String,
+    source: PathBuf,
+    entry: String,
+    target: String,
+    #[serde(default)]
+    defines: Vec<String>,
+    #[serde(default)]
+    include_dirs: Vec<PathBuf>,
+    #[serde(default)]
+    args: Vec<String>,
+    root_signature: Option<String>,
+    #[serde(default)]
+    permutations: Vec<AxisEntry>,
+    #[serde(default)]
+    exclude: Vec<BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AxisEntry {
+    name: String,
+    values: Option<Vec<String>>,
+}
+
+struct Options {
+    manifest: PathBuf,
+    output: Option<PathBuf>,
+    cache: Option<PathBuf>,
+    compress: bool,
+}
+
+fn parse_options() -> Result<Options, Error> {
+    let mut manifest = None;
+    let mut output = None;
+    let mut cache = None;
+    let mut compress = false;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" | "--output" => output = Some(args.next().ok_or(USAGE)?.into()),
+            "--cache" => cache = Some(args.next().ok_or(USAGE)?.into()),
+            "--compress" => compress = true,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                std::process::exit(0);
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{arg}`").into()),
+            _ if manifest.is_none() => manifest = Some(arg.into()),
+            _ => return Err(USAGE.into()),
+        }
+    }
+    Ok(Options {
+        manifest: manifest.ok_or(USAGE)?,
+        output,
+        cache,
+        compress,
+    })
+}
+
+impl ShaderEntry {
+    fn permutation_set(&self) -> Result<PermutationSet, Error> {
+        let mut set = PermutationSet::new();
+        for axis in &self.permutations {
+            if set.axes().iter().any(|a| a.name() == axis.name) {
+                return Err(format!("{}: duplicate permutation `{}`", self.name, axis.name).into());
+            }
+            set = match &axis.values {
+                Some(values) if !values.is_empty() => set.enum_axis(&axis.name, values),
+                Some(_) => {
+                    return Err(format!("{}: `{}` has no values", self.name, axis.name).into());
+                }
+                None => set.bool_axis(&axis.name),
+            };
+        }
+        for conditions in &self.exclude {
+            let conditions = conditions.iter().collect::<Vec<_>>();
+            set = set
+                .exclude(&conditions)
+                .map_err(|e| format!("{}: {}", self.name, e))?;
+        }
+        Ok(set)
+    }
+
+    fn arguments(&self, base_dir: &Path) -> Result<Arguments, Error> {
+        let mut args = Arguments::parse(&self.args)
+            .map_err(|e| format!("{}: {}", self.name, e))?
+            .source_name(base_dir.join(&self.source).to_string_lossy())
+            .target(&self.target)
+            .entry_point(&self.entry);
+        if let Some(root_signature) = &self.root_signature {
+            args = args.rootsig_define(root_signature);
+        }
+        let args = self
+            .include_dirs
+            .iter()
+            .fold(args, |args, dir| args.include_dir(base_dir.join(dir)));
+        Ok(self
+            .defines
+            .iter()
+            .fold(args, |args, define| args.define(define)))
+    }
+}
+
+fn escape_dep_path(path: &Path) -> String {
+    path.to_string_lossy().replace(' ', "\\ ")
+}
+
+fn write_atomic(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+    std::fs::write(&tmp, data)?;
+    std::fs::rename(&tmp, path).inspect_err(|_| {
+        std::fs::remove_file(&tmp).ok();
+    })
+}
+
+fn run() -> Result<(), Error> {
+    let options = parse_options()?;
+    let text = std::fs::read_to_string(&options.manifest)
+        .map_err(|e| format!("{}: {}", options.manifest.display(), e))?;
+    let manifest: Manifest =
+        toml::from_str(&text).map_err(|e| format!("{}: {}", options.manifest.display(), e))?;
+    let base_dir = options.manifest.parent().unwrap_or(Path::new(""));
+    let output = options
+        .output
+        .or_else(|| manifest.output.as_ref().map(|path| base_dir.join(path)))
+        .unwrap_or_else(|| options.manifest.with_extension("dxar"));
+    let cache_dir = options
+        .cache
+        .or_else(|| manifest.cache.as_ref().map(|path| base_dir.join(path)))
+        .unwrap_or_else(|| {
+            output
+                .parent()
+                .unwrap_or(Path::new(""))
+                .join(".dxwr-shaderc-cache")
+        });
+    let cache =
+        ShaderCache::new(&cache_dir).map_err(|e| format!("{}: {}", cache_dir.display(), e))?;
+    let library = Library::from_env()?;
+    let utils = library.utils()?;
+    let compiler = library.compiler(&utils)?;
+    let mut writer = ShaderArchiveWriter::new().compression(if options.compress {
+        Compression::Lz4
+    } else {
+        Compression::None
+    });
+    let mut deps = BTreeSet::new();
+    deps.insert(options.manifest.clone());
+    for shader in &manifest.shaders {
+        let source = base_dir.join(&shader.source);
+        let src = std::fs::read(&source).map_err(|e| format!("{}: {}", source.display(), e))?;
+        deps.insert(source);
+        let set = shader.permutation_set()?;
+        let args = shader.arguments(base_dir)?;
+        for permutation in set.permutations() {
+            let defines = set.defines(&permutation)?.join(", ");
+            let compiled = cache
+                .compile(
+                    &compiler,
+                    &src,
+                    &set.arguments(&args, &permutation)?,
+                    FileSystemIncludeHandler::new(),
+                )
+                .map_err(|e| format!("{} [{}]: {}", shader.name, defines, e.message()))?;
+            deps.extend(compiled.includes.iter().map(|include| include.path.clone()));
+            writer.add(
+                &shader.name,
+                set.key(&permutation)?,
+                &compiled.object,
+                compiled.reflection.as_deref(),
+                compiled.shader_hash.clone(),
+            );
+        }
+    }
+    write_atomic(&output, &writer.to_bytes())
+        .map_err(|e| format!("{}: {}", output.display(), e))?;
+    let depfile = format!(
+        "{}: {}\n",
+        escape_dep_path(&output),
+        deps.iter()
+            .map(|path| escape_dep_path(path))
+            .collect::<Vec<_>>()
+            .join(" ")
+    );
+    let depfile_path = PathBuf::from(format!("{}.d", output.display()));
+    write_atomic(&depfile_path, depfile.as_bytes())
+        .map_err(|e| format!("{}: {}", depfile_path.display(), e))?;
+    println!(
+        "dxwr-shaderc: wrote {} shaders to {}",
+        writer.len(),
+        output.display()
+    );
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("dxwr-shaderc: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arguments_test() {
+        let entry = toml::from_str::<ShaderEntry>(
+            r#"
+            name = "mesh"
+            source = "mesh.hlsl"
+            entry = "main"
+            target = "ps_6_6"
+            args = ["-Zi"]
+            root_signature = "MeshRS"
+            "#,
+        )
+        .unwrap();
+        let args = entry.arguments(Path::new("shaders")).unwrap().to_args();
+        let source = Path::new("shaders").join("mesh.hlsl");
+        assert_eq!(
+            args[..5],
+            [&*source.to_string_lossy(), "-T", "ps_6_6", "-E", "main"]
+        );
+        let i = args
+            .iter()
+            .position(|arg| arg == "-rootsig-define")
+            .unwrap();
+        assert_eq!(args[i + 1], "MeshRS");
+        assert!(args.iter().any(|arg| arg == "-Zi"));
+    }
+}
@@ -143,28 +143,29 @@ fn main() -> anyhow::Result<()> {
     };
     let state_object_props = dxwr::StateObjectProperties::new(&state_object);
     let (vertex_buffer, index_buffer) = build_geometry(&device)?;
-    let geometry_desc: dxwr::RaytracingGeometryDesc = dxwr::RaytracingGeometryDesc::triangles()
-        .vertex_buffer(
-            dxwr::GpuVirtualAddressAndStride::new()
-                .start_address(vertex_buffer.get_gpu_virtual_address())
-                .stride_in_bytes(std::mem::size_of::<Vertex>() as u64),
-            3,
-            DXGI_FORMAT_R32G32B32_FLOAT,
-        )
-        .index_buffer(
-            index_buffer.get_gpu_virtual_address(),
-            3,
-            DXGI_FORMAT_R32_UINT,
-        )
-        .flags(D3D12_RAYTRACING_GEOMETRY_FLAG_OPAQUE)
-        .into();
+    let geometry_descs: [dxwr::RaytracingGeometryDesc; 1] =
+        [dxwr::RaytracingGeometryDesc::triangles()
+            .vertex_buffer(
+                dxwr::GpuVirtualAddressAndStride::new()
+                    .start_address(vertex_buffer.get_gpu_virtual_address())
+                    .stride_in_bytes(std::mem::size_of::<Vertex>() as u64),
+                3,
+                DXGI_FORMAT_R32G32B32_FLOAT,
+            )
+            .index_buffer(
+                index_buffer.get_gpu_virtual_address(),
+                3,
+                DXGI_FORMAT_R32_UINT,
+            )
+            .flags(D3D12_RAYTRACING_GEOMETRY_FLAG_OPAQUE)
+            .into()];
     let top_level_inputs = dxwr::BuildRaytracingAccelerationStructureInputs::top_level()
         .num_descs(1)
         .flags(D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_PREFER_FAST_TRACE);
     let top_level_prebuild_info =
         device.get_raytracing_acceleration_structure_prebuild_info(&top_level_inputs);
     let bottom_level_inputs = dxwr::BuildRaytracingAccelerationStructureInputs::bottom_level()
-        .geometry_descs(&[geometry_desc])
+        .geometry_descs(&geometry_descs)
         .flags(D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_PREFER_FAST_TRACE);
     let bottom_level_prebuild_info =
         device.get_raytracing_acceleration_structure_prebuild_info(&bottom_level_inputs);
@@ -0,0 +1,643 @@
+use crate::dxc::{Arguments, Library};
+use crate::reflection::{ShaderBufferDesc, Struct, Type};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+use windows::Win32::Foundation::E_FAIL;
+use windows::Win32::Graphics::Direct3D::{
+    D3D_CT_CBUFFER, D3D_CT_RESOURCE_BIND_INFO, D3D_CT_TBUFFER,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(C)]
+pub struct Padded<T, const N: usize> {
+    pub value: T,
+    pad: [u8; N],
+}
+
+impl<T, const N: usize> Padded<T, N> {
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        Self { value, pad: [0; N] }
+    }
+}
+
+impl<T: Default, const N: usize> Default for Padded<T, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T, const N: usize> From<T> for Padded<T, N> {
+    #[inline]
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T, const N: usize> std::ops::Deref for Padded<T, N> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T, const N: usize> std::ops::DerefMut for Padded<T, N> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Layout {
+    ConstantBuffer,
+    NoLegacyConstantBuffer,
+    StructuredBuffer,
+}
+
+impl Layout {
+    #[inline]
+    fn is_legacy(self) -> bool {
+        self == Self::ConstantBuffer
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum CodegenError {
+    Unsupported {
+        name: String,
+        ty: String,
+    },
+    OffsetMismatch {
+        name: String,
+        computed: usize,
+        reflected: usize,
+    },
+    SizeMismatch {
+        name: String,
+        computed: usize,
+        reflected: usize,
+    },
+    ConflictingStruct(String),
+}
+
+impl std::fmt::Display for CodegenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unsupported { name, ty } => write!(f, "`{name}` has unsupported type {ty}"),
+            Self::OffsetMismatch {
+                name,
+                computed,
+                reflected,
+            } => write!(
+                f,
+                "`{name}` is at offset {reflected} but the packing rules place it at {computed}"
+            ),
+            Self::SizeMismatch {
+                name,
+                computed,
+                reflected,
+            } => write!(
+                f,
+                "`{name}` is {reflected} bytes but the packing rules give {computed} bytes"
+            ),
+            Self::ConflictingStruct(name) => {
+                write!(
+                    f,
+                    "struct `{name}` has different layouts in the same module"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for CodegenError {}
+
+#[derive(Clone, Debug)]
+enum Shape<'a> {
+    Scalar(&'static str, usize),
+    Vector(&'static str, usize, usize),
+    Array(Box<Shape<'a>>, usize),
+    Struct(&'a Struct),
+}
+
+fn scalar(ty: &Type) -> Option<(&'static str, usize)> {
+    let ret = match ty {
+        Type::Bool | Type::Uint | Type::Min16Uint => ("u32", 4),
+        Type::Int | Type::Min16Int | Type::Min12Int => ("i32", 4),
+        Type::Float | Type::Min10Float | Type::Min8Float => ("f32", 4),
+        Type::Double => ("f64", 8),
+        Type::Int64 => ("i64", 8),
+        Type::Uint64 => ("u64", 8),
+        Type::Float16 | Type::Uint16 => ("u16", 2),
+        Type::Int16 => ("i16", 2),
+        _ => return None,
+    };
+    Some(ret)
+}
+
+fn shape<'a>(name: &str, ty: &'a Type) -> Result<Shape<'a>, CodegenError> {
+    let unsupported = || CodegenError::Unsupported {
+        name: name.to_string(),
+        ty: format!("{ty:?}"),
+    };
+    if let Some((rust, size)) = scalar(ty) {
+        return Ok(Shape::Scalar(rust, size));
+    }
+    match ty {
+        Type::Vector(v) => {
+            let (rust, size) = scalar(&v.ty).ok_or_else(unsupported)?;
+            Ok(Shape::Vector(rust, size, v.len))
+        }
+        Type::Matrix(m) => {
+            let (rust, size) = scalar(&m.ty).ok_or_else(unsupported)?;
+            let (count, len) = match m.major {
+                crate::reflection::MatrixMajor::Columns => (m.columns, m.rows),
+                crate::reflection::MatrixMajor::Rows => (m.rows, m.columns),
+            };
+            Ok(Shape::Array(
+                Box::new(Shape::Vector(rust, size, len)),
+                count,
+            ))
+        }
+        Type::Array(a) if a.len > 0 => Ok(Shape::Array(Box::new(shape(name, &a.ty)?), a.len)),
+        Type::Struct(s) => Ok(Shape::Struct(s)),
+        _ => Err(unsupported()),
+    }
+}
+
+fn round_up(value: usize, align: usize) -> usize {
+    value.div_ceil(align) * align
+}
+
+struct FieldLayout<'a> {
+    name: String,
+    shape: Shape<'a>,
+    offset: usize,
+}
+
+struct StructLayout<'a> {
+    fields: Vec<FieldLayout<'a>>,
+    size: usize,
+    align: usize,
+}
+
+fn rust_ident(name: &str) -> String {
+    let mut ident = name
+        .trim_start_matches('$')
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    const KEYWORDS: &[&str] = &[
+        "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else",
+        "enum", "extern", "false", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "match",
+        "mod", "move", "mut", "priv", "pub", "ref", "return", "static", "struct", "trait", "true",
+        "type", "unsafe", "use", "where", "while", "yield",
+    ];
+    match ident.as_str() {
+        "self" | "Self" | "super" | "_" => format!("{ident}_"),
+        _ if KEYWORDS.contains(&ident.as_str()) => format!("r#{ident}"),
+        _ => ident,
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CodeGenerator {
+    layout: Layout,
+    crate_path: String,
+    items: Vec<(String, String)>,
+    index: HashMap<String, usize>,
+}
+
+impl CodeGenerator {
+    #[inline]
+    pub fn new(layout: Layout) -> Self {
+        Self {
+            layout,
+            crate_path: "dxwr".into(),
+            items: vec![],
+            index: HashMap::new(),
+        }
+    }
+
+    #[inline]
+    pub fn crate_path(mut self, path: impl AsRef<str>) -> Self {
+        self.crate_path = path.as_ref().into();
+        self
+    }
+
+    #[inline]
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn add_buffer(&mut self, desc: &ShaderBufferDesc) -> Result<(), CodegenError> {
+        match desc.ty {
+            D3D_CT_CBUFFER | D3D_CT_TBUFFER => {
+                let name = rust_ident(&desc.name);
+                let layout = self.layout_members(&name, &desc.variables)?;
+                if desc.size < layout.size {
+                    return Err(CodegenError::SizeMismatch {
+                        name,
+                        computed: layout.size,
+                        reflected: desc.size,
+                    });
+                }
+                self.emit_struct(&name, layout.fields, desc.size.max(layout.size))?;
+                Ok(())
+            }
+            D3D_CT_RESOURCE_BIND_INFO => {
+                let Some(Type::Struct(element)) = desc.variables.first().map(|v| &v.ty) else {
+                    return Ok(());
+                };
+                let name = self.add_struct_named(&desc.name, element)?;
+                let size = self.struct_layout(&name, element)?.size;
+                if desc.size != 0 && size != desc.size {
+                    return Err(CodegenError::SizeMismatch {
+                        name,
+                        computed: size,
+                        reflected: desc.size,
+                    });
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    #[inline]
+    pub fn add_struct(&mut self, ty: &Struct) -> Result<String, CodegenError> {
+        self.add_struct_named("", ty)
+    }
+
+    pub fn generate(&self) -> String {
+        self.items
+            .iter()
+            .map(|(_, code)| code.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn add_struct_named(&mut self, fallback: &str, ty: &Struct) -> Result<String, CodegenError> {
+        let name = if ty.name.is_empty() || ty.name.contains('<') {
+            rust_ident(fallback)
+        } else {
+            rust_ident(&ty.name)
+        };
+        let layout = self.struct_layout(&name, ty)?;
+        let size = layout.size;
+        self.emit_struct(&name, layout.fields, size)?;
+        Ok(name)
+    }
+
+    fn struct_layout<'a>(
+        &self,
+        name: &str,
+        ty: &'a Struct,
+    ) -> Result<StructLayout<'a>, CodegenError> {
+        let mut layout = self.layout_members(name, &ty.members)?;
+        if !self.layout.is_legacy() {
+            layout.size = round_up(layout.size, layout.align);
+        }
+        Ok(layout)
+    }
+
+    fn layout_members<'a>(
+        &self,
+        parent: &str,
+        members: &'a [crate::reflection::Member],
+    ) -> Result<StructLayout<'a>, CodegenError> {
+        let mut fields = vec![];
+        let mut end = 0;
+        let mut max_align = 1;
+        for member in members {
+            let path = format!("{parent}.{}", member.name);
+            let shape = shape(&path, &member.ty)?;
+            let (size, align) = self.size_align(&path, &shape)?;
+            let offset = self.place(end, size, align);
+            if offset != member.offset {
+                return Err(CodegenError::OffsetMismatch {
+                    name: path,
+                    computed: offset,
+                    reflected: member.offset,
+                });
+            }
+            fields.push(FieldLayout {
+                name: rust_ident(&member.name),
+                shape,
+                offset,
+            });
+            end = offset + size;
+            max_align = max_align.max(align);
+        }
+        Ok(StructLayout {
+            fields,
+            size: end,
+            align: max_align,
+        })
+    }
+
+    fn size_align(&self, name: &str, shape: &Shape) -> Result<(usize, usize), CodegenError> {
+        let ret = match shape {
+            Shape::Scalar(_, size) => (*size, *size),
+            Shape::Vector(_, size, len) => (size * len, *size),
+            Shape::Array(element, len) => {
+                let (size, align) = self.size_align(name, element)?;
+                if self.layout.is_legacy() {
+                    (round_up(size, 16) * (len - 1) + size, 16)
+                } else {
+                    (round_up(size, align) * len, align)
+                }
+            }
+            Shape::Struct(ty) => {
+                let layout = self.struct_layout(name, ty)?;
+                if self.layout.is_legacy() {
+                    (layout.size, 16)
+                } else {
+                    (layout.size, layout.align)
+                }
+            }
+        };
+        Ok(ret)
+    }
+
+    fn place(&self, end: usize, size: usize, align: usize) -> usize {
+        let offset = round_up(end, align);
+        if self.layout.is_legacy() && offset / 16 != (offset + size - 1) / 16 {
+            round_up(offset, 16)
+        } else {
+            offset
+        }
+    }
+
+    fn rust_type(&mut self, field: &str, shape: &Shape) -> Result<(String, usize), CodegenError> {
+        let ret = match shape {
+            Shape::Scalar(rust, size) => (rust.to_string(), *size),
+            Shape::Vector(rust, size, len) => (format!("[{rust}; {len}]"), size * len),
+            Shape::Array(element, len) => {
+                let (element, stride) = self.element_type(field, element)?;
+                (format!("[{element}; {len}]"), stride * len)
+            }
+            Shape::Struct(ty) => {
+                let name = self.add_struct_named(field, ty)?;
+                let size = self.struct_layout(&name, ty)?.size;
+                (name, size)
+            }
+        };
+        Ok(ret)
+    }
+
+    fn element_type(
+        &mut self,
+        field: &str,
+        shape: &Shape,
+    ) -> Result<(String, usize), CodegenError> {
+        let (element, size) = self.rust_type(field, shape)?;
+        if !self.layout.is_legacy() || size % 16 == 0 {
+            return Ok((element, size));
+        }
+        let stride = round_up(size, 16);
+        Ok((
+            format!(
+                "{}::codegen::Padded<{element}, {}>",
+                self.crate_path,
+                stride - size
+            ),
+            stride,
+        ))
+    }
+
+    fn emit_struct(
+        &mut self,
+        name: &str,
+        fields: Vec<FieldLayout>,
+        size: usize,
+    ) -> Result<(), CodegenError> {
+        let mut body = String::new();
+        let mut asserts = vec![];
+        let mut cursor = 0;
+        let mut pads = 0;
+        let mut pad = |body: &mut String, cursor: &mut usize, to: usize| {
+            if to > *cursor {
+                writeln!(body, "    pub _pad{pads}: [u8; {}],", to - *cursor).unwrap();
+                pads += 1;
+                *cursor = to;
+            }
+        };
+        for (i, field) in fields.iter().enumerate() {
+            let next = fields.get(i + 1).map_or(size, |next| next.offset);
+            pad(&mut body, &mut cursor, field.offset);
+            let last_name = format!("{}_last", field.name.trim_start_matches("r#"));
+            let split = match &field.shape {
+                Shape::Array(element, len) => {
+                    let (ty, stride) = self.element_type(&field.name, element)?;
+                    (field.offset + stride * len > next).then_some((element, ty, stride, *len))
+                }
+                _ => None,
+            };
+            if let Some((element, ty, stride, len)) = split {
+                if len > 1 {
+                    writeln!(body, "    pub {}: [{ty}; {}],", field.name, len - 1).unwrap();
+                    asserts.push((field.name.clone(), field.offset));
+                }
+                let last_offset = field.offset + stride * (len - 1);
+                let (ty, size) = self.rust_type(&field.name, element)?;
+                writeln!(body, "    pub {last_name}: {ty},").unwrap();
+                asserts.push((last_name, last_offset));
+                cursor = last_offset + size;
+            } else {
+                let (ty, size) = self.rust_type(&field.name, &field.shape)?;
+                writeln!(body, "    pub {}: {ty},", field.name).unwrap();
+                asserts.push((field.name.clone(), field.offset));
+                cursor = field.offset + size;
+            }
+        }
+        pad(&mut body, &mut cursor, size);
+        let mut code = String::new();
+        writeln!(code, "#[allow(non_camel_case_types, non_snake_case)]").unwrap();
+        writeln!(code, "#[derive(Clone, Copy, Debug)]").unwrap();
+        writeln!(code, "#[repr(C)]").unwrap();
+        writeln!(code, "pub struct {name} {{\n{body}}}\n").unwrap();
+        writeln!(code, "impl Default for {name} {{").unwrap();
+        writeln!(code, "    fn default() -> Self {{").unwrap();
+        writeln!(code, "        unsafe {{ core::mem::zeroed() }}").unwrap();
+        writeln!(code, "    }}").unwrap();
+        writeln!(code, "}}\n").unwrap();
+        writeln!(code, "const _: () = {{").unwrap();
+        writeln!(
+            code,
+            "    assert!(core::mem::size_of::<{name}>() == {size});"
+        )
+        .unwrap();
+        for (field, offset) in asserts {
+            writeln!(
+                code,
+                "    assert!(core::mem::offset_of!({name}, {field}) == {offset});"
+            )
+            .unwrap();
+        }
+        writeln!(code, "}};").unwrap();
+        match self.index.get(name) {
+            Some(&i) if self.items[i].1 == code => Ok(()),
+            Some(_) => Err(CodegenError::ConflictingStruct(name.to_string())),
+            None => {
+                self.index.insert(name.to_string(), self.items.len());
+                self.items.push((name.to_string(), code));
+                Ok(())
+            }
+        }
+    }
+}
+
+pub(crate) fn type_size(ty: &Type, layout: Layout) -> Result<usize, CodegenError> {
+    let shape = shape("", ty)?;
+    Ok(CodeGenerator::new(layout).size_align("", &shape)?.0)
+}
+
+pub(crate) fn array_stride(ty: &Type, layout: Layout) -> Result<usize, CodegenError> {
+    let shape = shape("", ty)?;
+    let (size, align) = CodeGenerator::new(layout).size_align("", &shape)?;
+    if layout.is_legacy() {
+        Ok(round_up(size, 16))
+    } else {
+        Ok(round_up(size, align))
+    }
+}
+
+pub fn generate(descs: &[ShaderBufferDesc], layout: Layout) -> Result<String, CodegenError> {
+    let mut constant_buffers = CodeGenerator::new(layout);
+    let mut structured_buffers = CodeGenerator::new(Layout::StructuredBuffer);
+    for desc in descs {
+        if desc.ty == D3D_CT_RESOURCE_BIND_INFO {
+            structured_buffers.add_buffer(desc)?;
+        } else {
+            constant_buffers.add_buffer(desc)?;
+        }
+    }
+    let mut code = String::from("// Generated by dxwr::codegen. Do not edit.\n\n");
+    code.push_str(&constant_buffers.generate());
+    if !structured_buffers.is_empty() {
+        if !constant_buffers.is_empty() {
+            code.push('\n');
+        }
+        code.push_str("pub mod structured {\n");
+        for line in structured_buffers.generate().lines() {
+            if line.is_empty() {
+                code.push('\n');
+            } else {
+                writeln!(code, "    {line}").unwrap();
+            }
+        }
+        code.push_str("}\n");
+    }
+    Ok(code)
+}
+
+pub fn build_script(
+    src: impl AsRef<Path>,
+    args: &Arguments,
+    out: impl AsRef<Path>,
+) -> windows::core::Result<()> {
+    let src = src.as_ref();
+    let out = out.as_ref();
+    let error = |path: &Path, e: &dyn std::fmt::Display| {
+        windows::core::Error::new(E_FAIL, format!("{}: {}", path.display(), e))
+    };
+    println!("cargo:rerun-if-changed={}", src.display());
+    println!("cargo:rerun-if-env-changed={}", Library::PATH_VAR);
+    let data = std::fs::read(src).map_err(|e| error(src, &e))?;
+    let library = Library::from_env()?;
+    let utils = library.utils()?;
+    let compiler = library.compiler(&utils)?;
+    let args = args.clone().source_name(src.to_string_lossy());
+    let result = compiler.compile(&data, &args)?;
+    for include in result.includes() {
+        println!("cargo:rerun-if-changed={}", include.path.display());
+    }
+    result.status()?;
+    let layout = if args.is_no_legacy_cbuf_layout() {
+        Layout::NoLegacyConstantBuffer
+    } else {
+        Layout::ConstantBuffer
+    };
+    let descs = result.reflection(&utils)?.constant_buffer_descs()?;
+    let code = generate(&descs, layout).map_err(|e| error(src, &e))?;
+    if std::fs::read(out).is_ok_and(|old| old == code.as_bytes()) {
+        return Ok(());
+    }
+    std::fs::write(out, code).map_err(|e| error(out, &e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{float, member, per_frame};
+
+    #[test]
+    fn codegen_test() {
+        let legacy = per_frame([0, 64, 76, 80, 96, 112, 148, 160, 220], [0, 12, 16], 224);
+        let code = generate(std::slice::from_ref(&legacy), Layout::ConstantBuffer).unwrap();
+        assert!(code.contains("pub struct PerFrame {\n    pub view_proj: [[f32; 4]; 4],\n"));
+        assert!(code.contains("    pub _pad0: [u8; 8],\n    pub tint: [f32; 3],\n"));
+        assert!(code.contains(
+            "    pub weights: [dxwr::codegen::Padded<f32, 12>; 2],\n    pub weights_last: f32,\n    pub bias: f32,\n"
+        ));
+        assert!(code.contains(
+            "    pub lights: [dxwr::codegen::Padded<Light, 4>; 1],\n    pub lights_last: Light,\n    pub count: u32,\n"
+        ));
+        assert!(code.contains("pub struct Light {\n    pub pos: [f32; 3],\n"));
+        assert!(code.contains("assert!(core::mem::size_of::<PerFrame>() == 224);"));
+        assert!(code.contains("assert!(core::mem::offset_of!(PerFrame, lights_last) == 192);"));
+        assert!(code.contains("assert!(core::mem::size_of::<Light>() == 28);"));
+
+        let tight = per_frame([0, 64, 76, 80, 88, 100, 112, 116, 172], [0, 12, 16], 176);
+        let code = generate(&[tight], Layout::NoLegacyConstantBuffer).unwrap();
+        assert!(code.contains("    pub weights: [f32; 3],\n    pub bias: f32,\n"));
+        assert!(code.contains("    pub lights: [Light; 2],\n    pub count: u32,\n}\n"));
+
+        let mut wrong = legacy;
+        wrong.variables[4].offset = 88;
+        assert_eq!(
+            generate(&[wrong], Layout::ConstantBuffer).unwrap_err(),
+            CodegenError::OffsetMismatch {
+                name: "PerFrame.tint".into(),
+                computed: 96,
+                reflected: 88
+            }
+        );
+
+        let particle = Struct {
+            name: "Particle".into(),
+            members: vec![
+                member("position", float(3), 0),
+                member("age", Type::Double, 16),
+            ],
+        };
+        let structured = ShaderBufferDesc {
+            name: "particles".into(),
+            ty: D3D_CT_RESOURCE_BIND_INFO,
+            size: 24,
+            members: vec![Type::Struct(particle.clone())],
+            variables: vec![member("$Element", Type::Struct(particle), 0)],
+        };
+        let code = generate(&[structured], Layout::ConstantBuffer).unwrap();
+        assert!(code.contains("pub mod structured {\n"));
+        assert!(code.contains("        pub age: f64,\n"));
+        assert!(code.contains("assert!(core::mem::size_of::<Particle>() == 24);"));
+
+        assert_eq!(rust_ident("$Globals"), "Globals");
+        assert_eq!(rust_ident("type"), "r#type");
+    }
+}
@@ -224,6 +224,238 @@ impl DispatchRaysDesc {
     }
 }
 
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct SetWorkGraphProgramDesc(D3D12_SET_WORK_GRAPH_DESC);
+
+impl SetWorkGraphProgramDesc {
+    #[inline]
+    pub fn flags(mut self, flags: D3D12_SET_WORK_GRAPH_FLAGS) -> Self {
+        self.0.Flags = flags;
+        self
+    }
+
+    #[inline]
+    pub fn initialize(mut self, value: bool) -> Self {
+        self.0.Flags = if value {
+            D3D12_SET_WORK_GRAPH_FLAG_INITIALIZE
+        } else {
+            D3D12_SET_WORK_GRAPH_FLAG_NONE
+        };
+        self
+    }
+
+    #[inline]
+    pub fn backing_memory(mut self, range: GpuVirtualAddressRange) -> Self {
+        self.0.BackingMemory = range.0;
+        self
+    }
+
+    #[inline]
+    pub fn node_local_root_arguments_table(
+        mut self,
+        table: GpuVirtualAddressRangeAndStride,
+    ) -> Self {
+        self.0.NodeLocalRootArgumentsTable = table.0;
+        self
+    }
+}
+
+impl From<SetWorkGraphProgramDesc> for SetProgramDesc {
+    fn from(value: SetWorkGraphProgramDesc) -> Self {
+        Self(D3D12_SET_PROGRAM_DESC {
+            Type: D3D12_PROGRAM_TYPE_WORK_GRAPH,
+            Anonymous: D3D12_SET_PROGRAM_DESC_0 { WorkGraph: value.0 },
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct SetProgramDesc(D3D12_SET_PROGRAM_DESC);
+
+impl SetProgramDesc {
+    #[inline]
+    pub fn work_graph(id: ProgramIdentifier) -> SetWorkGraphProgramDesc {
+        SetWorkGraphProgramDesc(D3D12_SET_WORK_GRAPH_DESC {
+            ProgramIdentifier: id.0,
+            Flags: D3D12_SET_WORK_GRAPH_FLAG_NONE,
+            ..Default::default()
+        })
+    }
+
+    #[inline]
+    pub fn generic_pipeline(id: ProgramIdentifier) -> Self {
+        Self(D3D12_SET_PROGRAM_DESC {
+            Type: D3D12_PROGRAM_TYPE_GENERIC_PIPELINE,
+            Anonymous: D3D12_SET_PROGRAM_DESC_0 {
+                GenericPipeline: D3D12_SET_GENERIC_PIPELINE_DESC {
+                    ProgramIdentifier: id.0,
+                },
+            },
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+#[repr(transparent)]
+pub struct NodeCpuInput<'a> {
+    input: D3D12_NODE_CPU_INPUT,
+    _a: std::marker::PhantomData<&'a ()>,
+}
+
+impl<'a> NodeCpuInput<'a> {
+    #[inline]
+    pub fn new<T>(entrypoint_index: u32, records: &'a [T]) -> Self {
+        Self {
+            input: D3D12_NODE_CPU_INPUT {
+                EntrypointIndex: entrypoint_index,
+                NumRecords: records.len() as u32,
+                pRecords: records.as_ptr() as *const std::ffi::c_void,
+                RecordStrideInBytes: std::mem::size_of::<T>() as u64,
+            },
+            _a: std::marker::PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn from_bytes(
+        entrypoint_index: u32,
+        data: &'a [u8],
+        record_stride_in_bytes: u64,
+    ) -> Result<Self, NodeInputError> {
+        assert!(record_stride_in_bytes > 0);
+        if !(data.len() as u64).is_multiple_of(record_stride_in_bytes) {
+            return Err(NodeInputError::PartialRecord {
+                entrypoint_index,
+                len: data.len(),
+                stride: record_stride_in_bytes,
+            });
+        }
+        Ok(Self {
+            input: D3D12_NODE_CPU_INPUT {
+                EntrypointIndex: entrypoint_index,
+                NumRecords: (data.len() as u64 / record_stride_in_bytes) as u32,
+                pRecords: data.as_ptr() as *const std::ffi::c_void,
+                RecordStrideInBytes: record_stride_in_bytes,
+            },
+            _a: std::marker::PhantomData,
+        })
+    }
+
+    #[inline]
+    pub fn entrypoint_index(&self) -> u32 {
+        self.input.EntrypointIndex
+    }
+
+    #[inline]
+    pub fn num_records(&self) -> u32 {
+        self.input.NumRecords
+    }
+
+    #[inline]
+    pub fn record_stride_in_bytes(&self) -> u64 {
+        self.input.RecordStrideInBytes
+    }
+
+    pub(crate) fn records_ptr(&self) -> *const std::ffi::c_void {
+        self.input.pRecords
+    }
+}
+
+#[derive(Clone, Copy, Default, Debug)]
+#[repr(transparent)]
+pub struct NodeGpuInput(pub D3D12_NODE_GPU_INPUT);
+
+impl NodeGpuInput {
+    #[inline]
+    pub fn new() -> Self {
+        Self(D3D12_NODE_GPU_INPUT::default())
+    }
+
+    #[inline]
+    pub fn entrypoint_index(mut self, index: u32) -> Self {
+        self.0.EntrypointIndex = index;
+        self
+    }
+
+    #[inline]
+    pub fn num_records(mut self, n: u32) -> Self {
+        self.0.NumRecords = n;
+        self
+    }
+
+    #[inline]
+    pub fn records(mut self, records: GpuVirtualAddressAndStride) -> Self {
+        self.0.Records = records.0;
+        self
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct DispatchGraphDesc<'a> {
+    desc: D3D12_DISPATCH_GRAPH_DESC,
+    _a: std::marker::PhantomData<&'a ()>,
+}
+
+impl<'a> DispatchGraphDesc<'a> {
+    #[inline]
+    pub fn node_cpu_input(input: NodeCpuInput<'a>) -> Self {
+        Self {
+            desc: D3D12_DISPATCH_GRAPH_DESC {
+                Mode: D3D12_DISPATCH_MODE_NODE_CPU_INPUT,
+                Anonymous: D3D12_DISPATCH_GRAPH_DESC_0 {
+                    NodeCPUInput: input.input,
+                },
+            },
+            _a: std::marker::PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn multi_node_cpu_input(inputs: &'a [NodeCpuInput<'a>]) -> Self {
+        Self {
+            desc: D3D12_DISPATCH_GRAPH_DESC {
+                Mode: D3D12_DISPATCH_MODE_MULTI_NODE_CPU_INPUT,
+                Anonymous: D3D12_DISPATCH_GRAPH_DESC_0 {
+                    MultiNodeCPUInput: D3D12_MULTI_NODE_CPU_INPUT {
+                        NumNodeInputs: inputs.len() as u32,
+                        pNodeInputs: inputs.as_ptr() as *const D3D12_NODE_CPU_INPUT,
+                        NodeInputStrideInBytes: std::mem::size_of::<NodeCpuInput>() as u64,
+                    },
+                },
+            },
+            _a: std::marker::PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn node_gpu_input(addr: GpuVirtualAddress) -> Self {
+        Self {
+            desc: D3D12_DISPATCH_GRAPH_DESC {
+                Mode: D3D12_DISPATCH_MODE_NODE_GPU_INPUT,
+                Anonymous: D3D12_DISPATCH_GRAPH_DESC_0 {
+                    NodeGPUInput: addr.0,
+                },
+            },
+            _a: std::marker::PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn multi_node_gpu_input(addr: GpuVirtualAddress) -> Self {
+        Self {
+            desc: D3D12_DISPATCH_GRAPH_DESC {
+                Mode: D3D12_DISPATCH_MODE_MULTI_NODE_GPU_INPUT,
+                Anonymous: D3D12_DISPATCH_GRAPH_DESC_0 {
+                    MultiNodeGPUInput: addr.0,
+                },
+            },
+            _a: std::marker::PhantomData,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 #[repr(transparent)]
 pub struct StreamOutputBufferView(D3D12_STREAM_OUTPUT_BUFFER_VIEW);
@@ -439,6 +671,7 @@ impl Drop for TextureCopyLocation {
 
 pub struct Commands<'a, T> {
     cmd_list: &'a ID3D12GraphicsCommandList7,
+    cmd_list10: Option<&'a ID3D12GraphicsCommandList10>,
     _t: std::marker::PhantomData<T>,
 }
 
@@ -586,6 +819,17 @@ impl<T> Commands<'_, T> {
         }
     }
 
+    #[inline]
+    pub fn dispatch_graph(&self, desc: &DispatchGraphDesc) -> windows::core::Result<()> {
+        let cmd_list = self
+            .cmd_list10
+            .ok_or(windows::Win32::Foundation::E_NOINTERFACE)?;
+        unsafe {
+            cmd_list.DispatchGraph(&desc.desc);
+        }
+        Ok(())
+    }
+
     #[inline]
     pub fn draw_indexed_instanced(
         &self,
@@ -780,6 +1024,17 @@ impl<T> Commands<'_, T> {
         state.call(self.cmd_list);
     }
 
+    #[inline]
+    pub fn set_program(&self, desc: &SetProgramDesc) -> windows::core::Result<()> {
+        let cmd_list = self
+            .cmd_list10
+            .ok_or(windows::Win32::Foundation::E_NOINTERFACE)?;
+        unsafe {
+            cmd_list.SetProgram(&desc.0);
+        }
+        Ok(())
+    }
+
     #[inline]
     pub fn set_graphics_root_signature(&self, root_sig: &RootSignature) {
         unsafe {
@@ -1022,6 +1277,7 @@ where
         let name = self.name.map(|n| Name::new(&handle, n));
         unsafe { handle.Close()? };
         Ok(GraphicsCommandList {
+            handle10: handle.cast().ok(),
             handle,
             name,
             _t: std::marker::PhantomData,
@@ -1036,6 +1292,7 @@ pub trait CommandList<T> {
 #[derive(Clone, Debug)]
 pub struct GraphicsCommandList<T = ()> {
     handle: ID3D12GraphicsCommandList7,
+    handle10: Option<ID3D12GraphicsCommandList10>,
     name: Option<Name>,
     _t: std::marker::PhantomData<T>,
 }
@@ -1076,6 +1333,7 @@ where
             self.handle.Reset(allocator.handle(), None)?;
             let ret = f(Commands {
                 cmd_list: &self.handle,
+                cmd_list10: self.handle10.as_ref(),
                 _t: std::marker::PhantomData,
             });
             self.handle.Close()?;
@@ -0,0 +1,524 @@
+use crate::codegen::{CodegenError, Layout, array_stride};
+use crate::layout::Half;
+use crate::reflection::{MatrixMajor, Member, ShaderBufferDesc, Type, Vector};
+use std::ops::Range;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum WriterError {
+    InvalidPath(String),
+    Unknown(String),
+    Index {
+        path: String,
+        index: usize,
+        len: usize,
+    },
+    Type {
+        path: String,
+        ty: Type,
+    },
+    OutOfBounds {
+        path: String,
+        end: usize,
+        len: usize,
+    },
+    BufferSize {
+        required: usize,
+        actual: usize,
+    },
+    Layout(CodegenError),
+}
+
+impl std::fmt::Display for WriterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidPath(path) => write!(f, "`{path}` is not a valid path"),
+            Self::Unknown(path) => write!(f, "`{path}` does not name a variable"),
+            Self::Index { path, index, len } => {
+                write!(
+                    f,
+                    "index {index} is out of range for `{path}` ({len} elements)"
+                )
+            }
+            Self::Type { path, ty } => write!(f, "`{path}` is {ty:?}"),
+            Self::OutOfBounds { path, end, len } => write!(
+                f,
+                "`{path}` ends at byte {end} but the buffer is {len} bytes"
+            ),
+            Self::BufferSize { required, actual } => write!(
+                f,
+                "the buffer is {actual} bytes but the constant buffer needs {required} bytes"
+            ),
+            Self::Layout(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for WriterError {}
+
+impl From<CodegenError> for WriterError {
+    fn from(value: CodegenError) -> Self {
+        Self::Layout(value)
+    }
+}
+
+pub trait ConstantScalar: Copy {
+    const SIZE: usize;
+
+    fn matches(ty: &Type) -> bool;
+    fn write(self, dest: &mut [u8]);
+}
+
+macro_rules! impl_constant_scalar {
+    ($($t:ty => $($ty:ident)|+;)*) => {
+        $(
+            impl ConstantScalar for $t {
+                const SIZE: usize = std::mem::size_of::<$t>();
+
+                #[inline]
+                fn matches(ty: &Type) -> bool {
+                    matches!(ty, $(Type::$ty)|+)
+                }
+
+                #[inline]
+                fn write(self, dest: &mut [u8]) {
+                    dest.copy_from_slice(&self.to_le_bytes());
+                }
+            }
+        )*
+    };
+}
+
+impl_constant_scalar! {
+    f32 => Float | Min10Float | Min8Float;
+    u32 => Uint | Min16Uint | Bool;
+    i32 => Int | Min16Int | Min12Int;
+    f64 => Double;
+    u64 => Uint64;
+    i64 => Int64;
+    u16 => Uint16;
+    i16 => Int16;
+}
+
+impl ConstantScalar for bool {
+    const SIZE: usize = 4;
+
+    #[inline]
+    fn matches(ty: &Type) -> bool {
+        matches!(ty, Type::Bool)
+    }
+
+    #[inline]
+    fn write(self, dest: &mut [u8]) {
+        (self as u32).write(dest);
+    }
+}
+
+impl ConstantScalar for Half {
+    const SIZE: usize = 2;
+
+    #[inline]
+    fn matches(ty: &Type) -> bool {
+        matches!(ty, Type::Float16)
+    }
+
+    #[inline]
+    fn write(self, dest: &mut [u8]) {
+        self.0.write(dest);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ValueShape {
+    Scalar,
+    Vector(usize),
+    Matrix { rows: usize, columns: usize },
+}
+
+pub trait ConstantValue {
+    type Scalar: ConstantScalar;
+    const SHAPE: ValueShape;
+
+    fn scalars(&self) -> &[Self::Scalar];
+}
+
+impl<T: ConstantScalar> ConstantValue for T {
+    type Scalar = T;
+    const SHAPE: ValueShape = ValueShape::Scalar;
+
+    #[inline]
+    fn scalars(&self) -> &[T] {
+        std::slice::from_ref(self)
+    }
+}
+
+impl<T: ConstantScalar, const N: usize> ConstantValue for [T; N] {
+    type Scalar = T;
+    const SHAPE: ValueShape = ValueShape::Vector(N);
+
+    #[inline]
+    fn scalars(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T: ConstantScalar, const R: usize, const C: usize> ConstantValue for [[T; C]; R] {
+    type Scalar = T;
+    const SHAPE: ValueShape = ValueShape::Matrix {
+        rows: R,
+        columns: C,
+    };
+
+    #[inline]
+    fn scalars(&self) -> &[T] {
+        self.as_flattened()
+    }
+}
+
+enum Segment<'a> {
+    Name(&'a str),
+    Index(usize),
+}
+
+fn parse(path: &str) -> Option<Vec<Segment<'_>>> {
+    let is_ident = |s: &str| {
+        !s.is_empty()
+            && !s.starts_with(|c: char| c.is_ascii_digit())
+            && s.chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+    };
+    let mut segments = vec![];
+    let mut rest = path;
+    let mut first = true;
+    while !rest.is_empty() {
+        if let Some(index) = rest.strip_prefix('[') {
+            let (index, tail) = index.split_once(']')?;
+            segments.push(Segment::Index(index.trim().parse().ok()?));
+            rest = tail;
+        } else {
+            let name = if first { rest } else { rest.strip_prefix('.')? };
+            let end = name.find(['.', '[']).unwrap_or(name.len());
+            if !is_ident(&name[..end]) {
+                return None;
+            }
+            segments.push(Segment::Name(&name[..end]));
+            rest = &name[end..];
+        }
+        first = false;
+    }
+    matches!(segments.first(), Some(Segment::Name(_))).then_some(segments)
+}
+
+#[derive(Clone, Debug)]
+pub struct ConstantBufferWriter<B = Vec<u8>> {
+    variables: Vec<Member>,
+    layout: Layout,
+    data: B,
+    dirty: Vec<Range<usize>>,
+}
+
+impl ConstantBufferWriter<Vec<u8>> {
+    #[inline]
+    pub fn new(desc: &ShaderBufferDesc, layout: Layout) -> Self {
+        Self {
+            variables: desc.variables.clone(),
+            layout,
+            data: vec![0; desc.size],
+            dirty: vec![],
+        }
+    }
+}
+
+impl<B> ConstantBufferWriter<B>
+where
+    B: AsRef<[u8]> + AsMut<[u8]>,
+{
+    #[inline]
+    pub fn with_buffer(
+        desc: &ShaderBufferDesc,
+        layout: Layout,
+        data: B,
+    ) -> Result<Self, WriterError> {
+        let actual = data.as_ref().len();
+        if actual < desc.size {
+            return Err(WriterError::BufferSize {
+                required: desc.size,
+                actual,
+            });
+        }
+        Ok(Self {
+            variables: desc.variables.clone(),
+            layout,
+            data,
+            dirty: vec![],
+        })
+    }
+
+    #[inline]
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    pub fn resolve(&self, path: &str) -> Result<(usize, &Type), WriterError> {
+        let segments = parse(path).ok_or_else(|| WriterError::InvalidPath(path.into()))?;
+        let unknown = || WriterError::Unknown(path.into());
+        let mut offset = 0;
+        let mut ty = None::<&Type>;
+        for segment in segments {
+            match (segment, ty) {
+                (Segment::Name(name), None) => {
+                    let variable = self
+                        .variables
+                        .iter()
+                        .find(|v| v.name == name)
+                        .ok_or_else(unknown)?;
+                    offset = variable.offset;
+                    ty = Some(&variable.ty);
+                }
+                (Segment::Name(name), Some(Type::Struct(s))) => {
+                    let member = s
+                        .members
+                        .iter()
+                        .find(|m| m.name == name)
+                        .ok_or_else(unknown)?;
+                    offset += member.offset;
+                    ty = Some(&member.ty);
+                }
+                (Segment::Index(index), Some(Type::Array(a))) => {
+                    if index >= a.len {
+                        return Err(WriterError::Index {
+                            path: path.into(),
+                            index,
+                            len: a.len,
+                        });
+                    }
+                    offset += index * array_stride(&a.ty, self.layout)?;
+                    ty = Some(&a.ty);
+                }
+                _ => return Err(unknown()),
+            }
+        }
+        Ok((offset, ty.unwrap()))
+    }
+
+    pub fn set<V>(&mut self, path: &str, value: &V) -> Result<(), WriterError>
+    where
+        V: ConstantValue,
+    {
+        let (offset, ty) = self.resolve(path)?;
+        let size = V::Scalar::SIZE;
+        let scalars = value.scalars();
+        let mut writes = vec![];
+        match (V::SHAPE, ty) {
+            (ValueShape::Scalar, ty) if V::Scalar::matches(ty) => {
+                writes.push((offset, scalars[0]));
+            }
+            (ValueShape::Vector(len), Type::Vector(v))
+                if v.len == len && V::Scalar::matches(&v.ty) =>
+            {
+                writes.extend(
+                    scalars
+                        .iter()
+                        .enumerate()
+                        .map(|(i, scalar)| (offset + i * size, *scalar)),
+                );
+            }
+            (ValueShape::Matrix { rows, columns }, Type::Matrix(m))
+                if m.rows == rows && m.columns == columns && V::Scalar::matches(&m.ty) =>
+            {
+                let (count, len) = match m.major {
+                    MatrixMajor::Columns => (columns, rows),
+                    MatrixMajor::Rows => (rows, columns),
+                };
+                let vector = Type::Vector(Vector {
+                    ty: m.ty.clone(),
+                    len,
+                });
+                let stride = array_stride(&vector, self.layout)?;
+                for i in 0..count {
+                    for j in 0..len {
+                        let (row, column) = match m.major {
+                            MatrixMajor::Columns => (j, i),
+                            MatrixMajor::Rows => (i, j),
+                        };
+                        writes.push((
+                            offset + i * stride + j * size,
+                            scalars[row * columns + column],
+                        ));
+                    }
+                }
+            }
+            (_, ty) => {
+                return Err(WriterError::Type {
+                    path: path.into(),
+                    ty: ty.clone(),
+                });
+            }
+        }
+        let end = writes
+            .iter()
+            .map(|(offset, _)| offset + size)
+            .max()
+            .unwrap_or(offset);
+        let data = self.data.as_mut();
+        if end > data.len() {
+            return Err(WriterError::OutOfBounds {
+                path: path.into(),
+                end,
+                len: data.len(),
+            });
+        }
+        for (offset, scalar) in writes {
+            scalar.write(&mut data[offset..offset + size]);
+        }
+        self.mark_dirty(offset..end);
+        Ok(())
+    }
+
+    fn mark_dirty(&mut self, mut range: Range<usize>) {
+        self.dirty.retain(|r| {
+            if r.start <= range.end && range.start <= r.end {
+                range.start = range.start.min(r.start);
+                range.end = range.end.max(r.end);
+                false
+            } else {
+                true
+            }
+        });
+        let index = self.dirty.partition_point(|r| r.start < range.start);
+        self.dirty.insert(index, range);
+    }
+
+    #[inline]
+    pub fn dirty_ranges(&self) -> &[Range<usize>] {
+        &self.dirty
+    }
+
+    #[inline]
+    pub fn dirty_range(&self) -> Option<Range<usize>> {
+        Some(self.dirty.first()?.start..self.dirty.last()?.end)
+    }
+
+    #[inline]
+    pub fn clear_dirty(&mut self) {
+        self.dirty.clear();
+    }
+
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        self.data.as_ref()
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> B {
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::per_frame;
+
+    fn read(data: &[u8], offset: usize) -> f32 {
+        f32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn constant_buffer_writer_test() {
+        let view_proj: [[f32; 4]; 4] =
+            std::array::from_fn(|row| std::array::from_fn(|column| (row * 4 + column) as f32));
+        for (layout, offsets, size, expected) in [
+            (
+                Layout::ConstantBuffer,
+                [0, 64, 76, 80, 96, 112, 148, 160, 220],
+                224,
+                [144, 204],
+            ),
+            (
+                Layout::NoLegacyConstantBuffer,
+                [0, 64, 76, 80, 88, 100, 112, 116, 172],
+                176,
+                [108, 156],
+            ),
+        ] {
+            let desc = per_frame(offsets, [0, 12, 16], size);
+            let mut writer = ConstantBufferWriter::new(&desc, layout);
+            writer.set("light_dir", &[1.0f32, 0.5, 0.25]).unwrap();
+            writer.set("intensity", &2.0f32).unwrap();
+            writer.set("weights[2]", &0.5f32).unwrap();
+            assert_eq!(
+                writer.dirty_ranges(),
+                [64..80, expected[0]..expected[0] + 4]
+            );
+            writer.set("lights[1].radius", &4.0f32).unwrap();
+            writer.set("view_proj", &view_proj).unwrap();
+            writer.set("count", &3u32).unwrap();
+            assert_eq!(writer.dirty_range(), Some(0..size));
+            let data = writer.data();
+            assert_eq!(read(data, 68), 0.5);
+            assert_eq!(read(data, 76), 2.0);
+            assert_eq!(read(data, expected[0]), 0.5);
+            assert_eq!(read(data, expected[1]), 4.0);
+            assert_eq!(data[size - 4..], 3u32.to_le_bytes());
+            for (row, values) in view_proj.iter().enumerate() {
+                for (column, value) in values.iter().enumerate() {
+                    assert_eq!(read(data, column * 16 + row * 4), *value);
+                }
+            }
+        }
+
+        let desc = per_frame([0, 64, 76, 80, 96, 112, 148, 160, 220], [0, 12, 16], 224);
+        let mut writer = ConstantBufferWriter::new(&desc, Layout::ConstantBuffer);
+        assert!(matches!(
+            writer.set("light_dir", &1.0f32),
+            Err(WriterError::Type { .. })
+        ));
+        assert!(matches!(
+            writer.set("light_dir", &[1i32, 2, 3]),
+            Err(WriterError::Type { .. })
+        ));
+        assert!(matches!(
+            writer.set("view_proj", &[[1.0f32; 3]; 4]),
+            Err(WriterError::Type { .. })
+        ));
+        assert_eq!(
+            writer.set("lights[2].pos", &[0.0f32; 3]),
+            Err(WriterError::Index {
+                path: "lights[2].pos".into(),
+                index: 2,
+                len: 2,
+            })
+        );
+        assert_eq!(
+            writer.set("lights[0].intensity", &0.0f32),
+            Err(WriterError::Unknown("lights[0].intensity".into()))
+        );
+        assert_eq!(
+            writer.set("count[0]", &0u32),
+            Err(WriterError::Unknown("count[0]".into()))
+        );
+        for path in ["", "lights..pos", "[0]", "lights[x]", "lights[0"] {
+            assert_eq!(
+                writer.set(path, &0.0f32),
+                Err(WriterError::InvalidPath(path.into()))
+            );
+        }
+        assert!(writer.dirty_ranges().is_empty());
+
+        let mut buffer = [0u8; 224];
+        let mut writer =
+            ConstantBufferWriter::with_buffer(&desc, Layout::ConstantBuffer, &mut buffer[..])
+                .unwrap();
+        writer.set("lights[0].radius", &1.0f32).unwrap();
+        writer.clear_dirty();
+        assert_eq!(writer.dirty_range(), None);
+        assert_eq!(read(&buffer, 172), 1.0);
+        assert_eq!(
+            ConstantBufferWriter::with_buffer(&desc, Layout::ConstantBuffer, vec![0; 16]).err(),
+            Some(WriterError::BufferSize {
+                required: 224,
+                actual: 16,
+            })
+        );
+    }
+}
@@ -1264,6 +1264,18 @@ where
         }
     }
 
+    #[inline]
+    pub(crate) fn copy_descriptor(&self, src: &CpuDescriptorHandle<T>, dest: usize) {
+        unsafe {
+            self.field.device.CopyDescriptorsSimple(
+                1,
+                self.cpu_handle(dest).handle,
+                src.handle,
+                T::VALUE,
+            );
+        }
+    }
+
     #[inline]
     pub fn handle(&self) -> &ID3D12DescriptorHeap {
         &self.field.handle
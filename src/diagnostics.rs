@@ -0,0 +1,343 @@
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub enum Severity {
+    Note,
+    Remark,
+    Warning,
+    Error,
+    Fatal,
+}
+
+impl Severity {
+    const KEYWORDS: [(&'static str, Self); 5] = [
+        ("fatal error", Self::Fatal),
+        ("error", Self::Error),
+        ("warning", Self::Warning),
+        ("note", Self::Note),
+        ("remark", Self::Remark),
+    ];
+
+    #[inline]
+    pub fn is_error(&self) -> bool {
+        matches!(self, Self::Error | Self::Fatal)
+    }
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Note => "note",
+            Self::Remark => "remark",
+            Self::Warning => "warning",
+            Self::Error => "error",
+            Self::Fatal => "fatal error",
+        };
+        f.write_str(s)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct IncludeLocation {
+    pub file: String,
+    pub line: u32,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Diagnostic {
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub severity: Severity,
+    pub code: Option<String>,
+    pub message: String,
+    pub notes: Vec<Diagnostic>,
+    pub source_excerpt: Option<String>,
+    pub include_stack: Vec<IncludeLocation>,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, include) in self.include_stack.iter().enumerate() {
+            let prefix = if i == 0 {
+                "In file included from"
+            } else {
+                "                 from"
+            };
+            writeln!(f, "{} {}:{}:", prefix, include.file, include.line)?;
+        }
+        if let Some(file) = &self.file {
+            write!(f, "{file}:")?;
+            if let Some(line) = self.line {
+                write!(f, "{line}:")?;
+            }
+            if let Some(column) = self.column {
+                write!(f, "{column}:")?;
+            }
+            write!(f, " ")?;
+        }
+        write!(f, "{}: {}", self.severity, self.message)?;
+        if let Some(code) = &self.code {
+            write!(f, " [{code}]")?;
+        }
+        if let Some(excerpt) = &self.source_excerpt {
+            write!(f, "\n{excerpt}")?;
+        }
+        for note in &self.notes {
+            write!(f, "\n{note}")?;
+        }
+        Ok(())
+    }
+}
+
+struct Header<'a> {
+    location: &'a str,
+    severity: Severity,
+    message: &'a str,
+}
+
+fn parse_header(line: &str) -> Option<Header<'_>> {
+    let mut found: Option<(usize, usize, Severity)> = None;
+    for (keyword, severity) in Severity::KEYWORDS {
+        let candidates = [
+            line.starts_with(&format!("{keyword}: "))
+                .then_some((0, keyword.len() + 2)),
+            line.find(&format!(": {keyword}: "))
+                .map(|i| (i, i + keyword.len() + 4)),
+            line.find(&format!(": {keyword} "))
+                .filter(|&i| line[i..].starts_with(": ") && msvc_location(&line[..i]).is_some())
+                .map(|i| (i, i + keyword.len() + 3)),
+        ];
+        for (start, end) in candidates.into_iter().flatten() {
+            if found.is_none_or(|(s, _, _)| start < s) {
+                found = Some((start, end, severity));
+            }
+        }
+    }
+    let (start, end, severity) = found?;
+    Some(Header {
+        location: &line[..start],
+        severity,
+        message: &line[end..],
+    })
+}
+
+fn msvc_location(location: &str) -> Option<(&str, u32, Option<u32>)> {
+    let inner = location.strip_suffix(')')?;
+    let open = inner.rfind('(')?;
+    let mut numbers = inner[open + 1..].split(',');
+    let line = numbers.next()?.trim().parse().ok()?;
+    let column = match numbers.next() {
+        Some(column) => Some(column.trim().parse().ok()?),
+        None => None,
+    };
+    Some((&inner[..open], line, column))
+}
+
+fn parse_location(location: &str) -> (Option<String>, Option<u32>, Option<u32>) {
+    if location.is_empty() {
+        return (None, None, None);
+    }
+    if let Some((file, line, column)) = msvc_location(location) {
+        return (Some(file.to_string()), Some(line), column);
+    }
+    let mut parts = location.rsplitn(3, ':');
+    let last = parts.next().and_then(|s| s.parse::<u32>().ok());
+    let middle = parts.next();
+    match (
+        last,
+        middle.and_then(|s| s.parse::<u32>().ok()),
+        parts.next(),
+    ) {
+        (Some(column), Some(line), Some(file)) => {
+            (Some(file.to_string()), Some(line), Some(column))
+        }
+        (Some(line), _, _) => {
+            let file = location.rsplit_once(':').unwrap().0;
+            (Some(file.to_string()), Some(line), None)
+        }
+        _ => (Some(location.to_string()), None, None),
+    }
+}
+
+fn parse_include(line: &str) -> Option<IncludeLocation> {
+    let rest = line
+        .strip_prefix("In file included from ")
+        .or_else(|| line.trim_start().strip_prefix("from "))?;
+    let rest = rest.strip_suffix(':').unwrap_or(rest);
+    let (file, line) = rest.rsplit_once(':')?;
+    Some(IncludeLocation {
+        file: file.to_string(),
+        line: line.parse().ok()?,
+    })
+}
+
+fn is_summary(line: &str) -> bool {
+    line.ends_with(" generated.") && (line.contains("error") || line.contains("warning"))
+}
+
+fn split_code(message: &str, msvc: bool) -> (String, Option<String>) {
+    if let Some(rest) = message.strip_suffix(']')
+        && let Some(open) = rest.rfind(" [")
+        && !rest[open + 2..].contains(' ')
+    {
+        return (rest[..open].to_string(), Some(rest[open + 2..].to_string()));
+    }
+    if msvc
+        && let Some((code, rest)) = message.split_once(": ")
+        && !code.is_empty()
+        && code.chars().all(|c| c.is_ascii_alphanumeric())
+    {
+        return (rest.to_string(), Some(code.to_string()));
+    }
+    (message.to_string(), None)
+}
+
+pub fn parse(text: &str) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = vec![];
+    let mut include_stack = vec![];
+    let mut excerpt: Option<Vec<&str>> = None;
+    let flush = |diagnostics: &mut Vec<Diagnostic>, excerpt: &mut Option<Vec<&str>>| {
+        let Some(mut lines) = excerpt.take() else {
+            return;
+        };
+        while lines.last().is_some_and(|line| line.trim().is_empty()) {
+            lines.pop();
+        }
+        let Some(last) = diagnostics.last_mut() else {
+            return;
+        };
+        if lines.is_empty() {
+            return;
+        }
+        let target = match last.notes.last_mut() {
+            Some(note) => note,
+            None => last,
+        };
+        target.source_excerpt = Some(lines.join("\n"));
+    };
+    for line in text.lines() {
+        let line = line.trim_end_matches('\r');
+        if let Some(include) = parse_include(line) {
+            flush(&mut diagnostics, &mut excerpt);
+            include_stack.push(include);
+            continue;
+        }
+        if is_summary(line) {
+            flush(&mut diagnostics, &mut excerpt);
+            continue;
+        }
+        let Some(header) = parse_header(line) else {
+            if let Some(lines) = excerpt.as_mut() {
+                lines.push(line);
+            }
+            continue;
+        };
+        flush(&mut diagnostics, &mut excerpt);
+        let (file, line, column) = parse_location(header.location);
+        let msvc = msvc_location(header.location).is_some();
+        let (message, code) = split_code(header.message, msvc);
+        let diagnostic = Diagnostic {
+            file,
+            line,
+            column,
+            severity: header.severity,
+            code,
+            message,
+            notes: vec![],
+            source_excerpt: None,
+            include_stack: std::mem::take(&mut include_stack),
+        };
+        match diagnostics.last_mut() {
+            Some(last) if diagnostic.severity == Severity::Note => last.notes.push(diagnostic),
+            _ => diagnostics.push(diagnostic),
+        }
+        excerpt = Some(vec![]);
+    }
+    flush(&mut diagnostics, &mut excerpt);
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DXC_OUTPUT: &str = "\
+In file included from shader.hlsl:3:
+In file included from ./inc/lighting.hlsli:1:
+./inc/common.hlsli:5:12: error: use of undeclared identifier 'foo'
+    return foo;
+           ^
+shader.hlsl:10:16: warning: implicit truncation of vector type [-Wconversion]
+    float2 x = float4(1, 2, 3, 4);
+               ^
+shader.hlsl:2:6: note: previous definition is here
+void f();
+     ^
+C:\\shaders\\ps.hlsl:7:1: fatal error: 'missing.hlsli' file not found
+#include \"missing.hlsli\"
+^~~~~~~~~~~~~~~~~~~~~~~~
+error: validation errors
+1 warning and 2 errors generated.
+";
+
+    #[test]
+    fn parse_diagnostics_test() {
+        let diagnostics = parse(DXC_OUTPUT);
+        assert_eq!(diagnostics.len(), 4);
+
+        let d = &diagnostics[0];
+        assert_eq!(d.file.as_deref(), Some("./inc/common.hlsli"));
+        assert_eq!((d.line, d.column), (Some(5), Some(12)));
+        assert_eq!(d.severity, Severity::Error);
+        assert_eq!(d.message, "use of undeclared identifier 'foo'");
+        assert_eq!(
+            d.source_excerpt.as_deref(),
+            Some("    return foo;\n           ^")
+        );
+        assert_eq!(
+            d.include_stack,
+            [
+                IncludeLocation {
+                    file: "shader.hlsl".into(),
+                    line: 3
+                },
+                IncludeLocation {
+                    file: "./inc/lighting.hlsli".into(),
+                    line: 1
+                }
+            ]
+        );
+
+        let d = &diagnostics[1];
+        assert_eq!(d.severity, Severity::Warning);
+        assert_eq!(d.code.as_deref(), Some("-Wconversion"));
+        assert_eq!(d.message, "implicit truncation of vector type");
+        assert!(d.include_stack.is_empty());
+        assert_eq!(d.notes.len(), 1);
+        assert_eq!(d.notes[0].message, "previous definition is here");
+        assert_eq!(
+            d.notes[0].source_excerpt.as_deref(),
+            Some("void f();\n     ^")
+        );
+
+        let d = &diagnostics[2];
+        assert_eq!(d.file.as_deref(), Some("C:\\shaders\\ps.hlsl"));
+        assert_eq!((d.line, d.column), (Some(7), Some(1)));
+        assert_eq!(d.severity, Severity::Fatal);
+
+        let d = &diagnostics[3];
+        assert_eq!(d.file, None);
+        assert_eq!(d.message, "validation errors");
+        assert_eq!(d.source_excerpt, None);
+
+        let msvc = parse("shader.hlsl(4,9): error X3004: undeclared identifier 'a'");
+        assert_eq!(msvc[0].file.as_deref(), Some("shader.hlsl"));
+        assert_eq!((msvc[0].line, msvc[0].column), (Some(4), Some(9)));
+        assert_eq!(msvc[0].code.as_deref(), Some("X3004"));
+        assert_eq!(msvc[0].message, "undeclared identifier 'a'");
+
+        assert_eq!(
+            diagnostics[1].to_string(),
+            "shader.hlsl:10:16: warning: implicit truncation of vector type [-Wconversion]\n    float2 x = float4(1, 2, 3, 4);\n               ^\nshader.hlsl:2:6: note: previous definition is here\nvoid f();\n     ^"
+        );
+    }
+}
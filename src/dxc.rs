@@ -1,6 +1,8 @@
 use super::*;
-use std::path::Path;
-use windows::Win32::Foundation::{E_ABORT, E_POINTER};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use windows::Win32::Foundation::{E_ABORT, E_INVALIDARG, E_POINTER, ERROR_FILE_NOT_FOUND};
 use windows::core::{HSTRING, Interface, PCWSTR};
 
 pub use windows::Win32::Graphics::Direct3D::Dxc::*;
@@ -15,12 +17,131 @@ impl Utils {
         unsafe { Ok(Self(DxcCreateInstance(&CLSID_DxcUtils)?)) }
     }
 
+    #[inline]
+    pub fn from_library(library: &Library) -> windows::core::Result<Self> {
+        Ok(Self(library.create_instance(&CLSID_DxcUtils)?))
+    }
+
     #[inline]
     pub fn handle(&self) -> &IDxcUtils {
         &self.0
     }
 }
 
+mod loader {
+    use windows::Win32::System::LibraryLoader::{GetProcAddress, LoadLibraryW};
+    use windows::core::{HSTRING, PCSTR};
+
+    pub const DXCOMPILER: &str = "dxcompiler.dll";
+    pub const DXIL: &str = "dxil.dll";
+
+    pub fn open(path: &std::path::Path) -> windows::core::Result<*mut std::ffi::c_void> {
+        unsafe { Ok(LoadLibraryW(&HSTRING::from(path))?.0) }
+    }
+
+    pub fn symbol(
+        module: *mut std::ffi::c_void,
+        name: &std::ffi::CStr,
+    ) -> windows::core::Result<*const std::ffi::c_void> {
+        unsafe {
+            GetProcAddress(
+                windows::Win32::Foundation::HMODULE(module),
+                PCSTR(name.as_ptr() as *const u8),
+            )
+            .map(|f| f as *const std::ffi::c_void)
+            .ok_or_else(windows::core::Error::from_thread)
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Library {
+    path: PathBuf,
+    dxil: Option<PathBuf>,
+    create_instance: DxcCreateInstanceProc,
+}
+
+impl Library {
+    pub const PATH_VAR: &str = "DXWR_DXCOMPILER_PATH";
+    pub const DXIL_PATH_VAR: &str = "DXWR_DXIL_PATH";
+
+    pub fn load(path: impl AsRef<Path>) -> windows::core::Result<Self> {
+        let path = path.as_ref();
+        let path = if path.is_dir() {
+            path.join(loader::DXCOMPILER)
+        } else {
+            path.to_path_buf()
+        };
+        let dxil = path.parent().map(|dir| dir.join(loader::DXIL));
+        Self::load_with_dxil(path, dxil.filter(|dxil| dxil.is_file()))
+    }
+
+    pub fn load_with_dxil(
+        path: impl AsRef<Path>,
+        dxil: Option<impl AsRef<Path>>,
+    ) -> windows::core::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let dxil = dxil.map(|dxil| dxil.as_ref().to_path_buf());
+        if let Some(dxil) = &dxil {
+            loader::open(dxil).map_err(|e| with_path(e, dxil))?;
+        }
+        let module = loader::open(&path).map_err(|e| with_path(e, &path))?;
+        let f = loader::symbol(module, c"DxcCreateInstance").map_err(|e| with_path(e, &path))?;
+        Ok(Self {
+            path,
+            dxil,
+            create_instance: unsafe {
+                std::mem::transmute::<*const std::ffi::c_void, DxcCreateInstanceProc>(f)
+            },
+        })
+    }
+
+    pub fn from_env() -> windows::core::Result<Self> {
+        let dxil = std::env::var_os(Self::DXIL_PATH_VAR).map(PathBuf::from);
+        match std::env::var_os(Self::PATH_VAR) {
+            Some(path) if dxil.is_some() => Self::load_with_dxil(path, dxil),
+            Some(path) => Self::load(path),
+            None => Self::load_with_dxil(loader::DXCOMPILER, dxil),
+        }
+    }
+
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    #[inline]
+    pub fn dxil_path(&self) -> Option<&Path> {
+        self.dxil.as_deref()
+    }
+
+    pub fn create_instance<T: Interface>(
+        &self,
+        clsid: &windows::core::GUID,
+    ) -> windows::core::Result<T> {
+        let mut p = std::ptr::null_mut();
+        unsafe {
+            let create_instance = self.create_instance.unwrap();
+            create_instance(clsid, &T::IID, &mut p).ok()?;
+            Ok(T::from_raw(p))
+        }
+    }
+
+    #[inline]
+    pub fn utils(&self) -> windows::core::Result<Utils> {
+        Utils::from_library(self)
+    }
+
+    #[inline]
+    pub fn compiler(&self, utils: &Utils) -> windows::core::Result<Compiler> {
+        Compiler::from_library(self, utils)
+    }
+}
+
+fn with_path(e: windows::core::Error, path: &Path) -> windows::core::Error {
+    windows::core::Error::new(e.code(), format!("{}: {}", path.display(), e.message()))
+}
+
 pub trait BlobType: Sized {
     fn handle(&self) -> IDxcBlob;
 
@@ -100,8 +221,262 @@ impl std::hash::Hash for ShaderHash {
     }
 }
 
-#[repr(transparent)]
-pub struct CompileResult(IDxcResult);
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct ContentHash(pub u128);
+
+impl ContentHash {
+    #[inline]
+    pub fn new(data: &[u8]) -> Self {
+        let mut hasher = ContentHasher::new();
+        hasher.update(data);
+        hasher.finish()
+    }
+
+    #[inline]
+    pub fn to_bytes(&self) -> [u8; 16] {
+        self.0.to_le_bytes()
+    }
+}
+
+impl std::fmt::Debug for ContentHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ContentHash({:032x})", self.0)
+    }
+}
+
+impl std::fmt::Display for ContentHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:032x}", self.0)
+    }
+}
+
+// 128-bit FNV-1a
+#[derive(Clone, Debug)]
+pub struct ContentHasher(u128);
+
+impl ContentHasher {
+    const OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+    const PRIME: u128 = 0x0000000001000000000000000000013b;
+
+    #[inline]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    #[inline]
+    pub fn update(&mut self, data: &[u8]) {
+        for &b in data {
+            self.0 ^= b as u128;
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    #[inline]
+    pub fn update_str(&mut self, s: &str) {
+        self.update(&(s.len() as u64).to_le_bytes());
+        self.update(s.as_bytes());
+    }
+
+    #[inline]
+    pub fn finish(&self) -> ContentHash {
+        ContentHash(self.0)
+    }
+}
+
+pub trait IncludeHandler {
+    fn load(&self, path: &Path) -> Option<Vec<u8>>;
+}
+
+impl<T> IncludeHandler for Arc<T>
+where
+    T: IncludeHandler + ?Sized,
+{
+    fn load(&self, path: &Path) -> Option<Vec<u8>> {
+        self.as_ref().load(path)
+    }
+}
+
+fn normalize_include_path(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut components: Vec<&str> = vec![];
+    for c in path.split('/') {
+        match c {
+            "" | "." => {}
+            ".." if components.last().is_some_and(|&last| last != "..") => {
+                components.pop();
+            }
+            _ => components.push(c),
+        }
+    }
+    let joined = components.join("/");
+    if path.starts_with('/') {
+        format!("/{joined}")
+    } else {
+        joined
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct FileSystemIncludeHandler {
+    root: Option<PathBuf>,
+}
+
+impl FileSystemIncludeHandler {
+    #[inline]
+    pub fn new() -> Self {
+        Self { root: None }
+    }
+
+    #[inline]
+    pub fn root(mut self, path: impl AsRef<Path>) -> Self {
+        self.root = Some(path.as_ref().to_path_buf());
+        self
+    }
+}
+
+impl IncludeHandler for FileSystemIncludeHandler {
+    fn load(&self, path: &Path) -> Option<Vec<u8>> {
+        match &self.root {
+            Some(root) if path.is_relative() => std::fs::read(root.join(path)).ok(),
+            _ => std::fs::read(path).ok(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MemoryIncludeHandler {
+    files: HashMap<String, Vec<u8>>,
+}
+
+impl MemoryIncludeHandler {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            files: HashMap::new(),
+        }
+    }
+
+    #[inline]
+    pub fn file(mut self, path: impl AsRef<Path>, data: impl Into<Vec<u8>>) -> Self {
+        self.insert(path, data);
+        self
+    }
+
+    #[inline]
+    pub fn insert(&mut self, path: impl AsRef<Path>, data: impl Into<Vec<u8>>) {
+        self.files
+            .insert(normalize_include_path(path.as_ref()), data.into());
+    }
+
+    #[inline]
+    pub fn remove(&mut self, path: impl AsRef<Path>) -> Option<Vec<u8>> {
+        self.files.remove(&normalize_include_path(path.as_ref()))
+    }
+}
+
+impl IncludeHandler for MemoryIncludeHandler {
+    fn load(&self, path: &Path) -> Option<Vec<u8>> {
+        self.files.get(&normalize_include_path(path)).cloned()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EmbeddedIncludeHandler {
+    files: &'static [(&'static str, &'static [u8])],
+}
+
+impl EmbeddedIncludeHandler {
+    #[inline]
+    pub const fn new(files: &'static [(&'static str, &'static [u8])]) -> Self {
+        Self { files }
+    }
+}
+
+impl IncludeHandler for EmbeddedIncludeHandler {
+    fn load(&self, path: &Path) -> Option<Vec<u8>> {
+        let path = normalize_include_path(path);
+        self.files
+            .iter()
+            .find(|(name, _)| normalize_include_path(Path::new(name)) == path)
+            .map(|(_, data)| data.to_vec())
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct IncludedFile {
+    pub path: PathBuf,
+    pub hash: ContentHash,
+}
+
+#[windows_core::implement(IDxcIncludeHandler)]
+struct IncludeHandlerObject {
+    utils: IDxcUtils,
+    handler: Box<dyn IncludeHandler>,
+    includes: Arc<Mutex<Vec<IncludedFile>>>,
+}
+
+impl IDxcIncludeHandler_Impl for IncludeHandlerObject_Impl {
+    fn LoadSource(&self, filename: &PCWSTR) -> windows::core::Result<IDxcBlob> {
+        let path = PathBuf::from(unsafe { filename.to_string()? });
+        let Some(data) = self.handler.load(&path) else {
+            return Err(ERROR_FILE_NOT_FOUND.to_hresult().into());
+        };
+        let blob = unsafe {
+            self.utils.CreateBlob(
+                data.as_ptr() as *const std::ffi::c_void,
+                data.len() as u32,
+                DXC_CP_UTF8,
+            )?
+        };
+        let mut includes = self.includes.lock().unwrap();
+        if !includes.iter().any(|include| include.path == path) {
+            includes.push(IncludedFile {
+                hash: ContentHash::new(&data),
+                path,
+            });
+        }
+        blob.cast()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ShaderIr {
+    Dxil,
+    Spirv,
+}
+
+impl ShaderIr {
+    pub const SPIRV_MAGIC: u32 = 0x07230203;
+
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        if data.starts_with(b"DXBC") {
+            Some(Self::Dxil)
+        } else if data.len() >= 4
+            && data.len().is_multiple_of(4)
+            && data[..4] == Self::SPIRV_MAGIC.to_le_bytes()
+        {
+            Some(Self::Spirv)
+        } else {
+            None
+        }
+    }
+}
+
+impl std::fmt::Display for ShaderIr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Dxil => f.write_str("DXIL"),
+            Self::Spirv => f.write_str("SPIR-V"),
+        }
+    }
+}
+
+pub struct CompileResult {
+    result: IDxcResult,
+    includes: Vec<IncludedFile>,
+    ir: ShaderIr,
+}
 
 impl CompileResult {
     fn get_output<T>(&self, kind: DXC_OUT_KIND) -> windows::core::Result<T>
@@ -113,7 +488,7 @@ impl CompileResult {
         }
         let output = unsafe {
             let mut p: Option<T> = None;
-            self.0
+            self.result
                 .GetOutput(kind, std::ptr::null_mut(), &mut p)
                 .map(|_| p.unwrap())?
         };
@@ -147,9 +522,21 @@ impl CompileResult {
         }
     }
 
+    #[inline]
+    pub fn status(&self) -> windows::core::Result<()> {
+        let status = unsafe { self.result.GetStatus()? };
+        if status.is_err() {
+            return Err(windows::core::Error::new(
+                status,
+                self.errors().unwrap_or_default(),
+            ));
+        }
+        Ok(())
+    }
+
     #[inline]
     pub fn has_output(&self, kind: DXC_OUT_KIND) -> bool {
-        unsafe { self.0.HasOutput(kind).as_bool() }
+        unsafe { self.result.HasOutput(kind).as_bool() }
     }
 
     #[inline]
@@ -157,11 +544,48 @@ impl CompileResult {
         self.get_blob(DXC_OUT_OBJECT)
     }
 
+    #[inline]
+    pub fn ir(&self) -> ShaderIr {
+        self.ir
+    }
+
+    fn object_as(&self, ir: ShaderIr) -> windows::core::Result<RefBlob<'_>> {
+        if self.ir != ir {
+            return Err(windows::core::Error::new(
+                E_INVALIDARG,
+                format!("compile result is {}, not {}", self.ir, ir),
+            ));
+        }
+        self.object()
+    }
+
+    #[inline]
+    pub fn dxil(&self) -> windows::core::Result<RefBlob<'_>> {
+        self.object_as(ShaderIr::Dxil)
+    }
+
+    #[inline]
+    pub fn spirv(&self) -> windows::core::Result<RefBlob<'_>> {
+        self.object_as(ShaderIr::Spirv)
+    }
+
+    #[inline]
+    pub fn bytecode(&self) -> windows::core::Result<ShaderBytecode<'_>> {
+        let blob = self.dxil()?;
+        let data = unsafe { std::slice::from_raw_parts(blob.as_ptr() as *const u8, blob.len()) };
+        Ok(ShaderBytecode::new(data))
+    }
+
     #[inline]
     pub fn errors(&self) -> windows::core::Result<String> {
         self.get_string(DXC_OUT_ERRORS)
     }
 
+    #[inline]
+    pub fn diagnostics(&self) -> windows::core::Result<Vec<diagnostics::Diagnostic>> {
+        self.errors().map(|errors| diagnostics::parse(&errors))
+    }
+
     #[inline]
     pub fn pdb(&self) -> windows::core::Result<RefBlob<'_>> {
         self.get_blob(DXC_OUT_PDB)
@@ -198,13 +622,18 @@ impl CompileResult {
         self.get_blob(DXC_OUT_ROOT_SIGNATURE)
     }
 
+    #[inline]
+    pub fn includes(&self) -> &[IncludedFile] {
+        &self.includes
+    }
+
     #[inline]
     pub fn result(&self) -> &IDxcResult {
-        &self.0
+        &self.result
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Arguments {
     source_name: Option<HSTRING>,
     target: Option<HSTRING>,
@@ -222,6 +651,11 @@ pub struct Arguments {
     embed_debug: bool,
     warnings_as_errors: bool,
     hlsl_version: HSTRING,
+    rootsig_define: Option<HSTRING>,
+    spirv: bool,
+    spirv_target_env: Option<SpirvTargetEnv>,
+    vk_binding_shifts: Vec<VkBindingShift>,
+    spirv_reflect: bool,
     extra: Vec<HSTRING>,
 }
 
@@ -246,6 +680,11 @@ impl Arguments {
             embed_debug: false,
             warnings_as_errors: false,
             hlsl_version: "2021".into(),
+            rootsig_define: None,
+            spirv: false,
+            spirv_target_env: None,
+            vk_binding_shifts: vec![],
+            spirv_reflect: false,
             extra: vec![],
         }
     }
@@ -347,6 +786,60 @@ impl Arguments {
         self
     }
 
+    #[inline]
+    pub fn rootsig_define(mut self, name: impl AsRef<str>) -> Self {
+        self.rootsig_define = Some(name.as_ref().into());
+        self
+    }
+
+    #[inline]
+    pub fn spirv(mut self, flag: bool) -> Self {
+        self.spirv = flag;
+        self
+    }
+
+    #[inline]
+    pub fn spirv_target_env(mut self, env: SpirvTargetEnv) -> Self {
+        self.spirv_target_env = Some(env);
+        self
+    }
+
+    #[inline]
+    pub fn vk_binding_shift(
+        mut self,
+        register: VkRegisterType,
+        shift: u32,
+        space: Option<u32>,
+    ) -> Self {
+        self.vk_binding_shifts.push(VkBindingShift {
+            register,
+            shift,
+            space,
+        });
+        self
+    }
+
+    #[inline]
+    pub fn spirv_reflect(mut self, flag: bool) -> Self {
+        self.spirv_reflect = flag;
+        self
+    }
+
+    #[inline]
+    pub(crate) fn is_no_legacy_cbuf_layout(&self) -> bool {
+        self.no_legacy_cbuf_layout
+    }
+
+    #[inline]
+    pub fn ir(&self) -> ShaderIr {
+        let spirv = |arg: &String| arg == "-spirv" || (cfg!(windows) && arg == "/spirv");
+        if self.to_args().iter().any(spirv) {
+            ShaderIr::Spirv
+        } else {
+            ShaderIr::Dxil
+        }
+    }
+
     #[inline]
     pub fn extra<T>(mut self, args: &[T]) -> Self
     where
@@ -359,26 +852,124 @@ impl Arguments {
         self
     }
 
-    fn build(&self) -> (Vec<HSTRING>, Vec<PCWSTR>) {
+    pub fn parse<T>(args: &[T]) -> Result<Self, ArgumentsParseError>
+    where
+        T: AsRef<str>,
+    {
+        let mut ret = Self::new();
+        let mut iter = args.iter().map(|arg| arg.as_ref());
+        while let Some(arg) = iter.next() {
+            let Some(option) = normalize_option(arg) else {
+                ret.source_name = Some(arg.into());
+                continue;
+            };
+            let mut value = |name: &str| -> Result<Option<String>, ArgumentsParseError> {
+                if option == name {
+                    iter.next()
+                        .map(|v| Some(v.to_string()))
+                        .ok_or_else(|| ArgumentsParseError::MissingValue(name.to_string()))
+                } else {
+                    Ok(option.strip_prefix(name).map(|v| v.to_string()))
+                }
+            };
+            if let Some(v) = value("-T")? {
+                ret.target = Some(v.into());
+            } else if let Some(v) = value("-E")? {
+                ret.entry_point = Some(v.into());
+            } else if let Some(v) = value("-I")? {
+                ret.include_dirs.push(v.into());
+            } else if let Some(v) = value("-D")? {
+                ret.defines.push(v.into());
+            } else if option == "-rootsig-define" {
+                ret.rootsig_define = value("-rootsig-define")?.map(HSTRING::from);
+            } else if let Some(env) = option.strip_prefix("-fspv-target-env=") {
+                ret.spirv_target_env =
+                    Some(env.parse().map_err(|_| ArgumentsParseError::InvalidValue {
+                        option: "-fspv-target-env".to_string(),
+                        value: env.to_string(),
+                    })?);
+            } else if let Some(register) = VkRegisterType::from_option(&option) {
+                let mut next = || {
+                    iter.next()
+                        .ok_or_else(|| ArgumentsParseError::MissingValue(option.clone()))
+                };
+                let (shift, space) = (next()?, next()?);
+                let invalid = |value: &str| ArgumentsParseError::InvalidValue {
+                    option: option.clone(),
+                    value: value.to_string(),
+                };
+                ret.vk_binding_shifts.push(VkBindingShift {
+                    register,
+                    shift: shift.parse().map_err(|_| invalid(shift))?,
+                    space: match space {
+                        "all" => None,
+                        _ => Some(space.parse().map_err(|_| invalid(space))?),
+                    },
+                });
+            } else if option == "-HV" || (option.starts_with("-HV") && option.len() > 3) {
+                ret.hlsl_version = value("-HV")?.unwrap().into();
+            } else if let Some(level) = option
+                .strip_prefix("-O")
+                .filter(|level| matches!(*level, "d" | "0" | "1" | "2" | "3"))
+            {
+                ret.optimization = level.parse().ok();
+            } else {
+                match option.as_str() {
+                    "-Ges" => ret.strict_mode = true,
+                    "-no-legacy-cbuf-layout" => ret.no_legacy_cbuf_layout = true,
+                    "-no-warnings" => ret.no_warnings = true,
+                    "-Vd" => ret.validation = false,
+                    "-Zi" => ret.debug_info = true,
+                    "-Zsb" => ret.hash_considering_only_binary = true,
+                    "-Zss" => ret.hash_considering_source_info = true,
+                    "-Qembed_debug" => ret.embed_debug = true,
+                    "-WX" => ret.warnings_as_errors = true,
+                    "-spirv" => ret.spirv = true,
+                    "-fspv-reflect" => ret.spirv_reflect = true,
+                    _ => {
+                        ret.extra.push(option.as_str().into());
+                        let arity = OPTIONS_WITH_VALUES
+                            .iter()
+                            .find(|(name, _)| *name == option)
+                            .map_or(0, |(_, arity)| *arity);
+                        for _ in 0..arity {
+                            let v = iter
+                                .next()
+                                .ok_or_else(|| ArgumentsParseError::MissingValue(option.clone()))?;
+                            ret.extra.push(v.into());
+                        }
+                    }
+                }
+            }
+        }
+        Ok(ret)
+    }
+
+    #[inline]
+    pub fn parse_command_line(line: &str) -> Result<Self, ArgumentsParseError> {
+        Self::parse(&split_command_line(line))
+    }
+
+    pub fn to_args(&self) -> Vec<String> {
         let mut args = vec![];
         self.source_name
             .as_ref()
-            .inspect(|&source_name| args.push(source_name.clone()));
+            .inspect(|&source_name| args.push(source_name.to_string()));
         self.target.as_ref().inspect(|&target| {
             args.push("-T".into());
-            args.push(target.clone());
+            args.push(target.to_string());
         });
         self.entry_point.as_ref().inspect(|&entry_point| {
             args.push("-E".into());
-            args.push(entry_point.clone())
+            args.push(entry_point.to_string())
         });
         self.include_dirs.iter().for_each(|dir| {
             args.push("-I".into());
-            args.push(dir.clone());
+            args.push(dir.to_string());
         });
         self.defines.iter().for_each(|d| {
             args.push("-D".into());
-            args.push(d.clone());
+            args.push(d.to_string());
         });
         args.push(
             match self.optimization {
@@ -404,13 +995,274 @@ impl Arguments {
         self.embed_debug.then(|| args.push("-Qembed_debug".into()));
         self.warnings_as_errors.then(|| args.push("-WX".into()));
         args.push("-HV".into());
-        args.push(self.hlsl_version.clone());
-        self.extra.iter().for_each(|arg| args.push(arg.clone()));
+        args.push(self.hlsl_version.to_string());
+        self.rootsig_define.as_ref().inspect(|&name| {
+            args.push("-rootsig-define".into());
+            args.push(name.to_string());
+        });
+        self.spirv.then(|| args.push("-spirv".into()));
+        self.spirv_target_env
+            .inspect(|env| args.push(format!("-fspv-target-env={env}")));
+        self.vk_binding_shifts.iter().for_each(|shift| {
+            args.push(shift.register.option().into());
+            args.push(shift.shift.to_string());
+            args.push(
+                shift
+                    .space
+                    .map_or_else(|| "all".to_string(), |space| space.to_string()),
+            );
+        });
+        self.spirv_reflect
+            .then(|| args.push("-fspv-reflect".into()));
+        self.extra.iter().for_each(|arg| args.push(arg.to_string()));
+        args
+    }
+
+    #[inline]
+    pub fn to_command_line(&self) -> String {
+        join_command_line(&self.to_args())
+    }
+
+    pub(crate) fn build(&self) -> (Vec<HSTRING>, Vec<PCWSTR>) {
+        let args = self.to_args().iter().map(HSTRING::from).collect::<Vec<_>>();
         let ptrs = args.iter().map(|arg| PCWSTR(arg.as_ptr())).collect();
         (args, ptrs)
     }
 }
 
+const OPTIONS_WITH_VALUES: &[(&str, usize)] = &[
+    ("-Fo", 1),
+    ("-Fe", 1),
+    ("-Fd", 1),
+    ("-Fc", 1),
+    ("-Fh", 1),
+    ("-Fi", 1),
+    ("-Fre", 1),
+    ("-Frs", 1),
+    ("-Fsh", 1),
+    ("-MF", 1),
+    ("-Vn", 1),
+    ("-exports", 1),
+    ("-auto-binding-space", 1),
+    ("-setrootsignature", 1),
+    ("-verifyrootsignature", 1),
+    ("-force-rootsig-ver", 1),
+    ("-select-validator", 1),
+    ("-validator-version", 1),
+    ("-default-linkage", 1),
+    ("-encoding", 1),
+    ("-opt-enable", 1),
+    ("-opt-disable", 1),
+    ("-opt-select", 2),
+    ("-fvk-bind-globals", 2),
+    ("-fvk-bind-register", 4),
+];
+
+const KNOWN_OPTIONS: &[&str] = &[
+    "-T",
+    "-E",
+    "-I",
+    "-D",
+    "-HV",
+    "-rootsig-define",
+    "-Od",
+    "-O0",
+    "-O1",
+    "-O2",
+    "-O3",
+    "-Ges",
+    "-no-legacy-cbuf-layout",
+    "-no-warnings",
+    "-Vd",
+    "-Zi",
+    "-Zsb",
+    "-Zss",
+    "-Qembed_debug",
+    "-WX",
+    "-spirv",
+    "-fspv-reflect",
+    "-fvk-b-shift",
+    "-fvk-t-shift",
+    "-fvk-s-shift",
+    "-fvk-u-shift",
+];
+
+fn normalize_option(arg: &str) -> Option<String> {
+    if arg.starts_with('-') && arg.len() > 1 {
+        return Some(arg.to_string());
+    }
+    if !cfg!(windows) {
+        return None;
+    }
+    let option = format!("-{}", arg.strip_prefix('/')?);
+    let known = KNOWN_OPTIONS
+        .iter()
+        .copied()
+        .chain(OPTIONS_WITH_VALUES.iter().map(|(name, _)| *name))
+        .any(|known| option == known);
+    known.then_some(option)
+}
+
+fn split_command_line(line: &str) -> Vec<String> {
+    let mut args = vec![];
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'"') => {
+                current.push(chars.next().unwrap());
+                in_arg = true;
+            }
+            '"' => {
+                quoted = !quoted;
+                in_arg = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    args
+}
+
+fn join_command_line<T>(args: &[T]) -> String
+where
+    T: AsRef<str>,
+{
+    args.iter()
+        .map(|arg| {
+            let arg = arg.as_ref();
+            if !arg.is_empty() && !arg.contains(|c: char| c.is_whitespace() || c == '"') {
+                arg.to_string()
+            } else {
+                format!("\"{}\"", arg.replace('"', "\\\""))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SpirvTargetEnv {
+    Vulkan1_0,
+    Vulkan1_1,
+    Vulkan1_1Spirv1_4,
+    Vulkan1_2,
+    Vulkan1_3,
+    Universal1_5,
+}
+
+impl SpirvTargetEnv {
+    const NAMES: [(Self, &'static str); 6] = [
+        (Self::Vulkan1_0, "vulkan1.0"),
+        (Self::Vulkan1_1, "vulkan1.1"),
+        (Self::Vulkan1_1Spirv1_4, "vulkan1.1spirv1.4"),
+        (Self::Vulkan1_2, "vulkan1.2"),
+        (Self::Vulkan1_3, "vulkan1.3"),
+        (Self::Universal1_5, "universal1.5"),
+    ];
+}
+
+impl std::fmt::Display for SpirvTargetEnv {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (_, name) = Self::NAMES.iter().find(|(env, _)| env == self).unwrap();
+        f.write_str(name)
+    }
+}
+
+impl std::str::FromStr for SpirvTargetEnv {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::NAMES
+            .iter()
+            .find(|(_, name)| *name == s)
+            .map(|(env, _)| *env)
+            .ok_or(())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum VkRegisterType {
+    B,
+    T,
+    S,
+    U,
+}
+
+impl VkRegisterType {
+    fn option(&self) -> &'static str {
+        match self {
+            Self::B => "-fvk-b-shift",
+            Self::T => "-fvk-t-shift",
+            Self::S => "-fvk-s-shift",
+            Self::U => "-fvk-u-shift",
+        }
+    }
+
+    fn from_option(option: &str) -> Option<Self> {
+        [Self::B, Self::T, Self::S, Self::U]
+            .into_iter()
+            .find(|register| register.option() == option)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct VkBindingShift {
+    pub register: VkRegisterType,
+    pub shift: u32,
+    pub space: Option<u32>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ArgumentsParseError {
+    MissingValue(String),
+    InvalidValue { option: String, value: String },
+}
+
+impl std::fmt::Display for ArgumentsParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingValue(option) => write!(f, "missing value for `{option}`"),
+            Self::InvalidValue { option, value } => {
+                write!(f, "invalid value `{value}` for `{option}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArgumentsParseError {}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct CompilerVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub commit_count: u32,
+    pub commit_hash: String,
+}
+
+impl std::fmt::Display for CompilerVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}({})",
+            self.major, self.minor, self.commit_count, self.commit_hash
+        )
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Compiler {
     utils: Utils,
@@ -428,25 +1280,183 @@ impl Compiler {
         }
     }
 
+    #[inline]
+    pub fn from_library(library: &Library, utils: &Utils) -> windows::core::Result<Self> {
+        Ok(Self {
+            utils: utils.clone(),
+            compiler: library.create_instance(&CLSID_DxcCompiler)?,
+        })
+    }
+
+    pub fn version(&self) -> windows::core::Result<CompilerVersion> {
+        let info: IDxcVersionInfo = self.compiler.cast()?;
+        let mut version = CompilerVersion {
+            major: 0,
+            minor: 0,
+            commit_count: 0,
+            commit_hash: String::new(),
+        };
+        unsafe {
+            info.GetVersion(&mut version.major, &mut version.minor)?;
+            if let Ok(info) = info.cast::<IDxcVersionInfo2>() {
+                let mut hash: *mut i8 = std::ptr::null_mut();
+                info.GetCommitInfo(&mut version.commit_count, &mut hash)?;
+                if !hash.is_null() {
+                    version.commit_hash =
+                        std::ffi::CStr::from_ptr(hash).to_string_lossy().to_string();
+                    windows::Win32::System::Com::CoTaskMemFree(Some(hash as *const _));
+                }
+            }
+        }
+        Ok(version)
+    }
+
     #[inline]
     pub fn compile(&self, src: &[u8], args: &Arguments) -> windows::core::Result<CompileResult> {
+        self.compile_with_include_handler(src, args, FileSystemIncludeHandler::new())
+    }
+
+    pub fn compile_with_include_handler(
+        &self,
+        src: &[u8],
+        args: &Arguments,
+        handler: impl IncludeHandler + 'static,
+    ) -> windows::core::Result<CompileResult> {
         let buffer = DxcBuffer {
             Ptr: src.as_ptr() as *const std::ffi::c_void,
             Size: src.len(),
             Encoding: DXC_CP_UTF8.0,
         };
+        let args_ir = args.ir();
         let (_a, args) = args.build();
+        let includes = Arc::new(Mutex::new(vec![]));
+        let include: IDxcIncludeHandler = IncludeHandlerObject {
+            utils: self.utils.0.clone(),
+            handler: Box::new(handler),
+            includes: includes.clone(),
+        }
+        .into();
         let result = unsafe {
-            let include = self.utils.0.CreateDefaultIncludeHandler()?;
             self.compiler
                 .Compile(&buffer, Some(&args), Some(&include))?
         };
-        Ok(CompileResult(result))
+        drop(include);
+        let includes = std::mem::take(&mut *includes.lock().unwrap());
+        let mut ret = CompileResult {
+            result,
+            includes,
+            ir: args_ir,
+        };
+        if let Some(ir) = ret
+            .object()
+            .ok()
+            .and_then(|object| ShaderIr::detect(object.as_slice()))
+        {
+            ret.ir = ir;
+        }
+        Ok(ret)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Linker {
+    utils: Utils,
+    linker: IDxcLinker,
+    libraries: Vec<String>,
+}
+
+impl Linker {
+    #[inline]
+    pub fn new(utils: &Utils) -> windows::core::Result<Self> {
+        unsafe {
+            Ok(Self {
+                utils: utils.clone(),
+                linker: DxcCreateInstance(&CLSID_DxcLinker)?,
+                libraries: vec![],
+            })
+        }
+    }
+
+    #[inline]
+    pub fn from_library(library: &Library, utils: &Utils) -> windows::core::Result<Self> {
+        Ok(Self {
+            utils: utils.clone(),
+            linker: library.create_instance(&CLSID_DxcLinker)?,
+            libraries: vec![],
+        })
+    }
+
+    pub fn register_library(
+        &mut self,
+        name: impl AsRef<str>,
+        data: &[u8],
+    ) -> windows::core::Result<()> {
+        let name = name.as_ref();
+        unsafe {
+            let blob = self.utils.0.CreateBlob(
+                data.as_ptr() as *const std::ffi::c_void,
+                data.len() as u32,
+                DXC_CP_ACP,
+            )?;
+            self.linker
+                .RegisterLibrary(&HSTRING::from(name), &blob.cast::<IDxcBlob>()?)?;
+        }
+        if !self.libraries.iter().any(|lib| lib == name) {
+            self.libraries.push(name.to_string());
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub fn libraries(&self) -> &[String] {
+        &self.libraries
+    }
+
+    pub fn link<T, U>(
+        &self,
+        entry_point: impl AsRef<str>,
+        target: impl AsRef<str>,
+        libraries: &[T],
+        args: &[U],
+    ) -> windows::core::Result<CompileResult>
+    where
+        T: AsRef<str>,
+        U: AsRef<str>,
+    {
+        let libraries = libraries
+            .iter()
+            .map(|lib| HSTRING::from(lib.as_ref()))
+            .collect::<Vec<_>>();
+        let library_ptrs = libraries
+            .iter()
+            .map(|lib| PCWSTR(lib.as_ptr()))
+            .collect::<Vec<_>>();
+        let args = args
+            .iter()
+            .map(|arg| HSTRING::from(arg.as_ref()))
+            .collect::<Vec<_>>();
+        let arg_ptrs = args
+            .iter()
+            .map(|arg| PCWSTR(arg.as_ptr()))
+            .collect::<Vec<_>>();
+        let result = unsafe {
+            self.linker.Link(
+                &HSTRING::from(entry_point.as_ref()),
+                &HSTRING::from(target.as_ref()),
+                &library_ptrs,
+                Some(&arg_ptrs),
+            )?
+        };
+        Ok(CompileResult {
+            result: result.cast()?,
+            includes: vec![],
+            ir: ShaderIr::Dxil,
+        })
     }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
-pub struct PartKind(u32);
+pub struct PartKind(pub(crate) u32);
 
 impl PartKind {
     #[inline]
@@ -534,6 +1544,16 @@ impl ContainerReflection {
         }
     }
 
+    #[inline]
+    pub fn from_library(library: &Library, blob: &impl BlobType) -> windows::core::Result<Self> {
+        let reflection: IDxcContainerReflection =
+            library.create_instance(&CLSID_DxcContainerReflection)?;
+        unsafe {
+            reflection.Load(&blob.handle())?;
+        }
+        Ok(Self(reflection))
+    }
+
     #[inline]
     pub fn parts(&self) -> impl Iterator<Item = Part> + '_ {
         unsafe {
@@ -553,3 +1573,174 @@ impl ContainerReflection {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn include_handler_test() {
+        assert_eq!(
+            normalize_include_path(Path::new("./a/../b\\c.hlsli")),
+            "b/c.hlsli"
+        );
+        assert_eq!(
+            normalize_include_path(Path::new("../c.hlsli")),
+            "../c.hlsli"
+        );
+        let memory = MemoryIncludeHandler::new().file("inc/common.hlsli", "float4 f;");
+        assert_eq!(
+            memory.load(Path::new("./inc\\common.hlsli")).unwrap(),
+            b"float4 f;"
+        );
+        assert!(memory.load(Path::new("common.hlsli")).is_none());
+        static FILES: &[(&str, &[u8])] = &[("common.hlsli", b"float4 g;")];
+        let embedded = EmbeddedIncludeHandler::new(FILES);
+        assert_eq!(
+            embedded.load(Path::new("./common.hlsli")).unwrap(),
+            b"float4 g;"
+        );
+        assert_eq!(ContentHash::new(b"").0, ContentHasher::OFFSET_BASIS);
+        assert_ne!(ContentHash::new(b"a"), ContentHash::new(b"b"));
+    }
+
+    #[test]
+    fn arguments_command_line_test() {
+        let line = r#"shader.hlsl -T ps_6_6 -Emain -D FOO=1 -DBAR -I inc -I "my dir" -Od -Zi -Qembed_debug -HV 2018 -Fo out.dxil -enable-16bit-types"#;
+        let args = Arguments::parse_command_line(line).unwrap();
+        assert_eq!(
+            args.to_args(),
+            [
+                "shader.hlsl",
+                "-T",
+                "ps_6_6",
+                "-E",
+                "main",
+                "-I",
+                "inc",
+                "-I",
+                "my dir",
+                "-D",
+                "FOO=1",
+                "-D",
+                "BAR",
+                "-Od",
+                "-Zi",
+                "-Qembed_debug",
+                "-HV",
+                "2018",
+                "-Fo",
+                "out.dxil",
+                "-enable-16bit-types",
+            ]
+        );
+        let rendered = args.to_command_line();
+        assert!(rendered.contains(r#"-I "my dir""#));
+        assert_eq!(
+            Arguments::parse_command_line(&rendered).unwrap().to_args(),
+            args.to_args()
+        );
+        assert_eq!(
+            Arguments::parse(&["-T"]).unwrap_err(),
+            ArgumentsParseError::MissingValue("-T".into())
+        );
+        let args = Arguments::parse(&[
+            "-Odump",
+            "-Oconfig=-mem2reg",
+            "-fvk-bind-register",
+            "t0",
+            "0",
+            "1",
+            "2",
+            "/Engine/a.hlsl",
+        ])
+        .unwrap();
+        assert_eq!(
+            args.to_args()[args.to_args().len() - 7..],
+            [
+                "-Odump",
+                "-Oconfig=-mem2reg",
+                "-fvk-bind-register",
+                "t0",
+                "0",
+                "1",
+                "2",
+            ]
+        );
+        assert_eq!(args.to_args()[0], "/Engine/a.hlsl");
+        let args = Arguments::parse(&["-rootsig-define", "RS", "a.hlsl"]).unwrap();
+        assert_eq!(
+            args.to_args(),
+            ["a.hlsl", "-O3", "-HV", "2021", "-rootsig-define", "RS"]
+        );
+        assert_eq!(
+            Arguments::new().rootsig_define("RS").to_args(),
+            ["-O3", "-HV", "2021", "-rootsig-define", "RS"]
+        );
+        assert_eq!(
+            Arguments::parse(&["-fvk-bind-register", "t0", "0"]).unwrap_err(),
+            ArgumentsParseError::MissingValue("-fvk-bind-register".into())
+        );
+        for path in ["/Dir", "/Include", "/Temp"] {
+            assert_eq!(Arguments::parse(&[path]).unwrap().to_args()[0], path);
+        }
+        let args = Arguments::parse(&["/T", "ps_6_6"]);
+        if cfg!(windows) {
+            assert_eq!(args.unwrap().to_args()[..2], ["-T", "ps_6_6"]);
+        } else {
+            assert_eq!(args.unwrap().to_args()[0], "ps_6_6");
+        }
+    }
+
+    #[test]
+    fn spirv_arguments_test() {
+        let args = Arguments::new()
+            .target("ps_6_6")
+            .spirv(true)
+            .spirv_target_env(SpirvTargetEnv::Vulkan1_2)
+            .vk_binding_shift(VkRegisterType::T, 1000, None)
+            .vk_binding_shift(VkRegisterType::U, 2000, Some(1))
+            .spirv_reflect(true);
+        assert_eq!(args.ir(), ShaderIr::Spirv);
+        let rendered = args.to_args();
+        assert_eq!(
+            rendered[rendered.len() - 9..],
+            [
+                "-spirv",
+                "-fspv-target-env=vulkan1.2",
+                "-fvk-t-shift",
+                "1000",
+                "all",
+                "-fvk-u-shift",
+                "2000",
+                "1",
+                "-fspv-reflect",
+            ]
+        );
+        assert_eq!(Arguments::parse(&rendered).unwrap().to_args(), rendered);
+        assert_eq!(Arguments::new().ir(), ShaderIr::Dxil);
+        assert_eq!(Arguments::new().extra(&["-spirv"]).ir(), ShaderIr::Spirv);
+        assert_eq!(
+            Arguments::parse(&["-Zi", "-spirv"]).unwrap().ir(),
+            ShaderIr::Spirv
+        );
+        assert_eq!(
+            Arguments::parse(&["-fspv-target-env=vulkan9"]).unwrap_err(),
+            ArgumentsParseError::InvalidValue {
+                option: "-fspv-target-env".into(),
+                value: "vulkan9".into()
+            }
+        );
+        assert_eq!(
+            Arguments::parse(&["-fvk-b-shift", "1"]).unwrap_err(),
+            ArgumentsParseError::MissingValue("-fvk-b-shift".into())
+        );
+
+        assert_eq!(
+            ShaderIr::detect(&[0x03, 0x02, 0x23, 0x07, 0, 0, 1, 0]),
+            Some(ShaderIr::Spirv)
+        );
+        assert_eq!(ShaderIr::detect(b"DXBC\0\0\0\0"), Some(ShaderIr::Dxil));
+        assert_eq!(ShaderIr::detect(b"abc"), None);
+    }
+}
@@ -0,0 +1,312 @@
+use crate::dxc::{Arguments, CompileResult, Compiler, ContentHash};
+use crate::fence::Signal;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+#[derive(Debug)]
+struct HotHandleInner<T> {
+    value: RwLock<Arc<T>>,
+    generation: AtomicU64,
+}
+
+#[derive(Debug)]
+pub struct HotHandle<T>(Arc<HotHandleInner<T>>);
+
+impl<T> HotHandle<T> {
+    #[inline]
+    pub fn new(value: T) -> Self {
+        Self(Arc::new(HotHandleInner {
+            value: RwLock::new(Arc::new(value)),
+            generation: AtomicU64::new(0),
+        }))
+    }
+
+    #[inline]
+    pub fn get(&self) -> Arc<T> {
+        self.0.value.read().unwrap().clone()
+    }
+
+    #[inline]
+    pub fn generation(&self) -> u64 {
+        self.0.generation.load(Ordering::Acquire)
+    }
+
+    fn swap(&self, value: T) -> Arc<T> {
+        let old = std::mem::replace(&mut *self.0.value.write().unwrap(), Arc::new(value));
+        self.0.generation.fetch_add(1, Ordering::AcqRel);
+        old
+    }
+}
+
+impl<T> Clone for HotHandle<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+#[derive(Debug)]
+pub struct ShaderSource {
+    path: PathBuf,
+    args: Arguments,
+}
+
+impl ShaderSource {
+    #[inline]
+    pub fn new(path: impl AsRef<Path>, args: Arguments) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            args,
+        }
+    }
+
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    #[inline]
+    pub fn args(&self) -> &Arguments {
+        &self.args
+    }
+}
+
+#[derive(Clone, Debug)]
+struct WatchedFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    hash: Option<ContentHash>,
+}
+
+impl WatchedFile {
+    fn new(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let modified = modified_time(&path);
+        let hash = std::fs::read(&path)
+            .ok()
+            .map(|data| ContentHash::new(&data));
+        Self {
+            path,
+            modified,
+            hash,
+        }
+    }
+
+    fn changed(&mut self) -> bool {
+        let modified = modified_time(&self.path);
+        if modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+        let hash = std::fs::read(&self.path)
+            .ok()
+            .map(|data| ContentHash::new(&data));
+        if hash == self.hash {
+            return false;
+        }
+        self.hash = hash;
+        true
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+struct WatchedShader {
+    source: ShaderSource,
+    files: Vec<WatchedFile>,
+}
+
+impl WatchedShader {
+    fn compile(&mut self, compiler: &Compiler) -> Result<CompileResult, String> {
+        let src = std::fs::read(&self.source.path)
+            .map_err(|e| format!("{}: {}", self.source.path.display(), e))?;
+        let result = compiler
+            .compile(&src, &self.source.args)
+            .map_err(|e| e.message())?;
+        result.status().map_err(|e| e.message())?;
+        self.files = std::iter::once(self.source.path.as_path())
+            .chain(
+                result
+                    .includes()
+                    .iter()
+                    .map(|include| include.path.as_path()),
+            )
+            .map(WatchedFile::new)
+            .collect();
+        Ok(result)
+    }
+
+    fn changed(&mut self) -> bool {
+        let mut changed = false;
+        for file in &mut self.files {
+            changed |= file.changed();
+        }
+        changed
+    }
+}
+
+type Rebuild = Box<dyn FnMut(&[CompileResult]) -> windows::core::Result<()>>;
+
+struct Program {
+    name: String,
+    shaders: Vec<WatchedShader>,
+    rebuild: Rebuild,
+    pending: bool,
+}
+
+#[derive(Debug)]
+pub enum ReloadEvent {
+    Reloaded {
+        name: String,
+    },
+    CompileFailed {
+        name: String,
+        path: PathBuf,
+        errors: String,
+    },
+    RebuildFailed {
+        name: String,
+        error: windows::core::Error,
+    },
+}
+
+pub struct HotReloader {
+    compiler: Compiler,
+    programs: Vec<Program>,
+}
+
+impl HotReloader {
+    #[inline]
+    pub fn new(compiler: &Compiler) -> Self {
+        Self {
+            compiler: compiler.clone(),
+            programs: vec![],
+        }
+    }
+
+    pub fn register<T, F>(
+        &mut self,
+        name: impl AsRef<str>,
+        sources: Vec<ShaderSource>,
+        mut f: F,
+    ) -> windows::core::Result<HotHandle<T>>
+    where
+        T: 'static,
+        F: FnMut(&[CompileResult]) -> windows::core::Result<T> + 'static,
+    {
+        let mut shaders = sources
+            .into_iter()
+            .map(|source| WatchedShader {
+                source,
+                files: vec![],
+            })
+            .collect::<Vec<_>>();
+        let results = shaders
+            .iter_mut()
+            .map(|shader| {
+                shader
+                    .compile(&self.compiler)
+                    .map_err(|e| windows::core::Error::new(windows::Win32::Foundation::E_FAIL, e))
+            })
+            .collect::<windows::core::Result<Vec<_>>>()?;
+        let handle = HotHandle::new(f(&results)?);
+        let target = handle.clone();
+        self.programs.push(Program {
+            name: name.as_ref().to_string(),
+            shaders,
+            rebuild: Box::new(move |results| {
+                target.swap(f(results)?);
+                Ok(())
+            }),
+            pending: false,
+        });
+        Ok(handle)
+    }
+
+    pub fn poll(&mut self, in_flight: &[Signal]) -> Vec<ReloadEvent> {
+        let mut events = vec![];
+        let mut waited = false;
+        for program in &mut self.programs {
+            let mut changed = program.pending;
+            for shader in &mut program.shaders {
+                changed |= shader.changed();
+            }
+            if !changed {
+                continue;
+            }
+            program.pending = false;
+            let mut results = vec![];
+            for shader in &mut program.shaders {
+                match shader.compile(&self.compiler) {
+                    Ok(result) => results.push(result),
+                    Err(errors) => {
+                        events.push(ReloadEvent::CompileFailed {
+                            name: program.name.clone(),
+                            path: shader.source.path.clone(),
+                            errors,
+                        });
+                        break;
+                    }
+                }
+            }
+            if results.len() != program.shaders.len() {
+                continue;
+            }
+            if !waited {
+                for signal in in_flight {
+                    if let Err(error) = signal.wait() {
+                        program.pending = true;
+                        events.push(ReloadEvent::RebuildFailed {
+                            name: program.name.clone(),
+                            error,
+                        });
+                        return events;
+                    }
+                }
+                waited = true;
+            }
+            match (program.rebuild)(&results) {
+                Ok(()) => events.push(ReloadEvent::Reloaded {
+                    name: program.name.clone(),
+                }),
+                Err(error) => events.push(ReloadEvent::RebuildFailed {
+                    name: program.name.clone(),
+                    error,
+                }),
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hot_reload_test() {
+        let handle = HotHandle::new(1);
+        let consumer = handle.clone();
+        let old = consumer.get();
+        assert_eq!(*handle.swap(2), 1);
+        assert_eq!(*old, 1);
+        assert_eq!(*consumer.get(), 2);
+        assert_eq!(consumer.generation(), 1);
+
+        let path =
+            std::env::temp_dir().join(format!("dxwr-hot-reload-{}.hlsl", std::process::id()));
+        std::fs::write(&path, "a").unwrap();
+        let mut file = WatchedFile::new(&path);
+        assert!(!file.changed());
+        file.modified = None;
+        assert!(!file.changed());
+        std::fs::write(&path, "b").unwrap();
+        file.modified = None;
+        assert!(file.changed());
+        std::fs::remove_file(&path).unwrap();
+        assert!(file.changed());
+    }
+}
@@ -0,0 +1,728 @@
+use crate::InputElementDesc;
+use crate::codegen::Padded;
+use crate::reflection::{Member, ShaderBufferDesc, SignatureParameterDesc, Type};
+use std::ffi::CStr;
+use windows::Win32::Graphics::Direct3D::*;
+use windows::Win32::Graphics::Direct3D12::*;
+use windows::Win32::Graphics::Dxgi::Common::*;
+
+#[cfg(feature = "derive")]
+pub use dxwr_derive::{ConstantBuffer, RootConstants, VertexLayout};
+
+pub trait HlslType: Copy + 'static {
+    const HLSL_SIZE: usize;
+    const HLSL_ALIGN: usize;
+
+    fn declarations(_out: &mut Vec<&'static str>) {}
+}
+
+pub trait HlslArrayElement: HlslType {}
+
+macro_rules! impl_hlsl_scalar {
+    ($($t:ty),*) => {
+        $(
+            impl HlslType for $t {
+                const HLSL_SIZE: usize = std::mem::size_of::<$t>();
+                const HLSL_ALIGN: usize = std::mem::size_of::<$t>();
+            }
+
+            impl HlslType for [$t; 1] {
+                const HLSL_SIZE: usize = std::mem::size_of::<$t>();
+                const HLSL_ALIGN: usize = std::mem::size_of::<$t>();
+            }
+
+            impl HlslType for [$t; 2] {
+                const HLSL_SIZE: usize = std::mem::size_of::<$t>() * 2;
+                const HLSL_ALIGN: usize = std::mem::size_of::<$t>();
+            }
+
+            impl HlslType for [$t; 3] {
+                const HLSL_SIZE: usize = std::mem::size_of::<$t>() * 3;
+                const HLSL_ALIGN: usize = std::mem::size_of::<$t>();
+            }
+
+            impl HlslType for [$t; 4] {
+                const HLSL_SIZE: usize = std::mem::size_of::<$t>() * 4;
+                const HLSL_ALIGN: usize = std::mem::size_of::<$t>();
+            }
+        )*
+    };
+}
+
+impl_hlsl_scalar!(f32, i32, u32, f64, i64, u64);
+
+impl HlslArrayElement for [f32; 4] {}
+impl HlslArrayElement for [i32; 4] {}
+impl HlslArrayElement for [u32; 4] {}
+impl HlslArrayElement for [f64; 2] {}
+impl HlslArrayElement for [i64; 2] {}
+impl HlslArrayElement for [u64; 2] {}
+
+impl<T: HlslType, const P: usize> HlslType for Padded<T, P> {
+    const HLSL_SIZE: usize = T::HLSL_SIZE;
+    const HLSL_ALIGN: usize = T::HLSL_ALIGN;
+
+    fn declarations(out: &mut Vec<&'static str>) {
+        T::declarations(out);
+    }
+}
+
+impl<T: HlslType, const P: usize> HlslArrayElement for Padded<T, P> {}
+
+impl<T: HlslArrayElement, const N: usize> HlslType for [T; N] {
+    const HLSL_SIZE: usize = {
+        assert!(N > 0, "HLSL arrays must not be empty");
+        assert!(
+            std::mem::size_of::<T>() == round_up(T::HLSL_SIZE, 16),
+            "HLSL array elements occupy whole 16-byte registers; wrap the element type in Padded"
+        );
+        std::mem::size_of::<T>() * (N - 1) + T::HLSL_SIZE
+    };
+    const HLSL_ALIGN: usize = 16;
+
+    fn declarations(out: &mut Vec<&'static str>) {
+        T::declarations(out);
+    }
+}
+
+#[inline]
+pub const fn round_up(value: usize, align: usize) -> usize {
+    value.div_ceil(align) * align
+}
+
+#[inline]
+pub const fn place(end: usize, size: usize, align: usize) -> usize {
+    let offset = round_up(end, align);
+    if size > 0 && offset / 16 != (offset + size - 1) / 16 {
+        round_up(offset, 16)
+    } else {
+        offset
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct FieldDesc {
+    pub name: &'static str,
+    pub offset: usize,
+    pub size: usize,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum LayoutMismatch {
+    MissingVariable(String),
+    MissingField(String),
+    Offset {
+        name: String,
+        rust: usize,
+        shader: usize,
+    },
+    Size {
+        rust: usize,
+        shader: usize,
+    },
+}
+
+impl std::fmt::Display for LayoutMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingVariable(name) => write!(f, "`{name}` is not in the shader"),
+            Self::MissingField(name) => write!(f, "`{name}` is not in the Rust struct"),
+            Self::Offset { name, rust, shader } => write!(
+                f,
+                "`{name}` is at offset {rust} in Rust but {shader} in the shader"
+            ),
+            Self::Size { rust, shader } => {
+                write!(f, "size is {rust} bytes in Rust but {shader} in the shader")
+            }
+        }
+    }
+}
+
+pub trait ConstantBufferLayout: HlslType {
+    const HLSL: &'static str;
+    const FIELDS: &'static [FieldDesc];
+
+    fn hlsl() -> String {
+        let mut declarations = vec![];
+        Self::declarations(&mut declarations);
+        if !declarations.contains(&Self::HLSL) {
+            declarations.push(Self::HLSL);
+        }
+        declarations.join("\n")
+    }
+
+    fn verify(desc: &ShaderBufferDesc) -> Result<(), Vec<LayoutMismatch>> {
+        let variables: &[Member] = match desc.variables.as_slice() {
+            [
+                Member {
+                    name,
+                    ty: Type::Struct(ty),
+                    offset: 0,
+                },
+            ] if !Self::FIELDS.iter().any(|field| field.name == name) => &ty.members,
+            variables => variables,
+        };
+        let mut errors = vec![];
+        for field in Self::FIELDS {
+            match variables.iter().find(|v| v.name == field.name) {
+                Some(v) if v.offset != field.offset => errors.push(LayoutMismatch::Offset {
+                    name: field.name.to_string(),
+                    rust: field.offset,
+                    shader: v.offset,
+                }),
+                Some(_) => {}
+                None => errors.push(LayoutMismatch::MissingVariable(field.name.to_string())),
+            }
+        }
+        for v in variables {
+            if !Self::FIELDS.iter().any(|field| field.name == v.name) {
+                errors.push(LayoutMismatch::MissingField(v.name.clone()));
+            }
+        }
+        let size = round_up(Self::HLSL_SIZE, 16);
+        if desc.size != 0 && desc.size != size {
+            errors.push(LayoutMismatch::Size {
+                rust: size,
+                shader: desc.size,
+            });
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+#[repr(transparent)]
+pub struct Half(pub u16);
+
+impl Half {
+    pub const fn from_f32(value: f32) -> Self {
+        let bits = value.to_bits();
+        let sign = ((bits >> 16) & 0x8000) as u16;
+        let exp = ((bits >> 23) & 0xff) as i32;
+        let man = bits & 0x7f_ffff;
+        if exp == 0xff {
+            return Self(sign | 0x7c00 | if man != 0 { 0x200 } else { 0 });
+        }
+        let exp = exp - 127 + 15;
+        if exp >= 0x1f {
+            return Self(sign | 0x7c00);
+        }
+        let (half, rem, halfway) = if exp <= 0 {
+            if exp < -10 {
+                return Self(sign);
+            }
+            let man = man | 0x80_0000;
+            let shift = (14 - exp) as u32;
+            (man >> shift, man & ((1 << shift) - 1), 1 << (shift - 1))
+        } else {
+            (((exp as u32) << 10) | (man >> 13), man & 0x1fff, 0x1000)
+        };
+        let round = rem > halfway || (rem == halfway && half & 1 == 1);
+        Self(sign | (half + round as u32) as u16)
+    }
+
+    pub fn to_f32(self) -> f32 {
+        let sign = ((self.0 & 0x8000) as u32) << 16;
+        let exp = ((self.0 >> 10) & 0x1f) as u32;
+        let man = (self.0 & 0x3ff) as u32;
+        let bits = match exp {
+            0 if man != 0 => {
+                let value = man as f32 / (1 << 24) as f32;
+                return if sign != 0 { -value } else { value };
+            }
+            0 => sign,
+            0x1f => sign | 0x7f80_0000 | (man << 13),
+            _ => sign | ((exp + 112) << 23) | (man << 13),
+        };
+        f32::from_bits(bits)
+    }
+}
+
+impl From<f32> for Half {
+    #[inline]
+    fn from(value: f32) -> Self {
+        Self::from_f32(value)
+    }
+}
+
+impl From<Half> for f32 {
+    #[inline]
+    fn from(value: Half) -> Self {
+        value.to_f32()
+    }
+}
+
+pub trait VertexFormat: Copy + 'static {
+    const FORMAT: DXGI_FORMAT;
+    const MASK: u8;
+    const COMPONENT_TYPE: D3D_REGISTER_COMPONENT_TYPE;
+}
+
+macro_rules! impl_vertex_format {
+    ($($t:ty => $format:ident, $mask:literal, $component_type:ident;)*) => {
+        $(
+            impl VertexFormat for $t {
+                const FORMAT: DXGI_FORMAT = $format;
+                const MASK: u8 = $mask;
+                const COMPONENT_TYPE: D3D_REGISTER_COMPONENT_TYPE = $component_type;
+            }
+        )*
+    };
+}
+
+impl_vertex_format! {
+    f32 => DXGI_FORMAT_R32_FLOAT, 0x1, D3D_REGISTER_COMPONENT_FLOAT32;
+    [f32; 1] => DXGI_FORMAT_R32_FLOAT, 0x1, D3D_REGISTER_COMPONENT_FLOAT32;
+    [f32; 2] => DXGI_FORMAT_R32G32_FLOAT, 0x3, D3D_REGISTER_COMPONENT_FLOAT32;
+    [f32; 3] => DXGI_FORMAT_R32G32B32_FLOAT, 0x7, D3D_REGISTER_COMPONENT_FLOAT32;
+    [f32; 4] => DXGI_FORMAT_R32G32B32A32_FLOAT, 0xf, D3D_REGISTER_COMPONENT_FLOAT32;
+    u32 => DXGI_FORMAT_R32_UINT, 0x1, D3D_REGISTER_COMPONENT_UINT32;
+    [u32; 1] => DXGI_FORMAT_R32_UINT, 0x1, D3D_REGISTER_COMPONENT_UINT32;
+    [u32; 2] => DXGI_FORMAT_R32G32_UINT, 0x3, D3D_REGISTER_COMPONENT_UINT32;
+    [u32; 3] => DXGI_FORMAT_R32G32B32_UINT, 0x7, D3D_REGISTER_COMPONENT_UINT32;
+    [u32; 4] => DXGI_FORMAT_R32G32B32A32_UINT, 0xf, D3D_REGISTER_COMPONENT_UINT32;
+    i32 => DXGI_FORMAT_R32_SINT, 0x1, D3D_REGISTER_COMPONENT_SINT32;
+    [i32; 1] => DXGI_FORMAT_R32_SINT, 0x1, D3D_REGISTER_COMPONENT_SINT32;
+    [i32; 2] => DXGI_FORMAT_R32G32_SINT, 0x3, D3D_REGISTER_COMPONENT_SINT32;
+    [i32; 3] => DXGI_FORMAT_R32G32B32_SINT, 0x7, D3D_REGISTER_COMPONENT_SINT32;
+    [i32; 4] => DXGI_FORMAT_R32G32B32A32_SINT, 0xf, D3D_REGISTER_COMPONENT_SINT32;
+    [u8; 2] => DXGI_FORMAT_R8G8_UNORM, 0x3, D3D_REGISTER_COMPONENT_FLOAT32;
+    [u8; 4] => DXGI_FORMAT_R8G8B8A8_UNORM, 0xf, D3D_REGISTER_COMPONENT_FLOAT32;
+    [i8; 2] => DXGI_FORMAT_R8G8_SNORM, 0x3, D3D_REGISTER_COMPONENT_FLOAT32;
+    [i8; 4] => DXGI_FORMAT_R8G8B8A8_SNORM, 0xf, D3D_REGISTER_COMPONENT_FLOAT32;
+    Half => DXGI_FORMAT_R16_FLOAT, 0x1, D3D_REGISTER_COMPONENT_FLOAT32;
+    [Half; 1] => DXGI_FORMAT_R16_FLOAT, 0x1, D3D_REGISTER_COMPONENT_FLOAT32;
+    [Half; 2] => DXGI_FORMAT_R16G16_FLOAT, 0x3, D3D_REGISTER_COMPONENT_FLOAT32;
+    [Half; 4] => DXGI_FORMAT_R16G16B16A16_FLOAT, 0xf, D3D_REGISTER_COMPONENT_FLOAT32;
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct VertexElement {
+    pub semantic_name: &'static CStr,
+    pub semantic_index: u32,
+    pub format: DXGI_FORMAT,
+    pub offset: u32,
+    pub mask: u8,
+    pub component_type: D3D_REGISTER_COMPONENT_TYPE,
+}
+
+impl VertexElement {
+    #[inline]
+    pub fn input_element_desc(
+        &self,
+        slot: u32,
+        instance_step_rate: Option<u32>,
+    ) -> InputElementDesc<'static> {
+        let desc = InputElementDesc::new()
+            .semantic_name(self.semantic_name.to_bytes_with_nul())
+            .semantic_index(self.semantic_index)
+            .format(self.format)
+            .input_slot(slot)
+            .aligned_byte_offset(self.offset);
+        match instance_step_rate {
+            Some(step_rate) => desc
+                .input_slot_class(D3D12_INPUT_CLASSIFICATION_PER_INSTANCE_DATA)
+                .instance_data_step_rate(step_rate),
+            None => desc,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum InputLayoutMismatch {
+    MissingElement {
+        semantic_name: String,
+        semantic_index: u32,
+    },
+    ComponentType {
+        semantic_name: String,
+        semantic_index: u32,
+        layout: D3D_REGISTER_COMPONENT_TYPE,
+        shader: D3D_REGISTER_COMPONENT_TYPE,
+    },
+}
+
+impl std::fmt::Display for InputLayoutMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingElement {
+                semantic_name,
+                semantic_index,
+            } => write!(
+                f,
+                "`{semantic_name}{semantic_index}` is not in the input layout"
+            ),
+            Self::ComponentType {
+                semantic_name,
+                semantic_index,
+                layout,
+                shader,
+            } => write!(
+                f,
+                "`{semantic_name}{semantic_index}` is {:?} in the input layout but {:?} in the shader",
+                layout, shader
+            ),
+        }
+    }
+}
+
+pub(crate) fn component_kind(ty: D3D_REGISTER_COMPONENT_TYPE) -> D3D_REGISTER_COMPONENT_TYPE {
+    match ty {
+        D3D_REGISTER_COMPONENT_FLOAT16 | D3D_REGISTER_COMPONENT_FLOAT64 => {
+            D3D_REGISTER_COMPONENT_FLOAT32
+        }
+        D3D_REGISTER_COMPONENT_UINT16 | D3D_REGISTER_COMPONENT_UINT64 => {
+            D3D_REGISTER_COMPONENT_UINT32
+        }
+        D3D_REGISTER_COMPONENT_SINT16 | D3D_REGISTER_COMPONENT_SINT64 => {
+            D3D_REGISTER_COMPONENT_SINT32
+        }
+        ty => ty,
+    }
+}
+
+pub(crate) struct LayoutElement<'a> {
+    pub semantic_name: &'a str,
+    pub semantic_index: u32,
+    pub component_type: D3D_REGISTER_COMPONENT_TYPE,
+}
+
+pub(crate) fn input_layout_mismatches(
+    elements: &[LayoutElement],
+    inputs: &[SignatureParameterDesc],
+) -> Vec<InputLayoutMismatch> {
+    let mut errors = vec![];
+    for input in inputs
+        .iter()
+        .filter(|input| input.system_value_type == D3D_NAME_UNDEFINED)
+    {
+        let semantic_name = input.semantic_name.clone();
+        let semantic_index = input.semantic_index;
+        let Some(element) = elements.iter().find(|e| {
+            e.semantic_index == semantic_index
+                && e.semantic_name.eq_ignore_ascii_case(&semantic_name)
+        }) else {
+            errors.push(InputLayoutMismatch::MissingElement {
+                semantic_name,
+                semantic_index,
+            });
+            continue;
+        };
+        if element.component_type != D3D_REGISTER_COMPONENT_UNKNOWN
+            && component_kind(element.component_type) != component_kind(input.component_type)
+        {
+            errors.push(InputLayoutMismatch::ComponentType {
+                semantic_name,
+                semantic_index,
+                layout: element.component_type,
+                shader: input.component_type,
+            });
+        }
+    }
+    errors
+}
+
+pub fn verify_input_layout(
+    elements: &[VertexElement],
+    inputs: &[SignatureParameterDesc],
+) -> Result<(), Vec<InputLayoutMismatch>> {
+    let elements = elements
+        .iter()
+        .map(|e| LayoutElement {
+            semantic_name: e.semantic_name.to_str().unwrap_or_default(),
+            semantic_index: e.semantic_index,
+            component_type: e.component_type,
+        })
+        .collect::<Vec<_>>();
+    let errors = input_layout_mismatches(&elements, inputs);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+pub trait VertexLayout: Copy + 'static {
+    const ELEMENTS: &'static [VertexElement];
+    const INSTANCE_STEP_RATE: Option<u32> = None;
+
+    fn input_elements(slot: u32) -> Vec<InputElementDesc<'static>> {
+        Self::ELEMENTS
+            .iter()
+            .map(|e| e.input_element_desc(slot, Self::INSTANCE_STEP_RATE))
+            .collect()
+    }
+
+    fn verify(inputs: &[SignatureParameterDesc]) -> Result<(), Vec<InputLayoutMismatch>> {
+        verify_input_layout(Self::ELEMENTS, inputs)
+    }
+}
+
+pub const MAX_ROOT_32BIT_VALUES: u32 = 64;
+
+/// # Safety
+///
+/// `Self` must be 4-byte aligned plain data with no padding, exactly
+/// `NUM_32BIT_VALUES` 32-bit values long.
+pub unsafe trait RootConstants: Copy + 'static {
+    const NUM_32BIT_VALUES: u32;
+
+    fn as_32bit_values(&self) -> &[u32] {
+        const {
+            assert!(
+                Self::NUM_32BIT_VALUES as usize * 4 == std::mem::size_of::<Self>()
+                    && std::mem::align_of::<Self>() >= 4
+            );
+        }
+        unsafe {
+            std::slice::from_raw_parts(
+                self as *const Self as *const u32,
+                Self::NUM_32BIT_VALUES as usize,
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_test() {
+        assert_eq!(place(4, 12, 4), 4);
+        assert_eq!(place(8, 12, 4), 16);
+        assert_eq!(place(12, 8, 8), 16);
+        assert_eq!(place(20, 4, 16), 32);
+        assert_eq!(<[Padded<f32, 12>; 3]>::HLSL_SIZE, 36);
+        assert_eq!(<[[f32; 4]; 4]>::HLSL_SIZE, 64);
+        assert_eq!(<[Padded<[f32; 3], 4>; 2]>::HLSL_SIZE, 28);
+        for value in [
+            0.0,
+            -2.5,
+            65504.0,
+            6.1035156e-5,
+            5.9604645e-8,
+            f32::INFINITY,
+        ] {
+            assert_eq!(Half::from_f32(value).to_f32(), value);
+        }
+        assert_eq!(Half::from_f32(1.0), Half(0x3c00));
+        assert_eq!(Half::from_f32(1.0 + 1.0 / 4096.0), Half(0x3c00));
+        assert_eq!(Half::from_f32(70000.0).to_f32(), f32::INFINITY);
+        assert!(Half::from_f32(f32::NAN).to_f32().is_nan());
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn constant_buffer_derive_test() {
+        use crate::reflection::Struct;
+
+        #[derive(Clone, Copy, ConstantBuffer)]
+        #[repr(C)]
+        struct Light {
+            pos: [f32; 3],
+            radius: f32,
+            color: [f32; 3],
+        }
+
+        #[derive(Clone, Copy, ConstantBuffer)]
+        #[repr(C)]
+        #[hlsl(cbuffer)]
+        struct PerFrame {
+            #[hlsl(ty = "float4x4")]
+            view_proj: [[f32; 4]; 4],
+            light_dir: [f32; 3],
+            intensity: f32,
+            uv_scale: [f32; 2],
+            #[hlsl(padding)]
+            _pad0: [u8; 8],
+            tint: [f32; 3],
+            #[hlsl(padding)]
+            _pad1: u32,
+            weights: [Padded<f32, 12>; 3],
+            #[hlsl(name = "fog")]
+            fog_color: [f32; 4],
+            lights: [Padded<Light, 4>; 2],
+            ambient: [f32; 4],
+        }
+
+        assert_eq!(
+            PerFrame::HLSL,
+            "cbuffer PerFrame {\n    float4x4 view_proj;\n    float3 light_dir;\n    float intensity;\n    float2 uv_scale;\n    float3 tint;\n    float weights[3];\n    float4 fog;\n    Light lights[2];\n    float4 ambient;\n};\n"
+        );
+        assert!(PerFrame::hlsl().starts_with("struct Light {\n    float3 pos;\n"));
+        assert_eq!(PerFrame::HLSL_SIZE, 256);
+        let offsets = PerFrame::FIELDS
+            .iter()
+            .map(|field| (field.name, field.offset))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            offsets,
+            [
+                ("view_proj", 0),
+                ("light_dir", 64),
+                ("intensity", 76),
+                ("uv_scale", 80),
+                ("tint", 96),
+                ("weights", 112),
+                ("fog", 160),
+                ("lights", 176),
+                ("ambient", 240),
+            ]
+        );
+
+        let mut desc = ShaderBufferDesc {
+            name: "PerFrame".into(),
+            ty: windows::Win32::Graphics::Direct3D::D3D_CT_CBUFFER,
+            size: 256,
+            members: vec![],
+            variables: offsets
+                .iter()
+                .map(|&(name, offset)| Member {
+                    name: name.into(),
+                    ty: Type::Float,
+                    offset,
+                })
+                .collect(),
+        };
+        assert_eq!(PerFrame::verify(&desc), Ok(()));
+        let wrapped = ShaderBufferDesc {
+            variables: vec![Member {
+                name: "g_frame".into(),
+                ty: Type::Struct(Struct {
+                    name: "PerFrame".into(),
+                    members: desc.variables.clone(),
+                }),
+                offset: 0,
+            }],
+            ..desc.clone()
+        };
+        assert_eq!(PerFrame::verify(&wrapped), Ok(()));
+        desc.variables.pop();
+        desc.size = 240;
+        assert_eq!(
+            PerFrame::verify(&desc).unwrap_err(),
+            [
+                LayoutMismatch::MissingVariable("ambient".into()),
+                LayoutMismatch::Size {
+                    rust: 256,
+                    shader: 240
+                },
+            ]
+        );
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn vertex_layout_derive_test() {
+        #[derive(Clone, Copy, VertexLayout)]
+        #[repr(C)]
+        struct Vertex {
+            position: [f32; 3],
+            #[semantic("TEXCOORD", 1)]
+            uv: [Half; 2],
+            #[format(R8G8B8A8_UNORM)]
+            color: u32,
+            joints: [u8; 4],
+        }
+
+        #[derive(Clone, Copy, VertexLayout)]
+        #[repr(C)]
+        #[vertex(instance, step_rate = 2)]
+        struct Instance {
+            #[semantic("INSTANCE_ID")]
+            id: u32,
+        }
+
+        let elements = [Vertex::ELEMENTS, Instance::ELEMENTS].concat();
+        assert_eq!(
+            elements
+                .iter()
+                .map(|e| (
+                    e.semantic_name,
+                    e.semantic_index,
+                    e.format,
+                    e.offset,
+                    e.mask
+                ))
+                .collect::<Vec<_>>(),
+            [
+                (c"POSITION", 0, DXGI_FORMAT_R32G32B32_FLOAT, 0, 0x7),
+                (c"TEXCOORD", 1, DXGI_FORMAT_R16G16_FLOAT, 12, 0x3),
+                (c"COLOR", 0, DXGI_FORMAT_R8G8B8A8_UNORM, 16, 0xf),
+                (c"JOINTS", 0, DXGI_FORMAT_R8G8B8A8_UNORM, 20, 0xf),
+                (c"INSTANCE_ID", 0, DXGI_FORMAT_R32_UINT, 0, 0x1),
+            ]
+        );
+        assert_eq!(Vertex::INSTANCE_STEP_RATE, None);
+        assert_eq!(Instance::INSTANCE_STEP_RATE, Some(2));
+        assert_eq!(Vertex::input_elements(0).len(), 4);
+
+        let input = |name: &str, index, mask, component_type| SignatureParameterDesc {
+            semantic_name: name.into(),
+            semantic_index: index,
+            register: 0,
+            system_value_type: D3D_NAME_UNDEFINED,
+            component_type,
+            mask,
+            read_write_mask: mask,
+            stream: 0,
+            min_precision: D3D_MIN_PRECISION_DEFAULT,
+        };
+        let mut inputs = vec![
+            input("Position", 0, 0x7, D3D_REGISTER_COMPONENT_FLOAT32),
+            input("TEXCOORD", 1, 0x3, D3D_REGISTER_COMPONENT_FLOAT16),
+            input("INSTANCE_ID", 0, 0x1, D3D_REGISTER_COMPONENT_UINT32),
+            SignatureParameterDesc {
+                system_value_type: D3D_NAME_VERTEX_ID,
+                ..input("SV_VertexID", 0, 0x1, D3D_REGISTER_COMPONENT_UINT32)
+            },
+        ];
+        assert_eq!(verify_input_layout(&elements, &inputs), Ok(()));
+        inputs[0].read_write_mask = 0xf;
+        inputs[2].component_type = D3D_REGISTER_COMPONENT_SINT32;
+        inputs.push(input("NORMAL", 0, 0x7, D3D_REGISTER_COMPONENT_FLOAT32));
+        assert_eq!(
+            verify_input_layout(&elements, &inputs).unwrap_err(),
+            [
+                InputLayoutMismatch::ComponentType {
+                    semantic_name: "INSTANCE_ID".into(),
+                    semantic_index: 0,
+                    layout: D3D_REGISTER_COMPONENT_UINT32,
+                    shader: D3D_REGISTER_COMPONENT_SINT32,
+                },
+                InputLayoutMismatch::MissingElement {
+                    semantic_name: "NORMAL".into(),
+                    semantic_index: 0,
+                },
+            ]
+        );
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn root_constants_derive_test() {
+        #[derive(Clone, Copy, RootConstants)]
+        #[repr(C)]
+        struct DrawConstants {
+            index: u32,
+            scale: f32,
+            offset: [i32; 2],
+        }
+
+        let constants = DrawConstants {
+            index: 7,
+            scale: 1.0,
+            offset: [-1, 2],
+        };
+        assert_eq!(DrawConstants::NUM_32BIT_VALUES, 4);
+        assert_eq!(
+            constants.as_32bit_values(),
+            [7, 1.0f32.to_bits(), -1i32 as u32, 2]
+        );
+    }
+}
@@ -1,14 +1,24 @@
+#[cfg(test)]
+extern crate self as dxwr;
+
 mod adapter;
+pub mod codegen;
 mod command_allocator;
 mod command_list;
 pub mod command_list_type;
 mod command_queue;
+pub mod constant_buffer;
 mod debug;
 pub mod descriptor_heap;
 mod device;
+pub mod diagnostics;
 pub mod dxc;
 pub mod features;
 mod fence;
+pub mod hot_reload;
+pub mod layout;
+mod parameter_block;
+pub mod permutation;
 mod pipeline_state;
 pub mod prelude;
 pub mod raytracing;
@@ -16,9 +26,14 @@ pub mod reflection;
 mod resource_barriers;
 pub mod resources;
 mod root_signature;
+pub mod shader_archive;
+pub mod shader_cache;
 mod state_object;
 mod swap_chain;
+#[cfg(test)]
+mod test_support;
 mod utility;
+pub mod validation;
 
 pub mod com {
     pub use ::windows::core::Interface;
@@ -49,8 +64,10 @@ pub use command_allocator::{
 pub use command_list::{
     BundleCommands, BundleGraphicsCommandList, CommandList, Commands, ComputeCommands,
     ComputeGraphicsCommandList, CopyCommands, CopyGraphicsCommandList, DirectCommands,
-    DirectGraphicsCommandList, DiscardRegion, DispatchRaysDesc, GraphicsCommandList,
-    IndexBufferView, TextureCopyLocation, VertexBufferView, Viewport,
+    DirectGraphicsCommandList, DiscardRegion, DispatchGraphDesc, DispatchRaysDesc,
+    GraphicsCommandList, IndexBufferView, NodeCpuInput, NodeGpuInput, SetComputeRoot32BitConstants,
+    SetGraphicsRoot32BitConstants, SetProgramDesc, SetWorkGraphProgramDesc, TextureCopyLocation,
+    VertexBufferView, Viewport,
 };
 pub use command_queue::{
     CommandQueue, ComputeCommandQueue, CopyCommandQueue, DirectCommandQueue,
@@ -69,13 +86,18 @@ pub use device::{Device, PlacedSubresourceFootprint, SubresourceFootprint};
 pub use dxc::{Blob, BlobType, RefBlob};
 pub use features::{Feature, RequestFeature};
 pub use fence::{Fence, Signal};
+pub use parameter_block::{ParameterBlock, ParameterError, ParameterKind, ResourceView};
 pub use pipeline_state::*;
 pub use raytracing::{
-    BuildRaytracingAccelerationStructureDesc, BuildRaytracingAccelerationStructureInputs,
-    BuildRaytracingAccelerationStructureInputsType, RaytracingAccelerationStructurePrebuildInfo,
-    RaytracingGeometryDesc, RaytracingInstanceDesc,
+    AlphaMask, BuildRaytracingAccelerationStructureDesc,
+    BuildRaytracingAccelerationStructureInputs, BuildRaytracingAccelerationStructureInputsType,
+    OpacityMicromapArray, OpacityMicromapArrayData, OpacityMicromapArrayDesc, OpacityMicromapBaker,
+    OpacityMicromapDesc, OpacityMicromapFormat, OpacityMicromapHistogramEntry, OpacityState,
+    RaytracingAabb, RaytracingAabbBuffer, RaytracingAccelerationStructurePrebuildInfo,
+    RaytracingGeometryDesc, RaytracingGeometryOmmLinkageDesc, RaytracingHitGroup,
+    RaytracingInstanceDesc, RaytracingPipeline, RaytracingPipelineLayout,
 };
-pub use reflection::{LibraryReflection, ReflectionType, ShaderReflection};
+pub use reflection::{LibraryReflection, ReflectionType, ShaderKind, ShaderReflection};
 pub use resource_barriers::{AliasingBarrier, ResourceBarrier, TransitionBarrier, UavBarrier};
 pub use resources::{ClearValue, Heap, HeapProperties, Resource, ResourceDesc};
 pub use root_signature::{
@@ -84,9 +106,11 @@ pub use root_signature::{
 };
 pub use state_object::{
     DxilLibraryDesc, DxilSubobjectToExportsAssociation, ExistingCollectionDesc, ExportDesc,
-    GlobalRootSignature, HitGroupDesc, LocalRootSignature, NodeMask, RaytracingPipelineConfig,
-    RaytracingShaderConfig, StateObject, StateObjectConfig, StateObjectProperties,
-    SubobjectToExportsAssociation,
+    GenericProgramDesc, GlobalRootSignature, HitGroupDesc, InputLayoutSubobject,
+    LocalRootSignature, NodeId, NodeInputError, NodeMask, ProgramIdentifier, RasterizerSubobject,
+    RaytracingPipelineConfig, RaytracingShaderConfig, RenderTargetFormatsSubobject, ShaderNode,
+    StateObject, StateObjectConfig, StateObjectDescError, StateObjectProperties,
+    SubobjectToExportsAssociation, WorkGraphDesc, WorkGraphMemoryRequirements, WorkGraphProperties,
 };
 pub use swap_chain::{ResizeBuffers, SwapChain};
 pub use utility::*;
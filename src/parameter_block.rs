@@ -0,0 +1,771 @@
+use super::descriptor_heap_type::*;
+use super::*;
+use crate::layout::RootConstants;
+use crate::validation::{
+    BindingError, RootBinding, ShaderBindings, range_type, required_32bit_values, resolve_binding,
+    validate_bindings,
+};
+use std::collections::BTreeMap;
+use windows::Win32::Graphics::Direct3D12::*;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ParameterKind {
+    Table,
+    RootDescriptor,
+    Constants,
+    StaticSampler,
+}
+
+impl std::fmt::Display for ParameterKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Table => "a descriptor table",
+            Self::RootDescriptor => "a root descriptor",
+            Self::Constants => "root constants",
+            Self::StaticSampler => "a static sampler",
+        };
+        f.write_str(s)
+    }
+}
+
+impl From<RootBinding> for ParameterKind {
+    fn from(value: RootBinding) -> Self {
+        match value {
+            RootBinding::Table { .. } => Self::Table,
+            RootBinding::Descriptor => Self::RootDescriptor,
+            RootBinding::Constants(_) => Self::Constants,
+            RootBinding::StaticSampler => Self::StaticSampler,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ParameterError {
+    Bindings(Vec<BindingError>),
+    UnknownName(String),
+    Type {
+        name: String,
+        expected: D3D12_DESCRIPTOR_RANGE_TYPE,
+        actual: D3D12_DESCRIPTOR_RANGE_TYPE,
+    },
+    WrongBinding {
+        name: String,
+        kind: ParameterKind,
+    },
+    Index {
+        name: String,
+        index: u32,
+        count: u32,
+    },
+    ConstantsSize {
+        name: String,
+        num_32bit_values: u32,
+        expected: u32,
+    },
+    Unset(String),
+    DescriptorHeap {
+        heap_type: D3D12_DESCRIPTOR_HEAP_TYPE,
+        required: usize,
+        available: usize,
+    },
+}
+
+fn view_name(range_type: D3D12_DESCRIPTOR_RANGE_TYPE) -> &'static str {
+    match range_type {
+        D3D12_DESCRIPTOR_RANGE_TYPE_CBV => "a constant buffer",
+        D3D12_DESCRIPTOR_RANGE_TYPE_UAV => "an unordered access view",
+        D3D12_DESCRIPTOR_RANGE_TYPE_SAMPLER => "a sampler",
+        _ => "a shader resource view",
+    }
+}
+
+impl std::fmt::Display for ParameterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bindings(errors) => {
+                for (i, e) in errors.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{e}")?;
+                }
+                Ok(())
+            }
+            Self::UnknownName(name) => write!(f, "no shader resource is named `{name}`"),
+            Self::Type {
+                name,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "`{name}` is {}, not {}",
+                view_name(*actual),
+                view_name(*expected)
+            ),
+            Self::WrongBinding { name, kind } => {
+                write!(f, "`{name}` is bound by {kind} and cannot take this value")
+            }
+            Self::Index { name, index, count } => {
+                write!(
+                    f,
+                    "index {index} is out of range for `{name}` ({count} descriptors)"
+                )
+            }
+            Self::ConstantsSize {
+                name,
+                num_32bit_values,
+                expected,
+            } => write!(
+                f,
+                "`{name}` takes {expected} 32-bit values, not {num_32bit_values}"
+            ),
+            Self::Unset(name) => write!(f, "`{name}` has not been set"),
+            Self::DescriptorHeap {
+                heap_type,
+                required,
+                available,
+            } => write!(
+                f,
+                "{heap_type:?} needs {required} descriptors but only {available} are available"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParameterError {}
+
+#[derive(Clone, Debug)]
+pub enum ResourceView {
+    Address(GpuVirtualAddress),
+    Descriptor(CpuDescriptorHandle<CbvSrvUav>),
+}
+
+impl From<GpuVirtualAddress> for ResourceView {
+    #[inline]
+    fn from(value: GpuVirtualAddress) -> Self {
+        Self::Address(value)
+    }
+}
+
+impl From<CpuDescriptorHandle<CbvSrvUav>> for ResourceView {
+    #[inline]
+    fn from(value: CpuDescriptorHandle<CbvSrvUav>) -> Self {
+        Self::Descriptor(value)
+    }
+}
+
+impl From<&CpuDescriptorHandle<CbvSrvUav>> for ResourceView {
+    #[inline]
+    fn from(value: &CpuDescriptorHandle<CbvSrvUav>) -> Self {
+        Self::Descriptor(value.clone())
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Value {
+    Unset,
+    Address(GpuVirtualAddress),
+    Views(Vec<Option<CpuDescriptorHandle<CbvSrvUav>>>),
+    Samplers(Vec<Option<CpuDescriptorHandle<Sampler>>>),
+    Constants(Vec<u32>),
+}
+
+#[derive(Clone, Debug)]
+struct Entry {
+    name: String,
+    range_type: D3D12_DESCRIPTOR_RANGE_TYPE,
+    count: u32,
+    parameter: u32,
+    binding: RootBinding,
+    required_32bit_values: Option<u32>,
+    value: Value,
+}
+
+impl Entry {
+    fn len(&self) -> Result<u32, ParameterError> {
+        let unset = || ParameterError::Unset(self.name.clone());
+        if self.binding == RootBinding::StaticSampler {
+            return Ok(0);
+        }
+        match &self.value {
+            Value::Unset => Err(unset()),
+            Value::Views(views) if views.is_empty() || views.iter().any(Option::is_none) => {
+                Err(unset())
+            }
+            Value::Samplers(samplers)
+                if samplers.is_empty() || samplers.iter().any(Option::is_none) =>
+            {
+                Err(unset())
+            }
+            Value::Views(views) => Ok(views.len() as u32),
+            Value::Samplers(samplers) => Ok(samplers.len() as u32),
+            _ => Ok(0),
+        }
+    }
+}
+
+fn fill<U: Clone>(
+    slots: &mut Vec<Option<U>>,
+    name: &str,
+    count: u32,
+    first: u32,
+    values: &[U],
+) -> Result<(), ParameterError> {
+    let end = first as usize + values.len();
+    if count != 0 && end > count as usize {
+        return Err(ParameterError::Index {
+            name: name.into(),
+            index: end as u32 - 1,
+            count,
+        });
+    }
+    if slots.len() < end {
+        slots.resize(end, None);
+    }
+    for (slot, value) in slots[first as usize..end].iter_mut().zip(values) {
+        *slot = Some(value.clone());
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug)]
+struct HeapCursor<T> {
+    heap: DescriptorHeap<T>,
+    start: usize,
+    next: usize,
+}
+
+impl<T: Clone> HeapCursor<T> {
+    fn new(heap: &DescriptorHeap<T>, start: usize) -> Self {
+        Self {
+            heap: heap.clone(),
+            start,
+            next: start,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ParameterBlock {
+    compute: bool,
+    entries: Vec<Entry>,
+    views: Option<HeapCursor<CbvSrvUav>>,
+    samplers: Option<HeapCursor<Sampler>>,
+}
+
+impl ParameterBlock {
+    pub fn new(
+        desc: &RootSignatureDesc,
+        shaders: &[&ShaderBindings],
+    ) -> Result<Self, ParameterError> {
+        let errors = shaders
+            .iter()
+            .filter_map(|shader| validate_bindings(desc, shader).err())
+            .flatten()
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            return Err(ParameterError::Bindings(errors));
+        }
+        let mut entries = Vec::<Entry>::new();
+        for shader in shaders {
+            for resource in &shader.resources {
+                let (parameter, binding) = resolve_binding(desc, shader.visibility, resource)
+                    .map_err(|e| ParameterError::Bindings(vec![e]))?;
+                if entries
+                    .iter()
+                    .any(|entry| entry.name == resource.name && entry.parameter == parameter)
+                {
+                    continue;
+                }
+                let range_type = range_type(resource.ty);
+                let value = match binding {
+                    RootBinding::Table { .. }
+                        if range_type == D3D12_DESCRIPTOR_RANGE_TYPE_SAMPLER =>
+                    {
+                        Value::Samplers(vec![None; resource.bind_count as usize])
+                    }
+                    RootBinding::Table { .. } => {
+                        Value::Views(vec![None; resource.bind_count as usize])
+                    }
+                    _ => Value::Unset,
+                };
+                entries.push(Entry {
+                    name: resource.name.clone(),
+                    range_type,
+                    count: resource.bind_count,
+                    parameter,
+                    binding,
+                    required_32bit_values: required_32bit_values(shader, &resource.name),
+                    value,
+                });
+            }
+        }
+        Ok(Self {
+            compute: shaders
+                .iter()
+                .all(|shader| shader.visibility == D3D12_SHADER_VISIBILITY_ALL),
+            entries,
+            views: None,
+            samplers: None,
+        })
+    }
+
+    #[inline]
+    pub fn descriptor_heap(mut self, heap: &DescriptorHeap<CbvSrvUav>, start: usize) -> Self {
+        self.views = Some(HeapCursor::new(heap, start));
+        self
+    }
+
+    #[inline]
+    pub fn sampler_heap(mut self, heap: &DescriptorHeap<Sampler>, start: usize) -> Self {
+        self.samplers = Some(HeapCursor::new(heap, start));
+        self
+    }
+
+    #[inline]
+    pub fn reset_descriptor_heaps(&mut self) {
+        if let Some(views) = &mut self.views {
+            views.next = views.start;
+        }
+        if let Some(samplers) = &mut self.samplers {
+            samplers.next = samplers.start;
+        }
+    }
+
+    #[inline]
+    pub fn is_compute(&self) -> bool {
+        self.compute
+    }
+
+    fn find(
+        &mut self,
+        name: &str,
+        range_type: D3D12_DESCRIPTOR_RANGE_TYPE,
+    ) -> Result<Vec<&mut Entry>, ParameterError> {
+        let entries = self
+            .entries
+            .iter_mut()
+            .filter(|entry| entry.name == name)
+            .collect::<Vec<_>>();
+        let Some(entry) = entries.first() else {
+            return Err(ParameterError::UnknownName(name.into()));
+        };
+        if entry.range_type != range_type {
+            return Err(ParameterError::Type {
+                name: name.into(),
+                expected: range_type,
+                actual: entry.range_type,
+            });
+        }
+        Ok(entries)
+    }
+
+    fn set_view(
+        &mut self,
+        name: &str,
+        range_type: D3D12_DESCRIPTOR_RANGE_TYPE,
+        first: u32,
+        views: &[ResourceView],
+    ) -> Result<(), ParameterError> {
+        for entry in self.find(name, range_type)? {
+            match (entry.binding, &mut entry.value, views) {
+                (RootBinding::Descriptor, value, [ResourceView::Address(address)]) => {
+                    *value = Value::Address(*address);
+                }
+                (RootBinding::Table { .. }, Value::Views(slots), views)
+                    if views
+                        .iter()
+                        .all(|view| matches!(view, ResourceView::Descriptor(_))) =>
+                {
+                    let handles = views
+                        .iter()
+                        .filter_map(|view| match view {
+                            ResourceView::Descriptor(handle) => Some(handle.clone()),
+                            ResourceView::Address(_) => None,
+                        })
+                        .collect::<Vec<_>>();
+                    fill(slots, name, entry.count, first, &handles)?;
+                }
+                (binding, _, _) => {
+                    return Err(ParameterError::WrongBinding {
+                        name: name.into(),
+                        kind: binding.into(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub fn set_cbv(
+        &mut self,
+        name: &str,
+        view: impl Into<ResourceView>,
+    ) -> Result<(), ParameterError> {
+        self.set_view(name, D3D12_DESCRIPTOR_RANGE_TYPE_CBV, 0, &[view.into()])
+    }
+
+    #[inline]
+    pub fn set_srv(
+        &mut self,
+        name: &str,
+        view: impl Into<ResourceView>,
+    ) -> Result<(), ParameterError> {
+        self.set_view(name, D3D12_DESCRIPTOR_RANGE_TYPE_SRV, 0, &[view.into()])
+    }
+
+    #[inline]
+    pub fn set_uav(
+        &mut self,
+        name: &str,
+        view: impl Into<ResourceView>,
+    ) -> Result<(), ParameterError> {
+        self.set_view(name, D3D12_DESCRIPTOR_RANGE_TYPE_UAV, 0, &[view.into()])
+    }
+
+    #[inline]
+    pub fn set_srv_array(
+        &mut self,
+        name: &str,
+        first: u32,
+        views: &[CpuDescriptorHandle<CbvSrvUav>],
+    ) -> Result<(), ParameterError> {
+        let views = views.iter().map(ResourceView::from).collect::<Vec<_>>();
+        self.set_view(name, D3D12_DESCRIPTOR_RANGE_TYPE_SRV, first, &views)
+    }
+
+    #[inline]
+    pub fn set_uav_array(
+        &mut self,
+        name: &str,
+        first: u32,
+        views: &[CpuDescriptorHandle<CbvSrvUav>],
+    ) -> Result<(), ParameterError> {
+        let views = views.iter().map(ResourceView::from).collect::<Vec<_>>();
+        self.set_view(name, D3D12_DESCRIPTOR_RANGE_TYPE_UAV, first, &views)
+    }
+
+    pub fn set_sampler(
+        &mut self,
+        name: &str,
+        sampler: &CpuDescriptorHandle<Sampler>,
+    ) -> Result<(), ParameterError> {
+        for entry in self.find(name, D3D12_DESCRIPTOR_RANGE_TYPE_SAMPLER)? {
+            let Value::Samplers(slots) = &mut entry.value else {
+                return Err(ParameterError::WrongBinding {
+                    name: name.into(),
+                    kind: entry.binding.into(),
+                });
+            };
+            fill(slots, name, entry.count, 0, std::slice::from_ref(sampler))?;
+        }
+        Ok(())
+    }
+
+    pub fn set_constants<T>(&mut self, name: &str, value: &T) -> Result<(), ParameterError>
+    where
+        T: RootConstants,
+    {
+        for entry in self.find(name, D3D12_DESCRIPTOR_RANGE_TYPE_CBV)? {
+            let RootBinding::Constants(num_32bit_values) = entry.binding else {
+                return Err(ParameterError::WrongBinding {
+                    name: name.into(),
+                    kind: entry.binding.into(),
+                });
+            };
+            let expected = if T::NUM_32BIT_VALUES > num_32bit_values {
+                Some(num_32bit_values)
+            } else {
+                entry
+                    .required_32bit_values
+                    .filter(|&required| T::NUM_32BIT_VALUES < required)
+            };
+            if let Some(expected) = expected {
+                return Err(ParameterError::ConstantsSize {
+                    name: name.into(),
+                    num_32bit_values: T::NUM_32BIT_VALUES,
+                    expected,
+                });
+            }
+            entry.value = Value::Constants(value.as_32bit_values().to_vec());
+        }
+        Ok(())
+    }
+
+    pub fn apply<T>(&mut self, commands: &Commands<T>) -> Result<(), ParameterError> {
+        let mut tables = BTreeMap::<u32, (bool, u32)>::new();
+        for entry in &self.entries {
+            let len = entry.len()?;
+            if let RootBinding::Table { offset } = entry.binding {
+                let table = tables
+                    .entry(entry.parameter)
+                    .or_insert((entry.range_type == D3D12_DESCRIPTOR_RANGE_TYPE_SAMPLER, 0));
+                table.1 = table.1.max(offset + len);
+            }
+        }
+        let mut view_start = self.views.as_ref().map_or(0, |views| views.next);
+        let mut sampler_start = self.samplers.as_ref().map_or(0, |samplers| samplers.next);
+        let mut starts = BTreeMap::new();
+        for (&parameter, &(sampler, len)) in &tables {
+            let start = if sampler {
+                &mut sampler_start
+            } else {
+                &mut view_start
+            };
+            starts.insert(parameter, *start);
+            *start += len as usize;
+        }
+        if let Some(error) = [
+            (
+                D3D12_DESCRIPTOR_HEAP_TYPE_CBV_SRV_UAV,
+                view_start,
+                self.views
+                    .as_ref()
+                    .map(|views| (views.heap.len(), views.next)),
+            ),
+            (
+                D3D12_DESCRIPTOR_HEAP_TYPE_SAMPLER,
+                sampler_start,
+                self.samplers
+                    .as_ref()
+                    .map(|samplers| (samplers.heap.len(), samplers.next)),
+            ),
+        ]
+        .into_iter()
+        .find_map(|(heap_type, end, heap)| {
+            let (len, start) = heap.unwrap_or((0, 0));
+            (end > len && end > start).then_some(ParameterError::DescriptorHeap {
+                heap_type,
+                required: end - start,
+                available: len.saturating_sub(start),
+            })
+        }) {
+            return Err(error);
+        }
+        for entry in &self.entries {
+            let parameter = entry.parameter;
+            match (&entry.value, entry.binding) {
+                (Value::Views(views), RootBinding::Table { offset }) => {
+                    let heap = &self.views.as_ref().unwrap().heap;
+                    let start = starts[&parameter] + offset as usize;
+                    for (i, view) in views.iter().flatten().enumerate() {
+                        heap.copy_descriptor(view, start + i);
+                    }
+                }
+                (Value::Samplers(samplers), RootBinding::Table { offset }) => {
+                    let heap = &self.samplers.as_ref().unwrap().heap;
+                    let start = starts[&parameter] + offset as usize;
+                    for (i, sampler) in samplers.iter().flatten().enumerate() {
+                        heap.copy_descriptor(sampler, start + i);
+                    }
+                }
+                (Value::Address(address), _) => {
+                    let address = *address;
+                    match (self.compute, entry.range_type) {
+                        (false, D3D12_DESCRIPTOR_RANGE_TYPE_CBV) => {
+                            commands.set_graphics_root_constant_buffer_view(parameter, address)
+                        }
+                        (false, D3D12_DESCRIPTOR_RANGE_TYPE_UAV) => {
+                            commands.set_graphics_root_unordered_access_view(parameter, address)
+                        }
+                        (false, _) => {
+                            commands.set_graphics_root_shader_resource_view(parameter, address)
+                        }
+                        (true, D3D12_DESCRIPTOR_RANGE_TYPE_CBV) => {
+                            commands.set_compute_root_constant_buffer_view(parameter, address)
+                        }
+                        (true, D3D12_DESCRIPTOR_RANGE_TYPE_UAV) => {
+                            commands.set_compute_root_unordered_access_view(parameter, address)
+                        }
+                        (true, _) => {
+                            commands.set_compute_root_shader_resource_view(parameter, address)
+                        }
+                    }
+                }
+                (Value::Constants(values), _) => {
+                    if self.compute {
+                        commands.set_compute_root_32bit_constants(parameter, values.as_slice(), 0);
+                    } else {
+                        commands.set_graphics_root_32bit_constants(parameter, values.as_slice(), 0);
+                    }
+                }
+                _ => {}
+            }
+        }
+        for (&parameter, &(sampler, _)) in &tables {
+            let start = starts[&parameter];
+            match (self.compute, sampler) {
+                (false, false) => commands.set_graphics_root_descriptor_table(
+                    parameter,
+                    &self.views.as_ref().unwrap().heap.gpu_handle(start),
+                ),
+                (false, true) => commands.set_graphics_root_descriptor_table(
+                    parameter,
+                    &self.samplers.as_ref().unwrap().heap.gpu_handle(start),
+                ),
+                (true, false) => commands.set_compute_root_descriptor_table(
+                    parameter,
+                    &self.views.as_ref().unwrap().heap.gpu_handle(start),
+                ),
+                (true, true) => commands.set_compute_root_descriptor_table(
+                    parameter,
+                    &self.samplers.as_ref().unwrap().heap.gpu_handle(start),
+                ),
+            }
+        }
+        if let Some(views) = &mut self.views {
+            views.next = view_start;
+        }
+        if let Some(samplers) = &mut self.samplers {
+            samplers.next = sampler_start;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reflection::{Member, ShaderBufferDesc, Type};
+    use crate::test_support::bind;
+    use windows::Win32::Graphics::Direct3D::*;
+
+    #[derive(Clone, Copy)]
+    #[repr(C)]
+    struct Push([u32; 2]);
+
+    unsafe impl RootConstants for Push {
+        const NUM_32BIT_VALUES: u32 = 2;
+    }
+
+    #[derive(Clone, Copy)]
+    #[repr(C)]
+    struct Large([u32; 3]);
+
+    unsafe impl RootConstants for Large {
+        const NUM_32BIT_VALUES: u32 = 3;
+    }
+
+    #[test]
+    fn parameter_block_test() {
+        let params: [RootParameter; 4] = [
+            RootParameter::constants_32bit()
+                .shader_register(0)
+                .num_32bit_values(2)
+                .into(),
+            RootParameter::cbv().shader_register(1).into(),
+            RootParameter::descriptor_table()
+                .ranges([DescriptorRange::srv().num_descriptors(4)])
+                .shader_visibility(D3D12_SHADER_VISIBILITY_PIXEL)
+                .into(),
+            RootParameter::descriptor_table()
+                .ranges([DescriptorRange::sampler()
+                    .num_descriptors(1)
+                    .base_shader_register(1)])
+                .shader_visibility(D3D12_SHADER_VISIBILITY_PIXEL)
+                .into(),
+        ];
+        let samplers = [StaticSamplerDesc::new()
+            .shader_register(0)
+            .shader_visibility(D3D12_SHADER_VISIBILITY_PIXEL)];
+        let desc = RootSignatureDesc::new()
+            .parameters(&params)
+            .static_samplers(&samplers);
+        let mut ps = ShaderBindings {
+            visibility: D3D12_SHADER_VISIBILITY_PIXEL,
+            resources: vec![
+                bind("gPush", D3D_SIT_CBUFFER, 0, 1, 0),
+                bind("gFrame", D3D_SIT_CBUFFER, 1, 1, 0),
+                bind("gAlbedo", D3D_SIT_TEXTURE, 0, 1, 0),
+                bind("gTextures", D3D_SIT_TEXTURE, 1, 2, 0),
+                bind("gPoint", D3D_SIT_SAMPLER, 0, 1, 0),
+                bind("gLinear", D3D_SIT_SAMPLER, 1, 1, 0),
+            ],
+            constant_buffers: vec![ShaderBufferDesc {
+                name: "gPush".into(),
+                ty: D3D_CT_CBUFFER,
+                size: 16,
+                members: vec![],
+                variables: vec![Member {
+                    name: "index".into(),
+                    ty: Type::Uint,
+                    offset: 0,
+                }],
+            }],
+        };
+        let mut block = ParameterBlock::new(&desc, &[&ps]).unwrap();
+        assert!(!block.is_compute());
+        let binding = |block: &ParameterBlock, name: &str| {
+            let entry = block.entries.iter().find(|e| e.name == name).unwrap();
+            (entry.parameter, entry.binding)
+        };
+        assert_eq!(binding(&block, "gPush"), (0, RootBinding::Constants(2)));
+        assert_eq!(binding(&block, "gFrame"), (1, RootBinding::Descriptor));
+        assert_eq!(
+            binding(&block, "gAlbedo"),
+            (2, RootBinding::Table { offset: 0 })
+        );
+        assert_eq!(
+            binding(&block, "gTextures"),
+            (2, RootBinding::Table { offset: 1 })
+        );
+        assert_eq!(binding(&block, "gPoint").1, RootBinding::StaticSampler);
+        assert_eq!(
+            binding(&block, "gLinear"),
+            (3, RootBinding::Table { offset: 0 })
+        );
+
+        block.set_cbv("gFrame", GpuVirtualAddress(0x100)).unwrap();
+        block.set_constants("gPush", &Push([1, 2])).unwrap();
+        assert!(matches!(
+            &block.entries[0].value,
+            Value::Constants(values) if values == &[1, 2]
+        ));
+        assert_eq!(
+            block.set_cbv("gMissing", GpuVirtualAddress(0)),
+            Err(ParameterError::UnknownName("gMissing".into()))
+        );
+        assert_eq!(
+            block.set_srv("gFrame", GpuVirtualAddress(0)),
+            Err(ParameterError::Type {
+                name: "gFrame".into(),
+                expected: D3D12_DESCRIPTOR_RANGE_TYPE_SRV,
+                actual: D3D12_DESCRIPTOR_RANGE_TYPE_CBV,
+            })
+        );
+        assert_eq!(
+            block.set_srv("gAlbedo", GpuVirtualAddress(0)),
+            Err(ParameterError::WrongBinding {
+                name: "gAlbedo".into(),
+                kind: ParameterKind::Table,
+            })
+        );
+        assert_eq!(
+            block.set_constants("gFrame", &Push([0, 0])),
+            Err(ParameterError::WrongBinding {
+                name: "gFrame".into(),
+                kind: ParameterKind::RootDescriptor,
+            })
+        );
+        assert_eq!(
+            block.set_constants("gPush", &Large([0, 0, 0])),
+            Err(ParameterError::ConstantsSize {
+                name: "gPush".into(),
+                num_32bit_values: 3,
+                expected: 2,
+            })
+        );
+        assert_eq!(
+            block.entries.iter().map(Entry::len).find(Result::is_err),
+            Some(Err(ParameterError::Unset("gAlbedo".into())))
+        );
+
+        ps.resources
+            .push(bind("gMissing", D3D_SIT_TEXTURE, 8, 1, 0));
+        assert!(matches!(
+            ParameterBlock::new(&desc, &[&ps]),
+            Err(ParameterError::Bindings(errors)) if errors.len() == 1
+        ));
+    }
+}
@@ -0,0 +1,497 @@
+use crate::dxc::{Arguments, BlobType, Compiler, ContentHasher, Utils};
+use crate::pipeline_state::ShaderBytecode;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Axis {
+    Bool { name: String },
+    Enum { name: String, values: Vec<String> },
+}
+
+impl Axis {
+    #[inline]
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Bool { name } => name,
+            Self::Enum { name, .. } => name,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Bool { .. } => 2,
+            Self::Enum { values, .. } => values.len(),
+        }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn value_name(&self, index: usize) -> &str {
+        match self {
+            Self::Bool { .. } => ["0", "1"][index],
+            Self::Enum { values, .. } => &values[index],
+        }
+    }
+
+    fn value_index(&self, value: &str) -> Option<usize> {
+        match self {
+            Self::Bool { .. } => match value {
+                "0" | "false" | "off" => Some(0),
+                "1" | "true" | "on" => Some(1),
+                _ => None,
+            },
+            Self::Enum { values, .. } => values.iter().position(|v| v == value),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum PermutationError {
+    UnknownAxis(String),
+    UnknownValue { axis: String, value: String },
+    Excluded(Vec<String>),
+    Mismatch,
+}
+
+impl std::fmt::Display for PermutationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownAxis(axis) => write!(f, "unknown permutation axis `{axis}`"),
+            Self::UnknownValue { axis, value } => {
+                write!(f, "unknown value `{value}` for permutation axis `{axis}`")
+            }
+            Self::Excluded(defines) => {
+                write!(f, "permutation [{}] is excluded", defines.join(", "))
+            }
+            Self::Mismatch => write!(f, "permutation does not belong to this set"),
+        }
+    }
+}
+
+impl std::error::Error for PermutationError {}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct PermutationKey(pub u64);
+
+impl std::fmt::Display for PermutationKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Permutation {
+    set: u64,
+    values: Vec<usize>,
+}
+
+impl Permutation {
+    #[inline]
+    pub fn values(&self) -> &[usize] {
+        &self.values
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct PermutationSet {
+    axes: Vec<Axis>,
+    exclusions: Vec<Vec<(usize, usize)>>,
+}
+
+impl PermutationSet {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn bool_axis(mut self, name: impl AsRef<str>) -> Self {
+        self.push_axis(Axis::Bool {
+            name: name.as_ref().to_string(),
+        });
+        self
+    }
+
+    #[inline]
+    pub fn enum_axis<T>(mut self, name: impl AsRef<str>, values: &[T]) -> Self
+    where
+        T: AsRef<str>,
+    {
+        assert!(!values.is_empty());
+        self.push_axis(Axis::Enum {
+            name: name.as_ref().to_string(),
+            values: values.iter().map(|v| v.as_ref().to_string()).collect(),
+        });
+        self
+    }
+
+    fn push_axis(&mut self, axis: Axis) {
+        assert!(self.axis_index(axis.name()).is_none());
+        self.axes.push(axis);
+    }
+
+    pub fn exclude<T, U>(mut self, conditions: &[(T, U)]) -> Result<Self, PermutationError>
+    where
+        T: AsRef<str>,
+        U: AsRef<str>,
+    {
+        let conditions = conditions
+            .iter()
+            .map(|(axis, value)| self.resolve(axis.as_ref(), value.as_ref()))
+            .collect::<Result<_, _>>()?;
+        self.exclusions.push(conditions);
+        Ok(self)
+    }
+
+    #[inline]
+    pub fn axes(&self) -> &[Axis] {
+        &self.axes
+    }
+
+    fn axis_index(&self, name: &str) -> Option<usize> {
+        self.axes.iter().position(|axis| axis.name() == name)
+    }
+
+    fn resolve(&self, axis: &str, value: &str) -> Result<(usize, usize), PermutationError> {
+        let index = self
+            .axis_index(axis)
+            .ok_or_else(|| PermutationError::UnknownAxis(axis.to_string()))?;
+        let value_index =
+            self.axes[index]
+                .value_index(value)
+                .ok_or_else(|| PermutationError::UnknownValue {
+                    axis: axis.to_string(),
+                    value: value.to_string(),
+                })?;
+        Ok((index, value_index))
+    }
+
+    fn id(&self) -> u64 {
+        let mut hasher = ContentHasher::new();
+        for axis in &self.axes {
+            hasher.update_str(axis.name());
+            for value in 0..axis.len() {
+                hasher.update_str(axis.value_name(value));
+            }
+        }
+        hasher.finish().0 as u64
+    }
+
+    fn check(&self, permutation: &Permutation) -> Result<(), PermutationError> {
+        if permutation.set == self.id() && permutation.values.len() == self.axes.len() {
+            Ok(())
+        } else {
+            Err(PermutationError::Mismatch)
+        }
+    }
+
+    #[inline]
+    pub fn is_excluded(&self, permutation: &Permutation) -> Result<bool, PermutationError> {
+        self.check(permutation)?;
+        Ok(self.excluded(permutation))
+    }
+
+    fn excluded(&self, permutation: &Permutation) -> bool {
+        self.exclusions.iter().any(|conditions| {
+            conditions
+                .iter()
+                .all(|&(axis, value)| permutation.values[axis] == value)
+        })
+    }
+
+    pub fn permutation<T, U>(&self, values: &[(T, U)]) -> Result<Permutation, PermutationError>
+    where
+        T: AsRef<str>,
+        U: AsRef<str>,
+    {
+        let mut permutation = Permutation {
+            set: self.id(),
+            values: vec![0; self.axes.len()],
+        };
+        for (axis, value) in values {
+            let (axis, value) = self.resolve(axis.as_ref(), value.as_ref())?;
+            permutation.values[axis] = value;
+        }
+        if self.excluded(&permutation) {
+            return Err(PermutationError::Excluded(self.raw_defines(&permutation)));
+        }
+        Ok(permutation)
+    }
+
+    pub fn permutations(&self) -> Vec<Permutation> {
+        let mut permutations = vec![];
+        if self.axes.iter().any(|axis| axis.is_empty()) {
+            return permutations;
+        }
+        let set = self.id();
+        let mut values = vec![0; self.axes.len()];
+        loop {
+            let permutation = Permutation {
+                set,
+                values: values.clone(),
+            };
+            if !self.excluded(&permutation) {
+                permutations.push(permutation);
+            }
+            let mut i = 0;
+            loop {
+                if i == values.len() {
+                    return permutations;
+                }
+                values[i] += 1;
+                if values[i] < self.axes[i].len() {
+                    break;
+                }
+                values[i] = 0;
+                i += 1;
+            }
+        }
+    }
+
+    #[inline]
+    pub fn key(&self, permutation: &Permutation) -> Result<PermutationKey, PermutationError> {
+        self.check(permutation)?;
+        Ok(self.raw_key(permutation))
+    }
+
+    fn raw_key(&self, permutation: &Permutation) -> PermutationKey {
+        let mut pairs = self
+            .axes
+            .iter()
+            .zip(permutation.values.iter())
+            .filter(|&(_, &value)| value != 0)
+            .map(|(axis, &value)| (axis.name(), axis.value_name(value)))
+            .collect::<Vec<_>>();
+        pairs.sort();
+        let mut hasher = ContentHasher::new();
+        for (axis, value) in pairs {
+            hasher.update_str(axis);
+            hasher.update_str(value);
+        }
+        PermutationKey(hasher.finish().0 as u64)
+    }
+
+    #[inline]
+    pub fn defines(&self, permutation: &Permutation) -> Result<Vec<String>, PermutationError> {
+        self.check(permutation)?;
+        Ok(self.raw_defines(permutation))
+    }
+
+    fn raw_defines(&self, permutation: &Permutation) -> Vec<String> {
+        let mut defines = vec![];
+        for (axis, &value) in self.axes.iter().zip(permutation.values.iter()) {
+            match axis {
+                Axis::Bool { name } => defines.push(format!("{name}={value}")),
+                Axis::Enum { name, values } => {
+                    defines.push(format!("{name}={value}"));
+                    defines.push(format!("{name}_{}=1", values[value]));
+                }
+            }
+        }
+        defines
+    }
+
+    #[inline]
+    pub fn arguments(
+        &self,
+        args: &Arguments,
+        permutation: &Permutation,
+    ) -> Result<Arguments, PermutationError> {
+        self.check(permutation)?;
+        Ok(self.raw_arguments(args, permutation))
+    }
+
+    fn raw_arguments(&self, args: &Arguments, permutation: &Permutation) -> Arguments {
+        self.raw_defines(permutation)
+            .into_iter()
+            .fold(args.clone(), |args, define| args.define(define))
+    }
+
+    pub fn compile(
+        &self,
+        src: &[u8],
+        args: &Arguments,
+    ) -> windows::core::Result<CompiledPermutations> {
+        let permutations = self.permutations();
+        let next = AtomicUsize::new(0);
+        let compiled = Mutex::new(HashMap::new());
+        let threads = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+            .min(permutations.len());
+        std::thread::scope(|scope| {
+            let handles = (0..threads)
+                .map(|_| {
+                    scope.spawn(|| -> windows::core::Result<()> {
+                        let utils = Utils::new()?;
+                        let compiler = Compiler::new(&utils)?;
+                        loop {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(permutation) = permutations.get(index) else {
+                                return Ok(());
+                            };
+                            let result =
+                                compiler.compile(src, &self.raw_arguments(args, permutation))?;
+                            if let Err(e) = result.status() {
+                                next.store(permutations.len(), Ordering::Relaxed);
+                                return Err(windows::core::Error::new(
+                                    e.code(),
+                                    format!(
+                                        "[{}] {}",
+                                        self.raw_defines(permutation).join(", "),
+                                        e.message()
+                                    ),
+                                ));
+                            }
+                            let object = result.dxil()?.as_slice().to_vec();
+                            compiled
+                                .lock()
+                                .unwrap()
+                                .insert(self.raw_key(permutation), object);
+                        }
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .try_for_each(|handle| handle.join().unwrap())
+        })?;
+        Ok(CompiledPermutations {
+            set: self.clone(),
+            shaders: compiled.into_inner().unwrap(),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CompiledPermutations {
+    set: PermutationSet,
+    shaders: HashMap<PermutationKey, Vec<u8>>,
+}
+
+impl CompiledPermutations {
+    #[inline]
+    pub fn set(&self) -> &PermutationSet {
+        &self.set
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.shaders.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.shaders.is_empty()
+    }
+
+    #[inline]
+    pub fn get(&self, key: PermutationKey) -> Option<ShaderBytecode<'_>> {
+        self.shaders.get(&key).map(|data| ShaderBytecode::new(data))
+    }
+
+    #[inline]
+    pub fn find<T, U>(
+        &self,
+        values: &[(T, U)],
+    ) -> Result<Option<ShaderBytecode<'_>>, PermutationError>
+    where
+        T: AsRef<str>,
+        U: AsRef<str>,
+    {
+        let permutation = self.set.permutation(values)?;
+        Ok(self.get(self.set.raw_key(&permutation)))
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (PermutationKey, &[u8])> + '_ {
+        self.shaders
+            .iter()
+            .map(|(key, data)| (*key, data.as_slice()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permutation_set_test() {
+        let set = PermutationSet::new()
+            .bool_axis("SHADOWS")
+            .enum_axis("QUALITY", &["LOW", "MEDIUM", "HIGH"])
+            .bool_axis("FOG")
+            .exclude(&[("SHADOWS", "1"), ("QUALITY", "LOW")])
+            .unwrap();
+        let permutations = set.permutations();
+        assert_eq!(permutations.len(), 2 * 3 * 2 - 2);
+        let keys = permutations
+            .iter()
+            .map(|p| set.key(p).unwrap())
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(keys.len(), permutations.len());
+
+        let p = set
+            .permutation(&[("QUALITY", "HIGH"), ("SHADOWS", "true")])
+            .unwrap();
+        assert_eq!(
+            set.defines(&p).unwrap(),
+            ["SHADOWS=1", "QUALITY=2", "QUALITY_HIGH=1", "FOG=0"]
+        );
+        let reordered = PermutationSet::new()
+            .bool_axis("FOG")
+            .bool_axis("SHADOWS")
+            .enum_axis("QUALITY", &["LOW", "MEDIUM", "HIGH"])
+            .bool_axis("EXTRA");
+        let q = reordered
+            .permutation(&[("SHADOWS", "1"), ("QUALITY", "HIGH")])
+            .unwrap();
+        assert_eq!(set.key(&p), reordered.key(&q));
+        assert_eq!(set.key(&q), Err(PermutationError::Mismatch));
+        assert_eq!(set.is_excluded(&q), Err(PermutationError::Mismatch));
+        assert_eq!(set.is_excluded(&p), Ok(false));
+        let flipped = PermutationSet::new()
+            .enum_axis("QUALITY", &["LOW", "MEDIUM", "HIGH"])
+            .bool_axis("SHADOWS")
+            .bool_axis("FOG");
+        let r = flipped.permutation(&[("QUALITY", "HIGH")]).unwrap();
+        assert_eq!(set.defines(&r), Err(PermutationError::Mismatch));
+        let s = flipped.permutation(&[("SHADOWS", "1")]).unwrap();
+        assert_eq!(s.values(), [0, 1, 0]);
+        assert_eq!(set.defines(&s), Err(PermutationError::Mismatch));
+        assert_eq!(set.key(&s), Err(PermutationError::Mismatch));
+        let narrowed = set.clone().exclude(&[("FOG", "1")]).unwrap();
+        assert_eq!(narrowed.key(&p), set.key(&p));
+        assert_eq!(
+            set.clone().exclude(&[("FOO", "1")]).err(),
+            Some(PermutationError::UnknownAxis("FOO".into()))
+        );
+
+        assert_eq!(
+            set.permutation(&[("SHADOWS", "1")]),
+            Err(PermutationError::Excluded(vec![
+                "SHADOWS=1".into(),
+                "QUALITY=0".into(),
+                "QUALITY_LOW=1".into(),
+                "FOG=0".into(),
+            ]))
+        );
+        assert_eq!(
+            set.permutation(&[("FOO", "1")]),
+            Err(PermutationError::UnknownAxis("FOO".into()))
+        );
+        assert_eq!(
+            set.permutation(&[("QUALITY", "ULTRA")]),
+            Err(PermutationError::UnknownValue {
+                axis: "QUALITY".into(),
+                value: "ULTRA".into()
+            })
+        );
+    }
+}
@@ -14,6 +14,11 @@ pub struct ShaderBytecode<'a> {
 impl<'a> ShaderBytecode<'a> {
     #[inline]
     pub fn new(data: &'a [u8]) -> Self {
+        debug_assert_ne!(
+            crate::dxc::ShaderIr::detect(data),
+            Some(crate::dxc::ShaderIr::Spirv),
+            "SPIR-V cannot be used as D3D12 shader bytecode"
+        );
         Self {
             desc: D3D12_SHADER_BYTECODE {
                 pShaderBytecode: data.as_ptr() as *const std::ffi::c_void,
@@ -247,7 +252,7 @@ impl Default for BlendDesc {
 
 #[derive(Clone, Debug)]
 #[repr(transparent)]
-pub struct RasterizerDesc(D3D12_RASTERIZER_DESC);
+pub struct RasterizerDesc(pub(crate) D3D12_RASTERIZER_DESC);
 
 impl RasterizerDesc {
     #[inline]
@@ -520,6 +525,11 @@ impl InputElementDesc<'_> {
         self.desc.InstanceDataStepRate = step_rate;
         self
     }
+
+    #[inline]
+    pub(crate) fn desc(&self) -> &D3D12_INPUT_ELEMENT_DESC {
+        &self.desc
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -1,4 +1,5 @@
 use super::*;
+use windows::Win32::Foundation::E_INVALIDARG;
 use windows::Win32::Graphics::Direct3D12::*;
 use windows::Win32::Graphics::Dxgi::Common::DXGI_FORMAT;
 
@@ -141,17 +142,20 @@ impl BuildRaytracingAccelerationStructureInputsType for TopLevel {
     }
 }
 
-impl From<TopLevel> for BuildRaytracingAccelerationStructureInputs {
+impl From<TopLevel> for BuildRaytracingAccelerationStructureInputs<'_> {
     fn from(value: TopLevel) -> Self {
-        Self(value.0)
+        Self(value.0, std::marker::PhantomData)
     }
 }
 
 #[derive(Clone)]
 #[repr(transparent)]
-pub struct BottomLevel(D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS);
+pub struct BottomLevel<'a>(
+    D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS,
+    std::marker::PhantomData<&'a ()>,
+);
 
-impl BottomLevel {
+impl<'a> BottomLevel<'a> {
     #[inline]
     pub fn flags(mut self, flags: D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAGS) -> Self {
         self.0.Flags = flags;
@@ -159,31 +163,42 @@ impl BottomLevel {
     }
 
     #[inline]
-    pub fn geometry_descs(mut self, descs: &[RaytracingGeometryDesc]) -> Self {
+    pub fn geometry_descs(mut self, descs: &'a [RaytracingGeometryDesc]) -> Self {
+        self.0.NumDescs = descs.len() as u32;
+        self.0.Anonymous.pGeometryDescs = descs.as_ptr() as *const D3D12_RAYTRACING_GEOMETRY_DESC;
+        self
+    }
+
+    #[inline]
+    pub fn omm_triangles_geometry_descs(
+        mut self,
+        descs: &'a [RaytracingGeometryOmmTrianglesDesc<'a>],
+    ) -> Self {
         self.0.NumDescs = descs.len() as u32;
         self.0.Anonymous.pGeometryDescs = descs.as_ptr() as *const D3D12_RAYTRACING_GEOMETRY_DESC;
         self
     }
 }
 
-impl BuildRaytracingAccelerationStructureInputsType for BottomLevel {
+impl BuildRaytracingAccelerationStructureInputsType for BottomLevel<'_> {
     fn get(&self) -> &D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS {
         &self.0
     }
 }
 
-impl From<BottomLevel> for BuildRaytracingAccelerationStructureInputs {
-    fn from(value: BottomLevel) -> Self {
-        Self(value.0)
+impl<'a> From<BottomLevel<'a>> for BuildRaytracingAccelerationStructureInputs<'a> {
+    fn from(value: BottomLevel<'a>) -> Self {
+        Self(value.0, value.1)
     }
 }
 
 #[repr(transparent)]
-pub struct BuildRaytracingAccelerationStructureInputs(
+pub struct BuildRaytracingAccelerationStructureInputs<'a>(
     D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS,
+    std::marker::PhantomData<&'a ()>,
 );
 
-impl BuildRaytracingAccelerationStructureInputs {
+impl<'a> BuildRaytracingAccelerationStructureInputs<'a> {
     #[inline]
     pub fn top_level() -> TopLevel {
         TopLevel(D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS {
@@ -194,12 +209,15 @@ impl BuildRaytracingAccelerationStructureInputs {
     }
 
     #[inline]
-    pub fn bottom_level() -> BottomLevel {
-        BottomLevel(D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS {
-            Type: D3D12_RAYTRACING_ACCELERATION_STRUCTURE_TYPE_BOTTOM_LEVEL,
-            DescsLayout: D3D12_ELEMENTS_LAYOUT_ARRAY,
-            ..Default::default()
-        })
+    pub fn bottom_level() -> BottomLevel<'a> {
+        BottomLevel(
+            D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS {
+                Type: D3D12_RAYTRACING_ACCELERATION_STRUCTURE_TYPE_BOTTOM_LEVEL,
+                DescsLayout: D3D12_ELEMENTS_LAYOUT_ARRAY,
+                ..Default::default()
+            },
+            std::marker::PhantomData,
+        )
     }
 }
 
@@ -279,15 +297,19 @@ impl Default for RaytracingInstanceDesc {
 }
 
 #[repr(transparent)]
-pub struct BuildRaytracingAccelerationStructureDesc(
+pub struct BuildRaytracingAccelerationStructureDesc<'a>(
     pub(crate) D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_DESC,
+    std::marker::PhantomData<&'a ()>,
 );
 
-impl BuildRaytracingAccelerationStructureDesc {
+impl<'a> BuildRaytracingAccelerationStructureDesc<'a> {
     #[inline]
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
-        Self(D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_DESC::default())
+        Self(
+            D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_DESC::default(),
+            std::marker::PhantomData,
+        )
     }
 
     #[inline]
@@ -299,7 +321,7 @@ impl BuildRaytracingAccelerationStructureDesc {
     #[inline]
     pub fn inputs<T>(mut self, inputs: &T) -> Self
     where
-        T: BuildRaytracingAccelerationStructureInputsType,
+        T: BuildRaytracingAccelerationStructureInputsType + 'a,
     {
         self.0.Inputs = *inputs.get();
         self
@@ -317,3 +339,1120 @@ impl BuildRaytracingAccelerationStructureDesc {
         self
     }
 }
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RaytracingHitGroup {
+    pub name: String,
+    pub ty: D3D12_HIT_GROUP_TYPE,
+    pub closest_hit_shader: Option<String>,
+    pub any_hit_shader: Option<String>,
+    pub intersection_shader: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RaytracingPipelineLayout {
+    pub ray_generation_shaders: Vec<String>,
+    pub miss_shaders: Vec<String>,
+    pub callable_shaders: Vec<String>,
+    pub hit_groups: Vec<RaytracingHitGroup>,
+    pub max_payload_size_in_bytes: u32,
+    pub max_attribute_size_in_bytes: u32,
+}
+
+#[derive(Clone, Debug)]
+struct PipelineFunction {
+    name: String,
+    kind: reflection::ShaderKind,
+    payload_size_in_bytes: Option<u32>,
+    attribute_size_in_bytes: Option<u32>,
+}
+
+pub fn default_hit_group_name(shader_name: &str) -> String {
+    let prefix = ["ClosestHit", "AnyHit", "Intersection"]
+        .iter()
+        .find_map(|suffix| shader_name.strip_suffix(suffix))
+        .map(|prefix| prefix.trim_end_matches('_'));
+    match prefix {
+        Some("") => "HitGroup".to_string(),
+        Some(prefix) => format!("{prefix}_HitGroup"),
+        None => format!("{shader_name}_HitGroup"),
+    }
+}
+
+pub struct RaytracingPipeline<'a> {
+    device: Device,
+    library: &'a [u8],
+    functions: Vec<PipelineFunction>,
+    hit_group_rule: Box<dyn Fn(&str) -> String + 'a>,
+    hit_groups: Vec<(String, Vec<String>)>,
+    max_payload_size_in_bytes: Option<u32>,
+    max_attribute_size_in_bytes: Option<u32>,
+    max_trace_recursion_depth: u32,
+    flags: D3D12_RAYTRACING_PIPELINE_FLAGS,
+    global_root_signature: Option<&'a RootSignature>,
+    local_root_signatures: Vec<(&'a RootSignature, Vec<String>)>,
+    name: Option<String>,
+}
+
+impl<'a> RaytracingPipeline<'a> {
+    #[inline]
+    pub fn new(device: &Device, library: &'a [u8], reflection: &LibraryReflection) -> Self {
+        let runtime_data = reflection::RuntimeData::new(library);
+        let functions = reflection
+            .functions()
+            .filter_map(|f| {
+                let kind = f.shader_kind();
+                if !kind.is_raytracing() {
+                    return None;
+                }
+                let info = runtime_data.as_ref().and_then(|rd| {
+                    rd.functions()
+                        .iter()
+                        .find(|info| info.unmangled_name == f.name())
+                });
+                Some(PipelineFunction {
+                    name: f.name().to_string(),
+                    kind,
+                    payload_size_in_bytes: info.map(|info| info.payload_size_in_bytes),
+                    attribute_size_in_bytes: info.map(|info| info.attribute_size_in_bytes),
+                })
+            })
+            .collect();
+        Self {
+            device: device.clone(),
+            library,
+            functions,
+            hit_group_rule: Box::new(default_hit_group_name),
+            hit_groups: vec![],
+            max_payload_size_in_bytes: None,
+            max_attribute_size_in_bytes: None,
+            max_trace_recursion_depth: 1,
+            flags: D3D12_RAYTRACING_PIPELINE_FLAG_NONE,
+            global_root_signature: None,
+            local_root_signatures: vec![],
+            name: None,
+        }
+    }
+
+    #[inline]
+    pub fn hit_group_rule(mut self, f: impl Fn(&str) -> String + 'a) -> Self {
+        self.hit_group_rule = Box::new(f);
+        self
+    }
+
+    #[inline]
+    pub fn hit_group(mut self, name: impl AsRef<str>, shaders: &[&str]) -> Self {
+        self.hit_groups.push((
+            name.as_ref().to_string(),
+            shaders.iter().map(|s| s.to_string()).collect(),
+        ));
+        self
+    }
+
+    #[inline]
+    pub fn max_payload_size_in_bytes(mut self, size: u32) -> Self {
+        self.max_payload_size_in_bytes = Some(size);
+        self
+    }
+
+    #[inline]
+    pub fn max_attribute_size_in_bytes(mut self, size: u32) -> Self {
+        self.max_attribute_size_in_bytes = Some(size);
+        self
+    }
+
+    #[inline]
+    pub fn max_trace_recursion_depth(mut self, depth: u32) -> Self {
+        self.max_trace_recursion_depth = depth;
+        self
+    }
+
+    #[inline]
+    pub fn flags(mut self, flags: D3D12_RAYTRACING_PIPELINE_FLAGS) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    #[inline]
+    pub fn global_root_signature(mut self, root_sig: &'a RootSignature) -> Self {
+        self.global_root_signature = Some(root_sig);
+        self
+    }
+
+    #[inline]
+    pub fn local_root_signature(mut self, root_sig: &'a RootSignature, exports: &[&str]) -> Self {
+        self.local_root_signatures
+            .push((root_sig, exports.iter().map(|e| e.to_string()).collect()));
+        self
+    }
+
+    #[inline]
+    pub fn name(mut self, name: impl AsRef<str>) -> Self {
+        self.name = Some(name.as_ref().to_string());
+        self
+    }
+
+    #[inline]
+    pub fn layout(&self) -> windows::core::Result<RaytracingPipelineLayout> {
+        assemble_layout(
+            &self.functions,
+            &self.hit_groups,
+            &*self.hit_group_rule,
+            self.max_payload_size_in_bytes,
+            self.max_attribute_size_in_bytes,
+        )
+    }
+
+    #[inline]
+    pub fn build(&self) -> windows::core::Result<StateObject> {
+        let layout = self.layout()?;
+        let exports = self
+            .functions
+            .iter()
+            .map(|f| ExportDesc::new().name(&f.name))
+            .collect::<Vec<_>>();
+        let library = DxilLibraryDesc::new(ShaderBytecode::new(self.library)).exports(&exports);
+        let hit_groups = layout
+            .hit_groups
+            .iter()
+            .map(|hg| {
+                let mut desc = HitGroupDesc::new().hit_group_type(hg.ty).export(&hg.name);
+                if let Some(s) = hg.closest_hit_shader.as_ref() {
+                    desc = desc.closest_hit_shader_import(s);
+                }
+                if let Some(s) = hg.any_hit_shader.as_ref() {
+                    desc = desc.any_hit_shader_import(s);
+                }
+                if let Some(s) = hg.intersection_shader.as_ref() {
+                    desc = desc.intersection_shader_import(s);
+                }
+                desc
+            })
+            .collect::<Vec<_>>();
+        let shader_config = RaytracingShaderConfig::new()
+            .max_payload_size_in_bytes(layout.max_payload_size_in_bytes)
+            .max_attribute_size_in_bytes(layout.max_attribute_size_in_bytes);
+        let pipeline_config = RaytracingPipelineConfig::new()
+            .max_trace_recursion_depth(self.max_trace_recursion_depth)
+            .flags(self.flags);
+        let global_root_signature = self.global_root_signature.map(GlobalRootSignature::new);
+        let local_root_signatures = self
+            .local_root_signatures
+            .iter()
+            .map(|(root_sig, _)| LocalRootSignature::new(root_sig))
+            .collect::<Vec<_>>();
+        let associations = local_root_signatures
+            .iter()
+            .zip(self.local_root_signatures.iter())
+            .map(|(root_sig, (_, exports))| {
+                SubobjectToExportsAssociation::new(root_sig)
+                    .exports(&exports.iter().map(|e| e.as_str()).collect::<Vec<_>>())
+            })
+            .collect::<Vec<_>>();
+        let mut builder =
+            StateObject::new(&self.device, D3D12_STATE_OBJECT_TYPE_RAYTRACING_PIPELINE)
+                .subobject(&library)
+                .subobject(&shader_config)
+                .subobject(&pipeline_config);
+        for hit_group in hit_groups.iter() {
+            builder = builder.subobject(hit_group);
+        }
+        if let Some(root_sig) = global_root_signature.as_ref() {
+            builder = builder.subobject(root_sig);
+        }
+        for root_sig in local_root_signatures.iter() {
+            builder = builder.subobject(root_sig);
+        }
+        for association in associations.iter() {
+            builder = builder.associate(association);
+        }
+        if let Some(name) = self.name.as_ref() {
+            builder = builder.name(name);
+        }
+        builder.build()
+    }
+}
+
+fn assemble_layout(
+    functions: &[PipelineFunction],
+    explicit_hit_groups: &[(String, Vec<String>)],
+    hit_group_rule: &dyn Fn(&str) -> String,
+    max_payload_size_in_bytes: Option<u32>,
+    max_attribute_size_in_bytes: Option<u32>,
+) -> windows::core::Result<RaytracingPipelineLayout> {
+    use reflection::ShaderKind;
+
+    let invalid_arg = |msg: String| windows::core::Error::new(E_INVALIDARG, msg);
+    let mut layout = RaytracingPipelineLayout {
+        ray_generation_shaders: vec![],
+        miss_shaders: vec![],
+        callable_shaders: vec![],
+        hit_groups: vec![],
+        max_payload_size_in_bytes: 0,
+        max_attribute_size_in_bytes: 0,
+    };
+    let mut grouped = vec![];
+    for (name, shaders) in explicit_hit_groups.iter() {
+        layout.hit_groups.push(RaytracingHitGroup {
+            name: name.clone(),
+            ty: D3D12_HIT_GROUP_TYPE_TRIANGLES,
+            closest_hit_shader: None,
+            any_hit_shader: None,
+            intersection_shader: None,
+        });
+        for shader in shaders.iter() {
+            let f = functions
+                .iter()
+                .find(|f| &f.name == shader)
+                .ok_or_else(|| invalid_arg(format!("{shader} is not in the library")))?;
+            add_to_hit_group(layout.hit_groups.last_mut().unwrap(), f)?;
+            grouped.push(shader.as_str());
+        }
+    }
+    for f in functions.iter() {
+        match f.kind {
+            ShaderKind::RayGeneration => layout.ray_generation_shaders.push(f.name.clone()),
+            ShaderKind::Miss => layout.miss_shaders.push(f.name.clone()),
+            ShaderKind::Callable => layout.callable_shaders.push(f.name.clone()),
+            ShaderKind::ClosestHit | ShaderKind::AnyHit | ShaderKind::Intersection => {
+                if grouped.contains(&f.name.as_str()) {
+                    continue;
+                }
+                let name = hit_group_rule(&f.name);
+                let index = match layout.hit_groups.iter().position(|hg| hg.name == name) {
+                    Some(index) => index,
+                    None => {
+                        layout.hit_groups.push(RaytracingHitGroup {
+                            name,
+                            ty: D3D12_HIT_GROUP_TYPE_TRIANGLES,
+                            closest_hit_shader: None,
+                            any_hit_shader: None,
+                            intersection_shader: None,
+                        });
+                        layout.hit_groups.len() - 1
+                    }
+                };
+                add_to_hit_group(&mut layout.hit_groups[index], f)?;
+            }
+            _ => {}
+        }
+    }
+    let payload_shaders = functions.iter().filter(|f| {
+        matches!(
+            f.kind,
+            ShaderKind::ClosestHit | ShaderKind::AnyHit | ShaderKind::Miss
+        )
+    });
+    layout.max_payload_size_in_bytes = match max_payload_size_in_bytes {
+        Some(size) => size,
+        None => payload_shaders
+            .map(|f| {
+                f.payload_size_in_bytes
+                    .ok_or_else(|| invalid_arg(format!("payload size of {} is unknown", f.name)))
+            })
+            .try_fold(0, |max, size| size.map(|size| max.max(size)))?,
+    };
+    layout.max_attribute_size_in_bytes = match max_attribute_size_in_bytes {
+        Some(size) => size,
+        None => functions
+            .iter()
+            .filter(|f| {
+                matches!(
+                    f.kind,
+                    ShaderKind::ClosestHit | ShaderKind::AnyHit | ShaderKind::Intersection
+                )
+            })
+            .map(|f| match (f.attribute_size_in_bytes, f.kind) {
+                (Some(size), _) => size,
+                (None, ShaderKind::Intersection) => D3D12_RAYTRACING_MAX_ATTRIBUTE_SIZE_IN_BYTES,
+                (None, _) => (std::mem::size_of::<f32>() * 2) as u32,
+            })
+            .max()
+            .unwrap_or(0),
+    };
+    Ok(layout)
+}
+
+fn add_to_hit_group(
+    hit_group: &mut RaytracingHitGroup,
+    f: &PipelineFunction,
+) -> windows::core::Result<()> {
+    use reflection::ShaderKind;
+
+    let slot = match f.kind {
+        ShaderKind::ClosestHit => &mut hit_group.closest_hit_shader,
+        ShaderKind::AnyHit => &mut hit_group.any_hit_shader,
+        ShaderKind::Intersection => {
+            hit_group.ty = D3D12_HIT_GROUP_TYPE_PROCEDURAL_PRIMITIVE;
+            &mut hit_group.intersection_shader
+        }
+        _ => {
+            return Err(windows::core::Error::new(
+                E_INVALIDARG,
+                format!("{} cannot be part of a hit group", f.name),
+            ));
+        }
+    };
+    if let Some(prev) = slot.as_ref() {
+        return Err(windows::core::Error::new(
+            E_INVALIDARG,
+            format!(
+                "{} and {} are both {:?} shaders in hit group {}",
+                prev, f.name, f.kind, hit_group.name
+            ),
+        ));
+    }
+    *slot = Some(f.name.clone());
+    Ok(())
+}
+
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+#[repr(transparent)]
+pub struct RaytracingAabb(pub D3D12_RAYTRACING_AABB);
+
+impl RaytracingAabb {
+    #[inline]
+    pub fn new(min: [f32; 3], max: [f32; 3]) -> Self {
+        Self(D3D12_RAYTRACING_AABB {
+            MinX: min[0],
+            MinY: min[1],
+            MinZ: min[2],
+            MaxX: max[0],
+            MaxY: max[1],
+            MaxZ: max[2],
+        })
+    }
+
+    #[inline]
+    pub fn from_points(points: &[[f32; 3]]) -> Self {
+        let mut min = [f32::MAX; 3];
+        let mut max = [f32::MIN; 3];
+        for p in points {
+            for i in 0..3 {
+                min[i] = min[i].min(p[i]);
+                max[i] = max[i].max(p[i]);
+            }
+        }
+        Self::new(min, max)
+    }
+
+    #[inline]
+    pub fn min(&self) -> [f32; 3] {
+        [self.0.MinX, self.0.MinY, self.0.MinZ]
+    }
+
+    #[inline]
+    pub fn max(&self) -> [f32; 3] {
+        [self.0.MaxX, self.0.MaxY, self.0.MaxZ]
+    }
+
+    #[inline]
+    pub fn union(&self, other: &Self) -> Self {
+        let (a_min, a_max) = (self.min(), self.max());
+        let (b_min, b_max) = (other.min(), other.max());
+        Self::new(
+            [
+                a_min[0].min(b_min[0]),
+                a_min[1].min(b_min[1]),
+                a_min[2].min(b_min[2]),
+            ],
+            [
+                a_max[0].max(b_max[0]),
+                a_max[1].max(b_max[1]),
+                a_max[2].max(b_max[2]),
+            ],
+        )
+    }
+}
+
+pub struct RaytracingAabbBuffer {
+    resource: Resource,
+    len: u64,
+}
+
+impl RaytracingAabbBuffer {
+    #[inline]
+    pub fn new(device: &Device, aabbs: &[RaytracingAabb]) -> windows::core::Result<Self> {
+        let resource = Resource::new(device)
+            .heap_properties(&HeapProperties::upload())
+            .resource_desc(
+                &ResourceDesc::buffer().width(std::mem::size_of_val(aabbs).max(1) as u64),
+            )
+            .init_state(D3D12_RESOURCE_STATE_GENERIC_READ)
+            .name("aabb_buffer")
+            .build()?;
+        unsafe {
+            let data = resource.map(0)?;
+            std::ptr::copy_nonoverlapping(
+                aabbs.as_ptr(),
+                data.as_mut_ptr() as *mut RaytracingAabb,
+                aabbs.len(),
+            );
+        }
+        Ok(Self {
+            resource,
+            len: aabbs.len() as u64,
+        })
+    }
+
+    #[inline]
+    pub fn resource(&self) -> &Resource {
+        &self.resource
+    }
+
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    pub fn geometry_desc(&self) -> RaytracingGeometryAABBsDesc {
+        RaytracingGeometryDesc::aabbs().aabb_count(self.len).aabbs(
+            GpuVirtualAddressAndStride::new()
+                .start_address(self.resource.get_gpu_virtual_address())
+                .stride_in_bytes(std::mem::size_of::<RaytracingAabb>() as u64),
+        )
+    }
+}
+
+const D3D12_RAYTRACING_GEOMETRY_TYPE_OMM_TRIANGLES: D3D12_RAYTRACING_GEOMETRY_TYPE =
+    D3D12_RAYTRACING_GEOMETRY_TYPE(2);
+const D3D12_RAYTRACING_ACCELERATION_STRUCTURE_TYPE_OPACITY_MICROMAP_ARRAY:
+    D3D12_RAYTRACING_ACCELERATION_STRUCTURE_TYPE = D3D12_RAYTRACING_ACCELERATION_STRUCTURE_TYPE(2);
+
+#[allow(non_camel_case_types, non_snake_case)]
+#[derive(Clone, Copy, Default)]
+#[repr(C)]
+struct D3D12_RAYTRACING_GEOMETRY_OMM_LINKAGE_DESC {
+    OpacityMicromapIndexBuffer: D3D12_GPU_VIRTUAL_ADDRESS_AND_STRIDE,
+    OpacityMicromapIndexFormat: DXGI_FORMAT,
+    OpacityMicromapBaseLocation: u32,
+    OpacityMicromapArray: u64,
+}
+
+#[allow(non_camel_case_types, non_snake_case)]
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct D3D12_RAYTRACING_GEOMETRY_OMM_TRIANGLES_DESC {
+    pTriangles: *const D3D12_RAYTRACING_GEOMETRY_TRIANGLES_DESC,
+    pOmmLinkage: *const D3D12_RAYTRACING_GEOMETRY_OMM_LINKAGE_DESC,
+}
+
+#[allow(non_camel_case_types, non_snake_case)]
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct D3D12_RAYTRACING_OPACITY_MICROMAP_ARRAY_DESC {
+    NumOmmHistogramEntries: u32,
+    pOmmHistogram: *const OpacityMicromapHistogramEntry,
+    InputBuffer: u64,
+    PerOmmDescs: D3D12_GPU_VIRTUAL_ADDRESS_AND_STRIDE,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(u32)]
+pub enum OpacityMicromapFormat {
+    Oc1_2State = 1,
+    Oc1_4State = 2,
+}
+
+impl OpacityMicromapFormat {
+    #[inline]
+    pub fn bits_per_micro_triangle(&self) -> u32 {
+        match self {
+            Self::Oc1_2State => 1,
+            Self::Oc1_4State => 2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(u8)]
+pub enum OpacityState {
+    Transparent = 0,
+    Opaque = 1,
+    UnknownTransparent = 2,
+    UnknownOpaque = 3,
+}
+
+pub mod opacity_micromap_special_index {
+    pub const FULLY_TRANSPARENT: i32 = -1;
+    pub const FULLY_OPAQUE: i32 = -2;
+    pub const FULLY_UNKNOWN_TRANSPARENT: i32 = -3;
+    pub const FULLY_UNKNOWN_OPAQUE: i32 = -4;
+}
+
+pub const OPACITY_MICROMAP_MAX_SUBDIVISION_LEVEL: u32 = 12;
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[repr(C)]
+pub struct OpacityMicromapDesc {
+    pub byte_offset: u32,
+    pub subdivision_level: u16,
+    pub format: u16,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(C)]
+pub struct OpacityMicromapHistogramEntry {
+    pub count: u32,
+    pub subdivision_level: u32,
+    pub format: OpacityMicromapFormat,
+}
+
+#[repr(transparent)]
+pub struct OpacityMicromapArrayDesc<'a> {
+    desc: D3D12_RAYTRACING_OPACITY_MICROMAP_ARRAY_DESC,
+    _a: std::marker::PhantomData<&'a ()>,
+}
+
+impl<'a> OpacityMicromapArrayDesc<'a> {
+    #[inline]
+    pub fn new(histogram: &'a [OpacityMicromapHistogramEntry]) -> Self {
+        Self {
+            desc: D3D12_RAYTRACING_OPACITY_MICROMAP_ARRAY_DESC {
+                NumOmmHistogramEntries: histogram.len() as u32,
+                pOmmHistogram: histogram.as_ptr(),
+                InputBuffer: 0,
+                PerOmmDescs: D3D12_GPU_VIRTUAL_ADDRESS_AND_STRIDE::default(),
+            },
+            _a: std::marker::PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn input_buffer(mut self, addr: GpuVirtualAddress) -> Self {
+        self.desc.InputBuffer = addr.0;
+        self
+    }
+
+    #[inline]
+    pub fn per_omm_descs(mut self, addr: GpuVirtualAddress) -> Self {
+        self.desc.PerOmmDescs = D3D12_GPU_VIRTUAL_ADDRESS_AND_STRIDE {
+            StartAddress: addr.0,
+            StrideInBytes: std::mem::size_of::<OpacityMicromapDesc>() as u64,
+        };
+        self
+    }
+}
+
+#[derive(Clone)]
+#[repr(transparent)]
+pub struct OpacityMicromapArray<'a>(
+    D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS,
+    std::marker::PhantomData<&'a ()>,
+);
+
+impl OpacityMicromapArray<'_> {
+    #[inline]
+    pub fn flags(mut self, flags: D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAGS) -> Self {
+        self.0.Flags = flags;
+        self
+    }
+}
+
+impl BuildRaytracingAccelerationStructureInputsType for OpacityMicromapArray<'_> {
+    fn get(&self) -> &D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS {
+        &self.0
+    }
+}
+
+impl<'a> From<OpacityMicromapArray<'a>> for BuildRaytracingAccelerationStructureInputs<'a> {
+    fn from(value: OpacityMicromapArray<'a>) -> Self {
+        Self(value.0, value.1)
+    }
+}
+
+impl<'a> BuildRaytracingAccelerationStructureInputs<'a> {
+    #[inline]
+    pub fn opacity_micromap_array(desc: &'a OpacityMicromapArrayDesc) -> OpacityMicromapArray<'a> {
+        let mut inputs = D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS {
+            Type: D3D12_RAYTRACING_ACCELERATION_STRUCTURE_TYPE_OPACITY_MICROMAP_ARRAY,
+            NumDescs: 1,
+            DescsLayout: D3D12_ELEMENTS_LAYOUT_ARRAY,
+            ..Default::default()
+        };
+        inputs.Anonymous.InstanceDescs = &desc.desc as *const _ as u64;
+        OpacityMicromapArray(inputs, std::marker::PhantomData)
+    }
+}
+
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct RaytracingGeometryOmmLinkageDesc(D3D12_RAYTRACING_GEOMETRY_OMM_LINKAGE_DESC);
+
+impl RaytracingGeometryOmmLinkageDesc {
+    #[inline]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self(D3D12_RAYTRACING_GEOMETRY_OMM_LINKAGE_DESC::default())
+    }
+
+    #[inline]
+    pub fn opacity_micromap_index_buffer(
+        mut self,
+        addr_and_stride: GpuVirtualAddressAndStride,
+        format: DXGI_FORMAT,
+    ) -> Self {
+        self.0.OpacityMicromapIndexBuffer = addr_and_stride.0;
+        self.0.OpacityMicromapIndexFormat = format;
+        self
+    }
+
+    #[inline]
+    pub fn opacity_micromap_base_location(mut self, location: u32) -> Self {
+        self.0.OpacityMicromapBaseLocation = location;
+        self
+    }
+
+    #[inline]
+    pub fn opacity_micromap_array(mut self, addr: GpuVirtualAddress) -> Self {
+        self.0.OpacityMicromapArray = addr.0;
+        self
+    }
+}
+
+#[repr(transparent)]
+pub struct RaytracingGeometryOmmTrianglesDesc<'a> {
+    desc: D3D12_RAYTRACING_GEOMETRY_DESC,
+    _a: std::marker::PhantomData<&'a ()>,
+}
+
+impl RaytracingGeometryOmmTrianglesDesc<'_> {
+    #[inline]
+    pub fn flags(mut self, flags: D3D12_RAYTRACING_GEOMETRY_FLAGS) -> Self {
+        self.desc.Flags = flags;
+        self
+    }
+}
+
+impl RaytracingGeometryDesc {
+    #[inline]
+    pub fn omm_triangles<'a>(
+        triangles: &'a RaytracingGeometryTrianglesDesc,
+        linkage: &'a RaytracingGeometryOmmLinkageDesc,
+    ) -> RaytracingGeometryOmmTrianglesDesc<'a> {
+        let mut desc = D3D12_RAYTRACING_GEOMETRY_DESC {
+            Type: D3D12_RAYTRACING_GEOMETRY_TYPE_OMM_TRIANGLES,
+            Flags: triangles.0.Flags,
+            Anonymous: D3D12_RAYTRACING_GEOMETRY_DESC_0 {
+                Triangles: D3D12_RAYTRACING_GEOMETRY_TRIANGLES_DESC::default(),
+            },
+        };
+        unsafe {
+            std::ptr::write(
+                &mut desc.Anonymous as *mut _ as *mut D3D12_RAYTRACING_GEOMETRY_OMM_TRIANGLES_DESC,
+                D3D12_RAYTRACING_GEOMETRY_OMM_TRIANGLES_DESC {
+                    pTriangles: &triangles.0.Anonymous.Triangles,
+                    pOmmLinkage: &linkage.0,
+                },
+            );
+        }
+        RaytracingGeometryOmmTrianglesDesc {
+            desc,
+            _a: std::marker::PhantomData,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct AlphaMask<'a> {
+    width: u32,
+    height: u32,
+    data: &'a [u8],
+}
+
+impl<'a> AlphaMask<'a> {
+    #[inline]
+    pub fn new(width: u32, height: u32, data: &'a [u8]) -> Self {
+        assert!(width > 0 && height > 0);
+        assert!(data.len() >= width as usize * height as usize);
+        Self {
+            width,
+            height,
+            data,
+        }
+    }
+
+    fn texel(&self, x: i64, y: i64) -> u8 {
+        let x = x.rem_euclid(self.width as i64) as usize;
+        let y = y.rem_euclid(self.height as i64) as usize;
+        self.data[y * self.width as usize + x]
+    }
+
+    fn sample(&self, uv: [f32; 2]) -> u8 {
+        self.texel(
+            (uv[0] * self.width as f32).floor() as i64,
+            (uv[1] * self.height as f32).floor() as i64,
+        )
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct OpacityMicromapArrayData {
+    pub data: Vec<u8>,
+    pub descs: Vec<OpacityMicromapDesc>,
+    pub indices: Vec<i32>,
+    pub histogram: Vec<OpacityMicromapHistogramEntry>,
+}
+
+#[derive(Clone, Debug)]
+pub struct OpacityMicromapBaker {
+    subdivision_level: u32,
+    format: OpacityMicromapFormat,
+    alpha_cutoff: u8,
+    special_indices: bool,
+}
+
+impl OpacityMicromapBaker {
+    #[inline]
+    pub fn new(subdivision_level: u32, format: OpacityMicromapFormat) -> Self {
+        assert!(subdivision_level <= OPACITY_MICROMAP_MAX_SUBDIVISION_LEVEL);
+        Self {
+            subdivision_level,
+            format,
+            alpha_cutoff: 128,
+            special_indices: true,
+        }
+    }
+
+    #[inline]
+    pub fn alpha_cutoff(mut self, cutoff: f32) -> Self {
+        self.alpha_cutoff = (cutoff.clamp(0.0, 1.0) * 255.0).round() as u8;
+        self
+    }
+
+    #[inline]
+    pub fn special_indices(mut self, value: bool) -> Self {
+        self.special_indices = value;
+        self
+    }
+
+    #[inline]
+    pub fn bake(&self, mask: &AlphaMask, texcoords: &[[[f32; 2]; 3]]) -> OpacityMicromapArrayData {
+        let num_micro_triangles = 1usize << (2 * self.subdivision_level);
+        let bits = self.format.bits_per_micro_triangle() as usize;
+        let size = (num_micro_triangles * bits).div_ceil(8);
+        let mut result = OpacityMicromapArrayData::default();
+        let mut omms = std::collections::HashMap::<Vec<u8>, i32>::new();
+        let mut states = Vec::with_capacity(num_micro_triangles);
+        for tex in texcoords {
+            states.clear();
+            states.extend((0..num_micro_triangles as u32).map(|i| {
+                let bary = micro_triangle_barycentrics(i, self.subdivision_level);
+                let tri = bary.map(|[u, v]| {
+                    [
+                        tex[0][0] * (1.0 - u - v) + tex[1][0] * u + tex[2][0] * v,
+                        tex[0][1] * (1.0 - u - v) + tex[1][1] * u + tex[2][1] * v,
+                    ]
+                });
+                self.classify(mask, &tri)
+            }));
+            if self.special_indices && states.iter().all(|s| *s == states[0]) {
+                result.indices.push(match states[0] {
+                    OpacityState::Transparent => opacity_micromap_special_index::FULLY_TRANSPARENT,
+                    OpacityState::Opaque => opacity_micromap_special_index::FULLY_OPAQUE,
+                    OpacityState::UnknownTransparent => {
+                        opacity_micromap_special_index::FULLY_UNKNOWN_TRANSPARENT
+                    }
+                    OpacityState::UnknownOpaque => {
+                        opacity_micromap_special_index::FULLY_UNKNOWN_OPAQUE
+                    }
+                });
+                continue;
+            }
+            let mut data = vec![0u8; size];
+            for (i, state) in states.iter().enumerate() {
+                let bit = i * bits;
+                data[bit / 8] |= (*state as u8) << (bit % 8);
+            }
+            let index = *omms.entry(data).or_insert_with_key(|data| {
+                result.descs.push(OpacityMicromapDesc {
+                    byte_offset: result.data.len() as u32,
+                    subdivision_level: self.subdivision_level as u16,
+                    format: self.format as u16,
+                });
+                result.data.extend_from_slice(data);
+                result.descs.len() as i32 - 1
+            });
+            result.indices.push(index);
+        }
+        if !result.descs.is_empty() {
+            result.histogram.push(OpacityMicromapHistogramEntry {
+                count: result.descs.len() as u32,
+                subdivision_level: self.subdivision_level,
+                format: self.format,
+            });
+        }
+        result
+    }
+
+    fn classify(&self, mask: &AlphaMask, tri: &[[f32; 2]; 3]) -> OpacityState {
+        let (mut min, mut max) = ([f32::MAX; 2], [f32::MIN; 2]);
+        for p in tri {
+            for i in 0..2 {
+                min[i] = min[i].min(p[i]);
+                max[i] = max[i].max(p[i]);
+            }
+        }
+        let x0 = (min[0] * mask.width as f32).floor() as i64;
+        let x1 = ((max[0] * mask.width as f32).ceil() as i64 - 1).max(x0);
+        let y0 = (min[1] * mask.height as f32).floor() as i64;
+        let y1 = ((max[1] * mask.height as f32).ceil() as i64 - 1).max(y0);
+        let mut any_opaque = false;
+        let mut any_transparent = false;
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                if mask.texel(x, y) >= self.alpha_cutoff {
+                    any_opaque = true;
+                } else {
+                    any_transparent = true;
+                }
+            }
+        }
+        let centroid = [
+            (tri[0][0] + tri[1][0] + tri[2][0]) / 3.0,
+            (tri[0][1] + tri[1][1] + tri[2][1]) / 3.0,
+        ];
+        let centroid_opaque = mask.sample(centroid) >= self.alpha_cutoff;
+        match (any_opaque, any_transparent) {
+            (true, false) => OpacityState::Opaque,
+            (false, _) => OpacityState::Transparent,
+            (true, true) => match (self.format, centroid_opaque) {
+                (OpacityMicromapFormat::Oc1_4State, true) => OpacityState::UnknownOpaque,
+                (OpacityMicromapFormat::Oc1_4State, false) => OpacityState::UnknownTransparent,
+                (OpacityMicromapFormat::Oc1_2State, true) => OpacityState::Opaque,
+                (OpacityMicromapFormat::Oc1_2State, false) => OpacityState::Transparent,
+            },
+        }
+    }
+}
+
+fn extract_even_bits(mut x: u32) -> u32 {
+    x &= 0x55555555;
+    x = (x | (x >> 1)) & 0x33333333;
+    x = (x | (x >> 2)) & 0x0f0f0f0f;
+    x = (x | (x >> 4)) & 0x00ff00ff;
+    x = (x | (x >> 8)) & 0x0000ffff;
+    x
+}
+
+fn prefix_eor(mut x: u32) -> u32 {
+    x ^= x >> 1;
+    x ^= x >> 2;
+    x ^= x >> 4;
+    x ^= x >> 8;
+    x
+}
+
+pub fn micro_triangle_barycentrics(index: u32, subdivision_level: u32) -> [[f32; 2]; 3] {
+    if subdivision_level == 0 {
+        return [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]];
+    }
+    let b0 = extract_even_bits(index);
+    let b1 = extract_even_bits(index >> 1);
+    let fx = prefix_eor(b0);
+    let fy = prefix_eor(b0 & !b1);
+    let t = fy ^ b1;
+    let mask = (1 << subdivision_level) - 1;
+    let mut iu = ((fx & !t) | (b0 & !t) | (!b0 & !fx & t)) & mask;
+    let mut iv = (fy ^ b0) & mask;
+    let iw = ((!fx & !t) | (b0 & !t) | (!b0 & fx & t)) & mask;
+    let upright = ((iu & 1) ^ (iv & 1) ^ (iw & 1)) != 0;
+    if !upright {
+        iu += 1;
+        iv += 1;
+    }
+    let scale = 1.0 / (1u32 << subdivision_level) as f32;
+    let u = iu as f32 * scale;
+    let v = iv as f32 * scale;
+    if upright {
+        [[u, v], [u + scale, v], [u, v + scale]]
+    } else {
+        [[u, v], [u - scale, v], [u, v - scale]]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reflection::ShaderKind;
+
+    fn function(name: &str, kind: ShaderKind, payload: u32, attribute: u32) -> PipelineFunction {
+        PipelineFunction {
+            name: name.to_string(),
+            kind,
+            payload_size_in_bytes: Some(payload),
+            attribute_size_in_bytes: Some(attribute),
+        }
+    }
+
+    #[test]
+    fn default_hit_group_name_test() {
+        assert_eq!(default_hit_group_name("ClosestHit"), "HitGroup");
+        assert_eq!(
+            default_hit_group_name("Sphere_ClosestHit"),
+            "Sphere_HitGroup"
+        );
+        assert_eq!(
+            default_hit_group_name("SphereIntersection"),
+            "Sphere_HitGroup"
+        );
+        assert_eq!(default_hit_group_name("Shade"), "Shade_HitGroup");
+    }
+
+    #[test]
+    fn assemble_layout_test() {
+        let functions = [
+            function("RayGen", ShaderKind::RayGeneration, 0, 0),
+            function("Miss", ShaderKind::Miss, 16, 0),
+            function("Triangle_ClosestHit", ShaderKind::ClosestHit, 16, 8),
+            function("Sphere_ClosestHit", ShaderKind::ClosestHit, 16, 12),
+            function("Sphere_Intersection", ShaderKind::Intersection, 0, 12),
+            function("Shadow_AnyHit", ShaderKind::AnyHit, 4, 8),
+            function("ShadowMiss", ShaderKind::Miss, 4, 0),
+        ];
+        let explicit = [("Shadow".to_string(), vec!["Shadow_AnyHit".to_string()])];
+        let layout =
+            assemble_layout(&functions, &explicit, &default_hit_group_name, None, None).unwrap();
+        assert_eq!(layout.ray_generation_shaders, ["RayGen"]);
+        assert_eq!(layout.miss_shaders, ["Miss", "ShadowMiss"]);
+        assert_eq!(
+            layout.hit_groups,
+            [
+                RaytracingHitGroup {
+                    name: "Shadow".to_string(),
+                    ty: D3D12_HIT_GROUP_TYPE_TRIANGLES,
+                    closest_hit_shader: None,
+                    any_hit_shader: Some("Shadow_AnyHit".to_string()),
+                    intersection_shader: None,
+                },
+                RaytracingHitGroup {
+                    name: "Triangle_HitGroup".to_string(),
+                    ty: D3D12_HIT_GROUP_TYPE_TRIANGLES,
+                    closest_hit_shader: Some("Triangle_ClosestHit".to_string()),
+                    any_hit_shader: None,
+                    intersection_shader: None,
+                },
+                RaytracingHitGroup {
+                    name: "Sphere_HitGroup".to_string(),
+                    ty: D3D12_HIT_GROUP_TYPE_PROCEDURAL_PRIMITIVE,
+                    closest_hit_shader: Some("Sphere_ClosestHit".to_string()),
+                    any_hit_shader: None,
+                    intersection_shader: Some("Sphere_Intersection".to_string()),
+                },
+            ]
+        );
+        assert_eq!(layout.max_payload_size_in_bytes, 16);
+        assert_eq!(layout.max_attribute_size_in_bytes, 12);
+    }
+
+    #[test]
+    fn assemble_layout_errors_test() {
+        let mut functions = vec![
+            function("A_ClosestHit", ShaderKind::ClosestHit, 16, 8),
+            function("A_ClosestHit2", ShaderKind::ClosestHit, 16, 8),
+        ];
+        let rule = |_: &str| "A".to_string();
+        assert!(assemble_layout(&functions, &[], &rule, None, None).is_err());
+        functions.pop();
+        functions[0].payload_size_in_bytes = None;
+        assert!(assemble_layout(&functions, &[], &rule, None, None).is_err());
+        let layout = assemble_layout(&functions, &[], &rule, Some(32), None).unwrap();
+        assert_eq!(layout.max_payload_size_in_bytes, 32);
+    }
+
+    #[test]
+    fn micro_triangle_barycentrics_test() {
+        assert_eq!(
+            micro_triangle_barycentrics(0, 0),
+            [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]
+        );
+        for level in 1..5 {
+            let scale = (1u32 << level) as f32;
+            let mut centroids = std::collections::HashSet::new();
+            for i in 0..1u32 << (2 * level) {
+                let tri = micro_triangle_barycentrics(i, level);
+                assert!(
+                    tri.iter()
+                        .all(|p| p[0] >= 0.0 && p[1] >= 0.0 && p[0] + p[1] <= 1.0)
+                );
+                let centroid = (
+                    ((tri[0][0] + tri[1][0] + tri[2][0]) * 3.0 * scale).round() as i32,
+                    ((tri[0][1] + tri[1][1] + tri[2][1]) * 3.0 * scale).round() as i32,
+                );
+                assert!(centroids.insert(centroid));
+            }
+        }
+        assert_eq!(
+            micro_triangle_barycentrics(2, 1),
+            [[0.5, 0.0], [1.0, 0.0], [0.5, 0.5]]
+        );
+    }
+
+    #[test]
+    fn bake_opacity_micromap_test() {
+        let texcoords = [[[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]];
+        let opaque = [255u8; 16];
+        let transparent = [0u8; 16];
+        let baker = OpacityMicromapBaker::new(1, OpacityMicromapFormat::Oc1_4State);
+        let result = baker.bake(&AlphaMask::new(4, 4, &opaque), &texcoords);
+        assert_eq!(
+            result.indices,
+            [opacity_micromap_special_index::FULLY_OPAQUE]
+        );
+        assert!(result.descs.is_empty() && result.histogram.is_empty());
+        let result = baker.bake(&AlphaMask::new(4, 4, &transparent), &texcoords);
+        assert_eq!(
+            result.indices,
+            [opacity_micromap_special_index::FULLY_TRANSPARENT]
+        );
+
+        let half = (0..16)
+            .map(|i| if i % 4 < 2 { 255 } else { 0 })
+            .collect::<Vec<u8>>();
+        let mask = AlphaMask::new(4, 4, &half);
+        let result = baker.bake(&mask, &[texcoords[0], texcoords[0]]);
+        assert_eq!(result.indices, [0, 0]);
+        assert_eq!(result.data, [0b01_00_01_01]);
+        assert_eq!(
+            result.descs,
+            [OpacityMicromapDesc {
+                byte_offset: 0,
+                subdivision_level: 1,
+                format: OpacityMicromapFormat::Oc1_4State as u16,
+            }]
+        );
+        assert_eq!(
+            result.histogram,
+            [OpacityMicromapHistogramEntry {
+                count: 1,
+                subdivision_level: 1,
+                format: OpacityMicromapFormat::Oc1_4State,
+            }]
+        );
+        let result =
+            OpacityMicromapBaker::new(1, OpacityMicromapFormat::Oc1_2State).bake(&mask, &texcoords);
+        assert_eq!(result.data, [0b1011]);
+
+        let third = (0..9)
+            .map(|i| if i % 3 == 0 { 255 } else { 0 })
+            .collect::<Vec<u8>>();
+        let result = baker.bake(&AlphaMask::new(3, 3, &third), &texcoords);
+        assert_eq!(result.data[0] & 0b11, OpacityState::UnknownOpaque as u8);
+    }
+
+    #[test]
+    fn aabb_test() {
+        let aabb = RaytracingAabb::from_points(&[[1.0, -2.0, 3.0], [-1.0, 2.0, 0.0]]);
+        assert_eq!(aabb.min(), [-1.0, -2.0, 0.0]);
+        assert_eq!(aabb.max(), [1.0, 2.0, 3.0]);
+        let merged = aabb.union(&RaytracingAabb::new([0.0; 3], [4.0; 3]));
+        assert_eq!(merged.max(), [4.0, 4.0, 4.0]);
+        assert_eq!(std::mem::size_of::<OpacityMicromapDesc>(), 8);
+    }
+}
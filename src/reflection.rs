@@ -7,6 +7,7 @@ use windows::core::Interface;
 pub struct SignatureParameterDesc {
     pub semantic_name: String,
     pub semantic_index: u32,
+    pub register: u32,
     pub system_value_type: D3D_NAME,
     pub component_type: D3D_REGISTER_COMPONENT_TYPE,
     pub mask: u8,
@@ -53,6 +54,7 @@ pub enum MatrixMajor {
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
 pub enum Type {
     Void,
     Bool,
@@ -75,8 +77,15 @@ pub enum Type {
     Min16Int,
     Min12Int,
     Min16Uint,
+    Double,
+    Int64,
+    Uint64,
+    Float16,
+    Int16,
+    Uint16,
     Vector(Vector),
     Matrix(Matrix),
+    Array(Array),
     Struct(Struct),
     Unsupported,
 }
@@ -95,6 +104,12 @@ impl Type {
             D3D_SVT_MIN16INT => Self::Min16Int,
             D3D_SVT_MIN12INT => Self::Min12Int,
             D3D_SVT_MIN16UINT => Self::Min16Uint,
+            D3D_SVT_DOUBLE => Self::Double,
+            D3D_SVT_INT64 => Self::Int64,
+            D3D_SVT_UINT64 => Self::Uint64,
+            D3D_SVT_FLOAT16 => Self::Float16,
+            D3D_SVT_INT16 => Self::Int16,
+            D3D_SVT_UINT16 => Self::Uint16,
             _ => Self::Unsupported,
         }
     }
@@ -140,6 +155,12 @@ impl Type {
                 }),
                 _ => Self::Unsupported,
             };
+            if desc.Elements > 0 {
+                return Ok(Self::Array(Array {
+                    ty: Box::new(ret),
+                    len: desc.Elements as usize,
+                }));
+            }
             Ok(ret)
         }
     }
@@ -187,6 +208,12 @@ pub struct Matrix {
     pub major: MatrixMajor,
 }
 
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Array {
+    pub ty: Box<Type>,
+    pub len: usize,
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Member {
     pub name: String,
@@ -201,9 +228,42 @@ pub struct Struct {
 }
 
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub struct ShaderBufferDesc {
     pub name: String,
+    pub ty: D3D_CBUFFER_TYPE,
+    pub size: usize,
     pub members: Vec<Type>,
+    pub variables: Vec<Member>,
+}
+
+impl ShaderBufferDesc {
+    fn from_reflection(cb: &ID3D12ShaderReflectionConstantBuffer) -> windows::core::Result<Self> {
+        unsafe {
+            let mut cb_desc = D3D12_SHADER_BUFFER_DESC::default();
+            cb.GetDesc(&mut cb_desc)?;
+            let variables = (0..cb_desc.Variables)
+                .map(|i| -> windows::core::Result<Member> {
+                    let v = cb.GetVariableByIndex(i).unwrap();
+                    let mut vd = D3D12_SHADER_VARIABLE_DESC::default();
+                    v.GetDesc(&mut vd)?;
+                    let t = v.GetType().unwrap();
+                    Ok(Member {
+                        name: vd.Name.to_string()?,
+                        ty: Type::from_reflection(&t)?,
+                        offset: vd.StartOffset as usize,
+                    })
+                })
+                .collect::<windows::core::Result<Vec<Member>>>()?;
+            Ok(Self {
+                name: cb_desc.Name.to_string()?,
+                ty: cb_desc.Type,
+                size: cb_desc.Size as usize,
+                members: variables.iter().map(|v| v.ty.clone()).collect(),
+                variables,
+            })
+        }
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -269,6 +329,11 @@ impl ShaderReflection {
         }
     }
 
+    #[inline]
+    pub fn shader_kind(&self) -> windows::core::Result<ShaderKind> {
+        Ok(ShaderKind::from_raw(self.get_desc()?.Version >> 16))
+    }
+
     #[inline]
     pub fn min_feature_level(&self) -> windows::core::Result<D3D_FEATURE_LEVEL> {
         unsafe { self.0.GetMinFeatureLevel() }
@@ -295,6 +360,7 @@ impl ShaderReflection {
                     Ok(SignatureParameterDesc {
                         semantic_name: ipd.SemanticName.to_string()?,
                         semantic_index: ipd.SemanticIndex,
+                        register: ipd.Register,
                         system_value_type: ipd.SystemValueType,
                         component_type: ipd.ComponentType,
                         mask: ipd.Mask,
@@ -326,22 +392,8 @@ impl ShaderReflection {
         let desc = self.get_desc()?;
         unsafe {
             (0..desc.ConstantBuffers)
-                .map(|i| -> windows::core::Result<ShaderBufferDesc> {
-                    let cb = self.0.GetConstantBufferByIndex(i).unwrap();
-                    let mut cb_desc = D3D12_SHADER_BUFFER_DESC::default();
-                    cb.GetDesc(&mut cb_desc)?;
-                    Ok(ShaderBufferDesc {
-                        name: cb_desc.Name.to_string()?,
-                        members: (0..cb_desc.Variables)
-                            .map(|i| -> windows::core::Result<Type> {
-                                let v = cb.GetVariableByIndex(i).unwrap();
-                                let mut vd = D3D12_SHADER_VARIABLE_DESC::default();
-                                v.GetDesc(&mut vd)?;
-                                let t = v.GetType().unwrap();
-                                Type::from_reflection(&t)
-                            })
-                            .collect::<windows::core::Result<Vec<Type>>>()?,
-                    })
+                .map(|i| {
+                    ShaderBufferDesc::from_reflection(&self.0.GetConstantBufferByIndex(i).unwrap())
                 })
                 .collect()
         }
@@ -358,6 +410,33 @@ impl ShaderReflection {
                     Ok(SignatureParameterDesc {
                         semantic_name: spd.SemanticName.to_string()?,
                         semantic_index: spd.SemanticIndex,
+                        register: spd.Register,
+                        system_value_type: spd.SystemValueType,
+                        component_type: spd.ComponentType,
+                        mask: spd.Mask,
+                        read_write_mask: spd.ReadWriteMask,
+                        stream: spd.Stream,
+                        min_precision: spd.MinPrecision,
+                    })
+                })
+                .collect()
+        }
+    }
+
+    #[inline]
+    pub fn patch_constant_parameter_descs(
+        &self,
+    ) -> windows::core::Result<Vec<SignatureParameterDesc>> {
+        let desc = self.get_desc()?;
+        unsafe {
+            (0..desc.PatchConstantParameters)
+                .map(|i| -> windows::core::Result<SignatureParameterDesc> {
+                    let mut spd = D3D12_SIGNATURE_PARAMETER_DESC::default();
+                    self.0.GetPatchConstantParameterDesc(i, &mut spd)?;
+                    Ok(SignatureParameterDesc {
+                        semantic_name: spd.SemanticName.to_string()?,
+                        semantic_index: spd.SemanticIndex,
+                        register: spd.Register,
                         system_value_type: spd.SystemValueType,
                         component_type: spd.ComponentType,
                         mask: spd.Mask,
@@ -385,6 +464,64 @@ fn demangle(src: &str) -> &str {
     &s[..end]
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ShaderKind {
+    Pixel,
+    Vertex,
+    Geometry,
+    Hull,
+    Domain,
+    Compute,
+    Library,
+    RayGeneration,
+    Intersection,
+    AnyHit,
+    ClosestHit,
+    Miss,
+    Callable,
+    Mesh,
+    Amplification,
+    Node,
+    Unknown(u32),
+}
+
+impl ShaderKind {
+    fn from_raw(value: u32) -> Self {
+        match D3D12_SHADER_VERSION_TYPE(value as i32) {
+            D3D12_SHVER_PIXEL_SHADER => Self::Pixel,
+            D3D12_SHVER_VERTEX_SHADER => Self::Vertex,
+            D3D12_SHVER_GEOMETRY_SHADER => Self::Geometry,
+            D3D12_SHVER_HULL_SHADER => Self::Hull,
+            D3D12_SHVER_DOMAIN_SHADER => Self::Domain,
+            D3D12_SHVER_COMPUTE_SHADER => Self::Compute,
+            D3D12_SHVER_LIBRARY => Self::Library,
+            D3D12_SHVER_RAY_GENERATION_SHADER => Self::RayGeneration,
+            D3D12_SHVER_INTERSECTION_SHADER => Self::Intersection,
+            D3D12_SHVER_ANY_HIT_SHADER => Self::AnyHit,
+            D3D12_SHVER_CLOSEST_HIT_SHADER => Self::ClosestHit,
+            D3D12_SHVER_MISS_SHADER => Self::Miss,
+            D3D12_SHVER_CALLABLE_SHADER => Self::Callable,
+            D3D12_SHVER_MESH_SHADER => Self::Mesh,
+            D3D12_SHVER_AMPLIFICATION_SHADER => Self::Amplification,
+            D3D12_SHVER_NODE_SHADER => Self::Node,
+            _ => Self::Unknown(value),
+        }
+    }
+
+    #[inline]
+    pub fn is_raytracing(&self) -> bool {
+        matches!(
+            self,
+            Self::RayGeneration
+                | Self::Intersection
+                | Self::AnyHit
+                | Self::ClosestHit
+                | Self::Miss
+                | Self::Callable
+        )
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct FunctionParameter {
     pub name: String,
@@ -412,6 +549,11 @@ impl Function {
         }
     }
 
+    #[inline]
+    pub fn shader_kind(&self) -> ShaderKind {
+        ShaderKind::from_raw(self.get_desc().Version >> 16)
+    }
+
     #[inline]
     pub fn parameters(&self) -> impl Iterator<Item = FunctionParameter> + '_ {
         let desc = self.get_desc();
@@ -450,22 +592,10 @@ impl Function {
         let desc = self.get_desc();
         unsafe {
             (0..desc.ConstantBuffers)
-                .map(|i| -> windows::core::Result<ShaderBufferDesc> {
-                    let cb = self.object.GetConstantBufferByIndex(i).unwrap();
-                    let mut cb_desc = D3D12_SHADER_BUFFER_DESC::default();
-                    cb.GetDesc(&mut cb_desc).unwrap();
-                    Ok(ShaderBufferDesc {
-                        name: cb_desc.Name.to_string()?,
-                        members: (0..cb_desc.Variables)
-                            .map(|i| -> windows::core::Result<Type> {
-                                let v = cb.GetVariableByIndex(i).unwrap();
-                                let mut vd = D3D12_SHADER_VARIABLE_DESC::default();
-                                v.GetDesc(&mut vd)?;
-                                let t = v.GetType().unwrap();
-                                Type::from_reflection(&t)
-                            })
-                            .collect::<windows::core::Result<Vec<Type>>>()?,
-                    })
+                .map(|i| {
+                    ShaderBufferDesc::from_reflection(
+                        &self.object.GetConstantBufferByIndex(i).unwrap(),
+                    )
                 })
                 .filter_map(|desc| desc.ok())
         }
@@ -530,3 +660,347 @@ impl ReflectionType for LibraryReflection {
         Self(this)
     }
 }
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RuntimeFunctionInfo {
+    pub name: String,
+    pub unmangled_name: String,
+    pub shader_kind: ShaderKind,
+    pub payload_size_in_bytes: u32,
+    pub attribute_size_in_bytes: u32,
+    pub node: Option<NodeInfo>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct NodeRecordLayout {
+    pub size_in_bytes: u32,
+    pub alignment_in_bytes: u32,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct NodeInfo {
+    pub name: String,
+    pub array_index: u32,
+    pub input: Option<NodeRecordLayout>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RuntimeData {
+    functions: Vec<RuntimeFunctionInfo>,
+}
+
+impl RuntimeData {
+    const STRING_BUFFER: u32 = 1;
+    const INDEX_ARRAYS: u32 = 2;
+    const FUNCTION_TABLE: u32 = 4;
+    const NODE_ID_TABLE: u32 = 7;
+    const NODE_SHADER_IO_ATTRIB_TABLE: u32 = 8;
+    const NODE_SHADER_FUNC_ATTRIB_TABLE: u32 = 9;
+    const IO_NODE_TABLE: u32 = 10;
+    const NODE_SHADER_INFO_TABLE: u32 = 11;
+
+    const NULL_REF: u32 = u32::MAX;
+    const NODE_FUNC_ATTRIB_ID: u32 = 1;
+    const NODE_IO_ATTRIB_RECORD_SIZE: u32 = 4;
+    const NODE_IO_ATTRIB_RECORD_ALIGNMENT: u32 = 8;
+
+    #[inline]
+    pub fn new(container: &[u8]) -> Option<Self> {
+        Self::from_part(find_container_part(container, dxc::PartKind::RUNTIME_DATA)?)
+    }
+
+    #[inline]
+    pub fn from_part(data: &[u8]) -> Option<Self> {
+        let version = read_u32(data, 0)?;
+        if version != 0x10 {
+            return None;
+        }
+        let part_count = read_u32(data, 4)? as usize;
+        let mut parts = RuntimeDataParts::default();
+        for i in 0..part_count {
+            let offset = read_u32(data, 8 + i * 4)? as usize;
+            let ty = read_u32(data, offset)?;
+            let size = read_u32(data, offset + 4)? as usize;
+            let part = data.get(offset + 8..offset + 8 + size)?;
+            match ty {
+                Self::STRING_BUFFER => parts.strings = part,
+                Self::INDEX_ARRAYS => parts.index_arrays = part,
+                Self::FUNCTION_TABLE => parts.functions = part,
+                Self::NODE_ID_TABLE => parts.node_ids = part,
+                Self::NODE_SHADER_IO_ATTRIB_TABLE => parts.node_io_attribs = part,
+                Self::NODE_SHADER_FUNC_ATTRIB_TABLE => parts.node_func_attribs = part,
+                Self::IO_NODE_TABLE => parts.io_nodes = part,
+                Self::NODE_SHADER_INFO_TABLE => parts.node_infos = part,
+                _ => {}
+            }
+        }
+        let mut functions = vec![];
+        if !parts.functions.is_empty() {
+            let record_count = read_u32(parts.functions, 0)? as usize;
+            if read_u32(parts.functions, 4)? < 7 * 4 {
+                return None;
+            }
+            for i in 0..record_count {
+                let record = table_record(parts.functions, i as u32)?;
+                let unmangled_name = read_str(parts.strings, read_u32(record, 4)?)?;
+                let shader_kind = ShaderKind::from_raw(read_u32(record, 16)?);
+                let node = match (shader_kind, read_u32(record, 48)) {
+                    (ShaderKind::Node, Some(index)) if index != Self::NULL_REF => {
+                        Some(parts.node(index, &unmangled_name)?)
+                    }
+                    _ => None,
+                };
+                functions.push(RuntimeFunctionInfo {
+                    name: read_str(parts.strings, read_u32(record, 0)?)?,
+                    unmangled_name,
+                    shader_kind,
+                    payload_size_in_bytes: read_u32(record, 20)?,
+                    attribute_size_in_bytes: read_u32(record, 24)?,
+                    node,
+                });
+            }
+        }
+        Some(Self { functions })
+    }
+
+    #[inline]
+    pub fn functions(&self) -> &[RuntimeFunctionInfo] {
+        &self.functions
+    }
+
+    #[inline]
+    pub fn node(&self, name: &str, array_index: u32) -> Option<&NodeInfo> {
+        self.functions
+            .iter()
+            .filter_map(|f| f.node.as_ref())
+            .find(|node| node.name == name && node.array_index == array_index)
+    }
+}
+
+#[derive(Default)]
+struct RuntimeDataParts<'a> {
+    strings: &'a [u8],
+    index_arrays: &'a [u8],
+    functions: &'a [u8],
+    node_ids: &'a [u8],
+    node_io_attribs: &'a [u8],
+    node_func_attribs: &'a [u8],
+    io_nodes: &'a [u8],
+    node_infos: &'a [u8],
+}
+
+impl RuntimeDataParts<'_> {
+    fn index_array(&self, index: u32) -> Option<Vec<u32>> {
+        if index == RuntimeData::NULL_REF {
+            return Some(vec![]);
+        }
+        let index = index as usize * 4;
+        let len = read_u32(self.index_arrays, index)? as usize;
+        (0..len)
+            .map(|i| read_u32(self.index_arrays, index + (i + 1) * 4))
+            .collect()
+    }
+
+    fn attribs(&self, table: &[u8], index: u32) -> Option<Vec<(u32, u32)>> {
+        self.index_array(index)?
+            .into_iter()
+            .map(|i| {
+                let record = table_record(table, i)?;
+                Some((read_u32(record, 0)?, read_u32(record, 4)?))
+            })
+            .collect()
+    }
+
+    fn node(&self, index: u32, unmangled_name: &str) -> Option<NodeInfo> {
+        let info = table_record(self.node_infos, index)?;
+        let mut node = NodeInfo {
+            name: unmangled_name.to_string(),
+            array_index: 0,
+            input: None,
+        };
+        for (kind, value) in self.attribs(self.node_func_attribs, read_u32(info, 8)?)? {
+            if kind == RuntimeData::NODE_FUNC_ATTRIB_ID {
+                let id = table_record(self.node_ids, value)?;
+                node.name = read_str(self.strings, read_u32(id, 0)?)?;
+                node.array_index = read_u32(id, 4)?;
+            }
+        }
+        if let Some(&input) = self.index_array(read_u32(info, 16)?)?.first() {
+            let io_node = table_record(self.io_nodes, input)?;
+            let mut layout = NodeRecordLayout::default();
+            for (kind, value) in self.attribs(self.node_io_attribs, read_u32(io_node, 4)?)? {
+                match kind {
+                    RuntimeData::NODE_IO_ATTRIB_RECORD_SIZE => layout.size_in_bytes = value,
+                    RuntimeData::NODE_IO_ATTRIB_RECORD_ALIGNMENT => {
+                        layout.alignment_in_bytes = value
+                    }
+                    _ => {}
+                }
+            }
+            node.input = Some(layout);
+        }
+        Some(node)
+    }
+}
+
+fn table_record(table: &[u8], index: u32) -> Option<&[u8]> {
+    let count = read_u32(table, 0)?;
+    let stride = read_u32(table, 4)? as usize;
+    if index >= count {
+        return None;
+    }
+    let start = 8 + index as usize * stride;
+    table.get(start..start + stride)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn read_str(strings: &[u8], offset: u32) -> Option<String> {
+    let s = strings.get(offset as usize..)?;
+    let end = s.iter().position(|&c| c == 0)?;
+    Some(String::from_utf8_lossy(&s[..end]).into_owned())
+}
+
+fn find_container_part(container: &[u8], kind: dxc::PartKind) -> Option<&[u8]> {
+    if read_u32(container, 0)? != dxc::PartKind::DXBC.0 {
+        return None;
+    }
+    let part_count = read_u32(container, 28)? as usize;
+    for i in 0..part_count {
+        let offset = read_u32(container, 32 + i * 4)? as usize;
+        let size = read_u32(container, offset + 4)? as usize;
+        if read_u32(container, offset)? == kind.0 {
+            return container.get(offset + 8..offset + 8 + size);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_u32(v: &mut Vec<u8>, value: u32) {
+        v.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn table(stride: u32, records: &[&[u32]]) -> Vec<u8> {
+        let mut table = vec![];
+        push_u32(&mut table, records.len() as u32);
+        push_u32(&mut table, stride);
+        for value in records.iter().flat_map(|record| record.iter()) {
+            push_u32(&mut table, *value);
+        }
+        table
+    }
+
+    fn rdat_part(tables: &[(u32, Vec<u8>)]) -> Vec<u8> {
+        let mut part = vec![];
+        push_u32(&mut part, 0x10);
+        push_u32(&mut part, tables.len() as u32);
+        let mut offset = 8 + tables.len() * 4;
+        for (_, data) in tables {
+            push_u32(&mut part, offset as u32);
+            offset += 8 + data.len();
+        }
+        for (ty, data) in tables {
+            push_u32(&mut part, *ty);
+            push_u32(&mut part, data.len() as u32);
+            part.extend_from_slice(data);
+        }
+        part
+    }
+
+    fn runtime_data_part() -> Vec<u8> {
+        let strings = b"\x01?Hit@@YAXUPayload@@UAttributes@@@Z\0Hit\0";
+        rdat_part(&[
+            (RuntimeData::STRING_BUFFER, strings.to_vec()),
+            (
+                RuntimeData::FUNCTION_TABLE,
+                table(
+                    11 * 4,
+                    &[&[0, 36, u32::MAX, u32::MAX, 10, 16, 8, 0, 0, 0, 0]],
+                ),
+            ),
+        ])
+    }
+
+    #[test]
+    fn runtime_data_test() {
+        let part = runtime_data_part();
+        let mut container = vec![];
+        push_u32(&mut container, dxc::PartKind::DXBC.0);
+        container.extend_from_slice(&[0; 16]);
+        push_u32(&mut container, 1);
+        push_u32(&mut container, (32 + 4 + 8 + part.len()) as u32);
+        push_u32(&mut container, 1);
+        push_u32(&mut container, 36);
+        push_u32(&mut container, dxc::PartKind::RUNTIME_DATA.0);
+        push_u32(&mut container, part.len() as u32);
+        container.extend_from_slice(&part);
+        let runtime_data = RuntimeData::new(&container).unwrap();
+        assert_eq!(
+            runtime_data.functions(),
+            [RuntimeFunctionInfo {
+                name: "\u{1}?Hit@@YAXUPayload@@UAttributes@@@Z".to_string(),
+                unmangled_name: "Hit".to_string(),
+                shader_kind: ShaderKind::ClosestHit,
+                payload_size_in_bytes: 16,
+                attribute_size_in_bytes: 8,
+                node: None,
+            }]
+        );
+        assert!(RuntimeData::new(&part).is_none());
+        assert!(RuntimeData::from_part(&part[..20]).is_none());
+
+        let node_kind = D3D12_SHVER_NODE_SHADER.0 as u32;
+        let part = rdat_part(&[
+            (RuntimeData::STRING_BUFFER, b"Main\0entry\0".to_vec()),
+            (
+                RuntimeData::INDEX_ARRAYS,
+                [1, 0, 1, 0, 2, 0, 1]
+                    .into_iter()
+                    .flat_map(u32::to_le_bytes)
+                    .collect(),
+            ),
+            (
+                RuntimeData::FUNCTION_TABLE,
+                table(
+                    13 * 4,
+                    &[&[0, 0, u32::MAX, u32::MAX, node_kind, 0, 0, 0, 0, 0, 0, 0, 0]],
+                ),
+            ),
+            (RuntimeData::NODE_ID_TABLE, table(8, &[&[5, 2]])),
+            (
+                RuntimeData::NODE_SHADER_IO_ATTRIB_TABLE,
+                table(8, &[&[4, 24], &[8, 8]]),
+            ),
+            (
+                RuntimeData::NODE_SHADER_FUNC_ATTRIB_TABLE,
+                table(8, &[&[1, 0]]),
+            ),
+            (RuntimeData::IO_NODE_TABLE, table(8, &[&[1, 4]])),
+            (
+                RuntimeData::NODE_SHADER_INFO_TABLE,
+                table(20, &[&[1, 0, 0, u32::MAX, 2]]),
+            ),
+        ]);
+        let runtime_data = RuntimeData::from_part(&part).unwrap();
+        let node = NodeInfo {
+            name: "entry".to_string(),
+            array_index: 2,
+            input: Some(NodeRecordLayout {
+                size_in_bytes: 24,
+                alignment_in_bytes: 8,
+            }),
+        };
+        assert_eq!(runtime_data.functions()[0].shader_kind, ShaderKind::Node);
+        assert_eq!(runtime_data.functions()[0].node.as_ref(), Some(&node));
+        assert_eq!(runtime_data.node("entry", 2), Some(&node));
+        assert_eq!(runtime_data.node("entry", 0), None);
+        assert_eq!(runtime_data.node("Main", 0), None);
+    }
+}
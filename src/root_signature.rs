@@ -177,6 +177,18 @@ where
     }
 }
 
+impl<T> RootParameter<T> {
+    #[inline]
+    pub(crate) fn param(&self) -> &D3D12_ROOT_PARAMETER {
+        &self.param
+    }
+
+    #[inline]
+    pub(crate) fn descriptor_ranges(&self) -> &[D3D12_DESCRIPTOR_RANGE] {
+        self.ranges.as_deref().unwrap_or_default()
+    }
+}
+
 impl RootParameter<root_parameter_type::DescriptorTable> {
     #[inline]
     pub fn ranges(
@@ -222,6 +234,11 @@ impl RootParameter<root_parameter_type::Constants32bit> {
         self.param.Anonymous.Constants.Num32BitValues = n;
         self
     }
+
+    #[inline]
+    pub fn constants<T: crate::layout::RootConstants>(self) -> Self {
+        self.num_32bit_values(T::NUM_32BIT_VALUES)
+    }
 }
 
 impl RootParameter<root_parameter_type::Cbv> {
@@ -383,6 +400,11 @@ impl StaticSamplerDesc {
         self.0.ShaderVisibility = visibility;
         self
     }
+
+    #[inline]
+    pub(crate) fn desc(&self) -> &D3D12_STATIC_SAMPLER_DESC {
+        &self.0
+    }
 }
 
 impl Default for StaticSamplerDesc {
@@ -438,6 +460,21 @@ impl<'params, 'samplers> RootSignatureDesc<'params, 'samplers> {
         self.flags = flags;
         self
     }
+
+    #[inline]
+    pub(crate) fn root_parameters(&self) -> &'params [RootParameter<()>] {
+        self.params.unwrap_or_default()
+    }
+
+    #[inline]
+    pub(crate) fn samplers(&self) -> &'samplers [StaticSamplerDesc] {
+        self.samplers.unwrap_or_default()
+    }
+
+    #[inline]
+    pub(crate) fn root_signature_flags(&self) -> D3D12_ROOT_SIGNATURE_FLAGS {
+        self.flags
+    }
 }
 
 pub struct Builder<Desc = ()> {
@@ -0,0 +1,696 @@
+use crate::dxc::ShaderHash;
+use crate::permutation::PermutationKey;
+use crate::pipeline_state::ShaderBytecode;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+pub const FORMAT_VERSION: u32 = 1;
+pub const MAGIC: [u8; 4] = *b"DXAR";
+
+const HEADER_SIZE: usize = 32;
+const BLOB_REF_SIZE: usize = 32;
+const ENTRY_SIZE: usize = 16 + 8 + BLOB_REF_SIZE * 2 + 24;
+const DEFAULT_ALIGNMENT: u32 = 16;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(u32)]
+pub enum Compression {
+    None = 0,
+    Lz4 = 1,
+}
+
+impl Compression {
+    fn from_raw(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::None),
+            1 => Some(Self::Lz4),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ShaderArchiveError {
+    InvalidMagic,
+    UnsupportedVersion(u32),
+    InvalidHeader,
+    InvalidEntry(usize),
+    UnsortedEntries,
+    Compressed,
+    InvalidCompressedData,
+}
+
+impl std::fmt::Display for ShaderArchiveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidMagic => write!(f, "not a shader archive"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported shader archive version {version}")
+            }
+            Self::InvalidHeader => write!(f, "invalid shader archive header"),
+            Self::InvalidEntry(index) => write!(f, "invalid shader archive entry {index}"),
+            Self::UnsortedEntries => write!(f, "shader archive entries are not sorted"),
+            Self::Compressed => write!(f, "blob is compressed"),
+            Self::InvalidCompressedData => write!(f, "invalid compressed data"),
+        }
+    }
+}
+
+impl std::error::Error for ShaderArchiveError {}
+
+// LZ4 block format
+mod lz4 {
+    const MIN_MATCH: usize = 4;
+    const LAST_LITERALS: usize = 5;
+    const MF_LIMIT: usize = 12;
+    const HASH_BITS: u32 = 12;
+    const MAX_OFFSET: usize = 65535;
+
+    fn push_length(dst: &mut Vec<u8>, mut len: usize) {
+        while len >= 255 {
+            dst.push(255);
+            len -= 255;
+        }
+        dst.push(len as u8);
+    }
+
+    fn push_sequence(dst: &mut Vec<u8>, literals: &[u8], matched: Option<(usize, usize)>) {
+        let literal_token = literals.len().min(15) as u8;
+        let match_token = matched.map_or(0, |(_, len)| (len - MIN_MATCH).min(15) as u8);
+        dst.push((literal_token << 4) | match_token);
+        if literals.len() >= 15 {
+            push_length(dst, literals.len() - 15);
+        }
+        dst.extend_from_slice(literals);
+        if let Some((offset, len)) = matched {
+            dst.extend_from_slice(&(offset as u16).to_le_bytes());
+            if len - MIN_MATCH >= 15 {
+                push_length(dst, len - MIN_MATCH - 15);
+            }
+        }
+    }
+
+    fn read_u32(src: &[u8], i: usize) -> u32 {
+        u32::from_le_bytes(src[i..i + 4].try_into().unwrap())
+    }
+
+    pub fn compress(src: &[u8]) -> Vec<u8> {
+        let mut dst = Vec::with_capacity(src.len() / 2);
+        let mut table = vec![usize::MAX; 1 << HASH_BITS];
+        let mut anchor = 0;
+        let mut i = 0;
+        while src.len() >= MF_LIMIT && i < src.len() - MF_LIMIT {
+            let h = (read_u32(src, i).wrapping_mul(2654435761) >> (32 - HASH_BITS)) as usize;
+            let candidate = std::mem::replace(&mut table[h], i);
+            if candidate == usize::MAX
+                || i - candidate > MAX_OFFSET
+                || read_u32(src, candidate) != read_u32(src, i)
+            {
+                i += 1;
+                continue;
+            }
+            let limit = src.len() - LAST_LITERALS;
+            let mut len = MIN_MATCH;
+            while i + len < limit && src[candidate + len] == src[i + len] {
+                len += 1;
+            }
+            push_sequence(&mut dst, &src[anchor..i], Some((i - candidate, len)));
+            i += len;
+            anchor = i;
+        }
+        push_sequence(&mut dst, &src[anchor..], None);
+        dst
+    }
+
+    fn read_length(src: &[u8], pos: &mut usize, mut len: usize) -> Option<usize> {
+        if len == 15 {
+            loop {
+                let b = *src.get(*pos)?;
+                *pos += 1;
+                len = len.checked_add(b as usize)?;
+                if b != 255 {
+                    break;
+                }
+            }
+        }
+        Some(len)
+    }
+
+    pub fn decompress(src: &[u8], size: usize) -> Option<Vec<u8>> {
+        let mut dst = Vec::with_capacity(size.min(src.len().saturating_mul(255)));
+        let mut pos = 0;
+        loop {
+            let token = *src.get(pos)?;
+            pos += 1;
+            let literals = read_length(src, &mut pos, (token >> 4) as usize)?;
+            let end = pos.checked_add(literals)?;
+            if dst.len() + literals > size {
+                return None;
+            }
+            dst.extend_from_slice(src.get(pos..end)?);
+            pos = end;
+            if pos == src.len() {
+                break;
+            }
+            let offset = u16::from_le_bytes(src.get(pos..pos + 2)?.try_into().unwrap()) as usize;
+            pos += 2;
+            if offset == 0 || offset > dst.len() {
+                return None;
+            }
+            let len = read_length(src, &mut pos, (token & 0xf) as usize)?.checked_add(MIN_MATCH)?;
+            if dst.len() + len > size {
+                return None;
+            }
+            let start = dst.len() - offset;
+            for i in 0..len {
+                dst.push(dst[start + i]);
+            }
+        }
+        (dst.len() == size).then_some(dst)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct PendingEntry {
+    object: Vec<u8>,
+    reflection: Option<Vec<u8>>,
+    shader_hash: Option<ShaderHash>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ShaderArchiveWriter {
+    entries: BTreeMap<(String, PermutationKey), PendingEntry>,
+    alignment: u32,
+    compression: Compression,
+}
+
+impl ShaderArchiveWriter {
+    #[inline]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            alignment: DEFAULT_ALIGNMENT,
+            compression: Compression::None,
+        }
+    }
+
+    #[inline]
+    pub fn alignment(mut self, alignment: u32) -> Self {
+        assert!(alignment.is_power_of_two());
+        self.alignment = alignment;
+        self
+    }
+
+    #[inline]
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    #[inline]
+    pub fn add(
+        &mut self,
+        name: impl AsRef<str>,
+        key: PermutationKey,
+        object: &[u8],
+        reflection: Option<&[u8]>,
+        shader_hash: Option<ShaderHash>,
+    ) {
+        self.entries.insert(
+            (name.as_ref().to_string(), key),
+            PendingEntry {
+                object: object.to_vec(),
+                reflection: reflection.map(|data| data.to_vec()),
+                shader_hash,
+            },
+        );
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn encode_blob<'a>(&self, blob: &'a [u8]) -> (Compression, Cow<'a, [u8]>) {
+        match self.compression {
+            Compression::None => (Compression::None, Cow::Borrowed(blob)),
+            Compression::Lz4 => {
+                let compressed = lz4::compress(blob);
+                if compressed.len() < blob.len() {
+                    (Compression::Lz4, Cow::Owned(compressed))
+                } else {
+                    (Compression::None, Cow::Borrowed(blob))
+                }
+            }
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let alignment = self.alignment as usize;
+        let toc_size = ENTRY_SIZE * self.entries.len();
+        let mut data = vec![0u8; HEADER_SIZE + toc_size];
+        let push_blob = |data: &mut Vec<u8>, blob: &[u8]| -> (u64, u64) {
+            data.resize(data.len().next_multiple_of(alignment), 0);
+            let offset = data.len() as u64;
+            data.extend_from_slice(blob);
+            (offset, blob.len() as u64)
+        };
+        let mut toc = Vec::with_capacity(toc_size);
+        for ((name, key), entry) in &self.entries {
+            let (name_offset, name_len) = push_blob(&mut data, name.as_bytes());
+            toc.extend_from_slice(&name_offset.to_le_bytes());
+            toc.extend_from_slice(&name_len.to_le_bytes());
+            toc.extend_from_slice(&key.0.to_le_bytes());
+            for blob in [Some(entry.object.as_slice()), entry.reflection.as_deref()] {
+                let (compression, encoded) = blob
+                    .map_or((Compression::None, Cow::default()), |blob| {
+                        self.encode_blob(blob)
+                    });
+                let (offset, size) = blob.map_or((0, 0), |_| push_blob(&mut data, &encoded));
+                toc.extend_from_slice(&offset.to_le_bytes());
+                toc.extend_from_slice(&size.to_le_bytes());
+                toc.extend_from_slice(&(blob.map_or(0, |blob| blob.len()) as u64).to_le_bytes());
+                toc.extend_from_slice(&(compression as u32).to_le_bytes());
+                toc.extend_from_slice(&(blob.is_some() as u32).to_le_bytes());
+            }
+            let hash = entry.shader_hash.as_ref();
+            toc.extend_from_slice(&(hash.is_some() as u32).to_le_bytes());
+            toc.extend_from_slice(&hash.map_or(0, |hash| hash.flags).to_le_bytes());
+            toc.extend_from_slice(&hash.map_or([0; 16], |hash| hash.digest));
+        }
+        let mut header = Vec::with_capacity(HEADER_SIZE);
+        header.extend_from_slice(&MAGIC);
+        header.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        header.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+        header.extend_from_slice(&self.alignment.to_le_bytes());
+        header.extend_from_slice(&(HEADER_SIZE as u64).to_le_bytes());
+        header.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        data[..HEADER_SIZE].copy_from_slice(&header);
+        data[HEADER_SIZE..HEADER_SIZE + toc_size].copy_from_slice(&toc);
+        data
+    }
+
+    #[inline]
+    pub fn write(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
+        w.write_all(&self.to_bytes())
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn slice_at(data: &[u8], offset: u64, size: u64) -> Option<&[u8]> {
+    let offset = usize::try_from(offset).ok()?;
+    let end = offset.checked_add(usize::try_from(size).ok()?)?;
+    data.get(offset..end)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct BlobRef<'a> {
+    data: &'a [u8],
+    raw_size: usize,
+    compression: Compression,
+}
+
+impl<'a> BlobRef<'a> {
+    fn parse(data: &'a [u8], raw: &[u8]) -> Option<Option<Self>> {
+        let present = read_u32(raw, 28);
+        let compression = Compression::from_raw(read_u32(raw, 24))?;
+        let blob = slice_at(data, read_u64(raw, 0), read_u64(raw, 8))?;
+        let raw_size = usize::try_from(read_u64(raw, 16)).ok()?;
+        match (present, compression) {
+            (0, _) => Some(None),
+            (1, Compression::None) if raw_size == blob.len() => Some(Some(Self {
+                data: blob,
+                raw_size,
+                compression,
+            })),
+            (1, Compression::Lz4) => Some(Some(Self {
+                data: blob,
+                raw_size,
+                compression,
+            })),
+            _ => None,
+        }
+    }
+
+    fn decode(&self) -> Result<Cow<'a, [u8]>, ShaderArchiveError> {
+        match self.compression {
+            Compression::None => Ok(Cow::Borrowed(self.data)),
+            Compression::Lz4 => lz4::decompress(self.data, self.raw_size)
+                .map(Cow::Owned)
+                .ok_or(ShaderArchiveError::InvalidCompressedData),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ShaderArchiveEntry<'a> {
+    name: &'a str,
+    key: PermutationKey,
+    object: BlobRef<'a>,
+    reflection: Option<BlobRef<'a>>,
+    shader_hash: Option<(u32, [u8; 16])>,
+}
+
+impl<'a> ShaderArchiveEntry<'a> {
+    fn parse(data: &'a [u8], raw: &[u8]) -> Option<Self> {
+        let name = std::str::from_utf8(slice_at(data, read_u64(raw, 0), read_u64(raw, 8))?).ok()?;
+        let key = PermutationKey(read_u64(raw, 16));
+        let object = BlobRef::parse(data, &raw[24..24 + BLOB_REF_SIZE])??;
+        let reflection = BlobRef::parse(data, &raw[56..56 + BLOB_REF_SIZE])?;
+        let shader_hash = match read_u32(raw, 88) {
+            0 => None,
+            1 => Some((read_u32(raw, 92), raw[96..112].try_into().unwrap())),
+            _ => return None,
+        };
+        Some(Self {
+            name,
+            key,
+            object,
+            reflection,
+            shader_hash,
+        })
+    }
+
+    #[inline]
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    #[inline]
+    pub fn key(&self) -> PermutationKey {
+        self.key
+    }
+
+    #[inline]
+    pub fn is_compressed(&self) -> bool {
+        self.object.compression != Compression::None
+    }
+
+    #[inline]
+    pub fn object(&self) -> Result<ShaderBytecode<'a>, ShaderArchiveError> {
+        match self.object.compression {
+            Compression::None => Ok(ShaderBytecode::new(self.object.data)),
+            _ => Err(ShaderArchiveError::Compressed),
+        }
+    }
+
+    #[inline]
+    pub fn object_data(&self) -> Result<Cow<'a, [u8]>, ShaderArchiveError> {
+        self.object.decode()
+    }
+
+    #[inline]
+    pub fn reflection_data(&self) -> Result<Option<Cow<'a, [u8]>>, ShaderArchiveError> {
+        self.reflection
+            .as_ref()
+            .map(|blob| blob.decode())
+            .transpose()
+    }
+
+    #[inline]
+    pub fn shader_hash(&self) -> Option<ShaderHash> {
+        self.shader_hash
+            .map(|(flags, digest)| ShaderHash { flags, digest })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ShaderArchive<'a> {
+    data: &'a [u8],
+    toc: &'a [u8],
+    len: usize,
+}
+
+impl<'a> ShaderArchive<'a> {
+    pub fn new(data: &'a [u8]) -> Result<Self, ShaderArchiveError> {
+        let header = data
+            .get(..HEADER_SIZE)
+            .ok_or(ShaderArchiveError::InvalidHeader)?;
+        if header[..4] != MAGIC {
+            return Err(ShaderArchiveError::InvalidMagic);
+        }
+        let version = read_u32(header, 4);
+        if version != FORMAT_VERSION {
+            return Err(ShaderArchiveError::UnsupportedVersion(version));
+        }
+        let len = read_u32(header, 8) as usize;
+        let file_size =
+            usize::try_from(read_u64(header, 24)).map_err(|_| ShaderArchiveError::InvalidHeader)?;
+        let data = data
+            .get(..file_size)
+            .ok_or(ShaderArchiveError::InvalidHeader)?;
+        let toc = len
+            .checked_mul(ENTRY_SIZE)
+            .and_then(|size| slice_at(data, read_u64(header, 16), size as u64))
+            .ok_or(ShaderArchiveError::InvalidHeader)?;
+        let archive = Self { data, toc, len };
+        let mut prev: Option<ShaderArchiveEntry> = None;
+        for index in 0..len {
+            let entry = archive
+                .entry(index)
+                .ok_or(ShaderArchiveError::InvalidEntry(index))?;
+            if prev.is_some_and(|prev| (prev.name, prev.key) >= (entry.name, entry.key)) {
+                return Err(ShaderArchiveError::UnsortedEntries);
+            }
+            prev = Some(entry);
+        }
+        Ok(archive)
+    }
+
+    fn entry(&self, index: usize) -> Option<ShaderArchiveEntry<'a>> {
+        let raw = &self.toc[index * ENTRY_SIZE..(index + 1) * ENTRY_SIZE];
+        ShaderArchiveEntry::parse(self.data, raw)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    pub fn entries(&self) -> impl Iterator<Item = ShaderArchiveEntry<'a>> + '_ {
+        (0..self.len).filter_map(|index| self.entry(index))
+    }
+
+    pub fn get(&self, name: &str, key: PermutationKey) -> Option<ShaderArchiveEntry<'a>> {
+        let (mut lo, mut hi) = (0, self.len);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            let entry = self.entry(mid)?;
+            match (entry.name, entry.key).cmp(&(name, key)) {
+                std::cmp::Ordering::Less => lo = mid + 1,
+                std::cmp::Ordering::Greater => hi = mid,
+                std::cmp::Ordering::Equal => return Some(entry),
+            }
+        }
+        None
+    }
+
+    #[inline]
+    pub fn bytecode(
+        &self,
+        name: &str,
+        key: PermutationKey,
+    ) -> Result<Option<ShaderBytecode<'a>>, ShaderArchiveError> {
+        self.get(name, key).map(|entry| entry.object()).transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+    }
+
+    fn sample_archive(compression: Compression) -> Vec<u8> {
+        let object = (0..2000u32)
+            .flat_map(|i| (i % 97).to_le_bytes())
+            .collect::<Vec<_>>();
+        let mut writer = ShaderArchiveWriter::new().compression(compression);
+        writer.add("vs", PermutationKey(2), b"vs object", None, None);
+        writer.add(
+            "ps",
+            PermutationKey(1),
+            &object,
+            Some(b"refl"),
+            Some(ShaderHash {
+                flags: 1,
+                digest: [3; 16],
+            }),
+        );
+        writer.add("ps", PermutationKey(0), b"ps default", None, None);
+        writer.to_bytes()
+    }
+
+    fn touch(archive: &ShaderArchive) {
+        for entry in archive.entries() {
+            let _ = entry.object();
+            let _ = entry.object_data();
+            let _ = entry.reflection_data();
+            let _ = entry.shader_hash();
+            let _ = archive.get(entry.name(), entry.key());
+        }
+    }
+
+    #[test]
+    fn shader_archive_writer_test() {
+        let mut writer = ShaderArchiveWriter::new();
+        writer.add("vs", PermutationKey(2), b"vs object", None, None);
+        writer.add(
+            "ps",
+            PermutationKey(1),
+            b"ps object",
+            Some(b"refl"),
+            Some(ShaderHash {
+                flags: 1,
+                digest: [3; 16],
+            }),
+        );
+        let data = writer.to_bytes();
+        assert_eq!(&data[..4], &MAGIC);
+        assert_eq!(u32::from_le_bytes(data[8..12].try_into().unwrap()), 2);
+        assert_eq!(
+            u64::from_le_bytes(data[24..32].try_into().unwrap()),
+            data.len() as u64
+        );
+        let entry = &data[HEADER_SIZE..HEADER_SIZE + ENTRY_SIZE];
+        let name_offset = u64::from_le_bytes(entry[..8].try_into().unwrap()) as usize;
+        assert_eq!(&data[name_offset..name_offset + 2], b"ps");
+        let object_offset = u64::from_le_bytes(entry[24..32].try_into().unwrap()) as usize;
+        assert_eq!(object_offset % DEFAULT_ALIGNMENT as usize, 0);
+        assert_eq!(&data[object_offset..object_offset + 9], b"ps object");
+    }
+
+    #[test]
+    fn shader_archive_test() {
+        let data = sample_archive(Compression::None);
+        let archive = ShaderArchive::new(&data).unwrap();
+        assert_eq!(archive.len(), 3);
+        let names = archive
+            .entries()
+            .map(|entry| (entry.name(), entry.key().0))
+            .collect::<Vec<_>>();
+        assert_eq!(names, [("ps", 0), ("ps", 1), ("vs", 2)]);
+        let ps = archive.get("ps", PermutationKey(1)).unwrap();
+        assert_eq!(ps.object_data().unwrap().len(), 8000);
+        assert_eq!(ps.reflection_data().unwrap().unwrap().as_ref(), b"refl");
+        assert_eq!(ps.shader_hash().unwrap().digest, [3; 16]);
+        let bytecode = archive.bytecode("vs", PermutationKey(2)).unwrap().unwrap();
+        assert_eq!(bytecode.desc.BytecodeLength, 9);
+        assert!(archive.bytecode("vs", PermutationKey(1)).unwrap().is_none());
+        assert!(archive.get("vs", PermutationKey(1)).is_none());
+        assert!(archive.get("gs", PermutationKey(0)).is_none());
+
+        let compressed = sample_archive(Compression::Lz4);
+        assert!(compressed.len() < data.len());
+        let archive = ShaderArchive::new(&compressed).unwrap();
+        let ps = archive.get("ps", PermutationKey(1)).unwrap();
+        assert!(ps.is_compressed());
+        assert_eq!(ps.object().unwrap_err(), ShaderArchiveError::Compressed);
+        assert_eq!(
+            archive.bytecode("ps", PermutationKey(1)).unwrap_err(),
+            ShaderArchiveError::Compressed
+        );
+        let expected = (0..2000u32)
+            .flat_map(|i| (i % 97).to_le_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(ps.object_data().unwrap().as_ref(), expected.as_slice());
+        assert!(
+            !archive
+                .get("vs", PermutationKey(2))
+                .unwrap()
+                .is_compressed()
+        );
+
+        assert_eq!(
+            ShaderArchive::new(b"DXBC").unwrap_err(),
+            ShaderArchiveError::InvalidHeader
+        );
+        let mut bad = data.clone();
+        bad[4] = 2;
+        assert_eq!(
+            ShaderArchive::new(&bad).unwrap_err(),
+            ShaderArchiveError::UnsupportedVersion(2)
+        );
+    }
+
+    #[test]
+    fn lz4_test() {
+        let mut rng = XorShift(0x2545f4914f6cdd1d);
+        for len in [0, 1, 12, 13, 100, 4096, 70000] {
+            let data = (0..len)
+                .map(|i| {
+                    if i % 3 == 0 {
+                        rng.next() as u8
+                    } else {
+                        (i / 7) as u8
+                    }
+                })
+                .collect::<Vec<_>>();
+            let compressed = lz4::compress(&data);
+            assert_eq!(lz4::decompress(&compressed, data.len()).unwrap(), data);
+            assert!(lz4::decompress(&compressed, data.len() + 1).is_none());
+        }
+    }
+
+    #[test]
+    fn shader_archive_fuzz_test() {
+        let mut rng = XorShift(0x9e3779b97f4a7c15);
+        for compression in [Compression::None, Compression::Lz4] {
+            let data = sample_archive(compression);
+            for len in 0..data.len() {
+                if let Ok(archive) = ShaderArchive::new(&data[..len]) {
+                    touch(&archive);
+                }
+            }
+            for _ in 0..2000 {
+                let mut mutated = data.clone();
+                for _ in 0..(rng.next() % 8 + 1) {
+                    let i = rng.next() as usize % mutated.len();
+                    mutated[i] = rng.next() as u8;
+                }
+                if let Ok(archive) = ShaderArchive::new(&mutated) {
+                    touch(&archive);
+                }
+            }
+        }
+        for _ in 0..2000 {
+            let len = rng.next() as usize % 256;
+            let garbage = (0..len).map(|_| rng.next() as u8).collect::<Vec<_>>();
+            let _ = lz4::decompress(&garbage, rng.next() as usize % 1024);
+            let mut archive = MAGIC.to_vec();
+            archive.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+            archive.extend_from_slice(&garbage);
+            if let Ok(archive) = ShaderArchive::new(&archive) {
+                touch(&archive);
+            }
+        }
+    }
+}
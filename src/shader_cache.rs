@@ -0,0 +1,526 @@
+use crate::dxc::{
+    Arguments, BlobType, CompileResult, Compiler, ContentHash, ContentHasher, IncludeHandler,
+    IncludedFile, ShaderHash, Utils,
+};
+use crate::reflection::ShaderReflection;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+use windows::Win32::Graphics::Direct3D::Dxc::*;
+
+pub const FORMAT_VERSION: u32 = 1;
+
+const MAGIC: [u8; 4] = *b"DWSC";
+const HEADER_SIZE: usize = 4 + 4 + 8 + 16;
+const ENTRY_EXTENSION: &str = "bin";
+const MARKER: &str = "dxwr-shader-cache";
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct CacheKey(pub ContentHash);
+
+impl CacheKey {
+    pub fn new<T>(src: &[u8], args: &[T], compiler_version: &str) -> Self
+    where
+        T: AsRef<str>,
+    {
+        let mut hasher = ContentHasher::new();
+        hasher.update(&FORMAT_VERSION.to_le_bytes());
+        hasher.update(&(src.len() as u64).to_le_bytes());
+        hasher.update(src);
+        hasher.update(&(args.len() as u64).to_le_bytes());
+        for arg in args {
+            hasher.update_str(arg.as_ref());
+        }
+        hasher.update_str(compiler_version);
+        Self(hasher.finish())
+    }
+}
+
+impl std::fmt::Display for CacheKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct CachedShader {
+    pub object: Vec<u8>,
+    pub reflection: Option<Vec<u8>>,
+    pub pdb: Option<Vec<u8>>,
+    pub shader_hash: Option<ShaderHash>,
+    pub includes: Vec<IncludedFile>,
+}
+
+impl CachedShader {
+    pub fn from_compile_result(result: &CompileResult) -> windows::core::Result<Self> {
+        let blob = |kind| {
+            result
+                .get_blob(kind)
+                .ok()
+                .map(|blob| blob.as_slice().to_vec())
+        };
+        Ok(Self {
+            object: blob(DXC_OUT_OBJECT).ok_or(windows::Win32::Foundation::E_ABORT)?,
+            reflection: blob(DXC_OUT_REFLECTION),
+            pdb: blob(DXC_OUT_PDB),
+            shader_hash: result.shader_hash().ok(),
+            includes: result.includes().to_vec(),
+        })
+    }
+
+    #[inline]
+    pub fn reflection(&self, utils: &Utils) -> windows::core::Result<ShaderReflection> {
+        let Some(data) = self.reflection.as_ref() else {
+            return Err(windows::Win32::Foundation::E_ABORT.into());
+        };
+        ShaderReflection::new(utils, data, DXC_CP_UTF8)
+    }
+
+    #[inline]
+    pub fn is_up_to_date(&self, handler: &dyn IncludeHandler) -> bool {
+        self.includes.iter().all(|include| {
+            handler
+                .load(&include.path)
+                .is_some_and(|data| ContentHash::new(&data) == include.hash)
+        })
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        fn bytes(buf: &mut Vec<u8>, data: &[u8]) {
+            buf.extend_from_slice(&(data.len() as u64).to_le_bytes());
+            buf.extend_from_slice(data);
+        }
+        fn optional_bytes(buf: &mut Vec<u8>, data: Option<&[u8]>) {
+            buf.push(data.is_some() as u8);
+            if let Some(data) = data {
+                bytes(buf, data);
+            }
+        }
+        let mut payload = vec![];
+        bytes(&mut payload, &self.object);
+        optional_bytes(&mut payload, self.reflection.as_deref());
+        optional_bytes(&mut payload, self.pdb.as_deref());
+        payload.push(self.shader_hash.is_some() as u8);
+        if let Some(hash) = self.shader_hash.as_ref() {
+            payload.extend_from_slice(&hash.flags.to_le_bytes());
+            payload.extend_from_slice(&hash.digest);
+        }
+        payload.extend_from_slice(&(self.includes.len() as u32).to_le_bytes());
+        for include in &self.includes {
+            bytes(&mut payload, include.path.to_string_lossy().as_bytes());
+            payload.extend_from_slice(&include.hash.to_bytes());
+        }
+        let mut buf = Vec::with_capacity(HEADER_SIZE + payload.len());
+        buf.extend_from_slice(&MAGIC);
+        buf.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        buf.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+        buf.extend_from_slice(&ContentHash::new(&payload).to_bytes());
+        buf.extend_from_slice(&payload);
+        buf
+    }
+
+    fn decode(data: &[u8]) -> Option<Self> {
+        let mut header = Reader::new(data);
+        if header.take(4)? != MAGIC || header.u32()? != FORMAT_VERSION {
+            return None;
+        }
+        let len = header.u64()? as usize;
+        let hash = header.u128()?;
+        let payload = header.take(len)?;
+        if !header.is_empty() || ContentHash::new(payload).0 != hash {
+            return None;
+        }
+        let mut reader = Reader::new(payload);
+        let object = reader.bytes()?.to_vec();
+        let reflection = reader.optional_bytes()?.map(|data| data.to_vec());
+        let pdb = reader.optional_bytes()?.map(|data| data.to_vec());
+        let shader_hash = match reader.u8()? {
+            0 => None,
+            1 => Some(ShaderHash {
+                flags: reader.u32()?,
+                digest: reader.take(16)?.try_into().ok()?,
+            }),
+            _ => return None,
+        };
+        let count = reader.u32()?;
+        let includes = (0..count)
+            .map(|_| {
+                let path = std::str::from_utf8(reader.bytes()?).ok()?;
+                Some(IncludedFile {
+                    path: PathBuf::from(path),
+                    hash: ContentHash(reader.u128()?),
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        reader.is_empty().then_some(Self {
+            object,
+            reflection,
+            pdb,
+            shader_hash,
+            includes,
+        })
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.data.len() < len {
+            return None;
+        }
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Some(head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take(4)
+            .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.take(8)
+            .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    fn u128(&mut self) -> Option<u128> {
+        self.take(16)
+            .map(|b| u128::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    fn bytes(&mut self) -> Option<&'a [u8]> {
+        let len = self.u64()?;
+        self.take(usize::try_from(len).ok()?)
+    }
+
+    fn optional_bytes(&mut self) -> Option<Option<&'a [u8]>> {
+        match self.u8()? {
+            0 => Some(None),
+            1 => self.bytes().map(Some),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ShaderCache {
+    dir: PathBuf,
+    max_size: Option<u64>,
+}
+
+impl ShaderCache {
+    pub fn new(dir: impl AsRef<Path>) -> std::io::Result<Self> {
+        let root = dir.as_ref();
+        let dir = root.join(format!("v{FORMAT_VERSION}"));
+        let cache = Self {
+            dir,
+            max_size: None,
+        };
+        cache.create_dir()?;
+        for entry in std::fs::read_dir(root)?.flatten() {
+            let path = entry.path();
+            let stale = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    name.starts_with('v')
+                        && name[1..].parse::<u32>().is_ok()
+                        && path != cache.dir
+                        && path.join(MARKER).is_file()
+                });
+            if stale {
+                std::fs::remove_dir_all(&path).ok();
+            }
+        }
+        Ok(cache)
+    }
+
+    fn create_dir(&self) -> std::io::Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let marker = self.dir.join(MARKER);
+        if !marker.is_file() {
+            std::fs::write(marker, [])?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub fn max_size(mut self, bytes: u64) -> Self {
+        self.max_size = Some(bytes);
+        self
+    }
+
+    #[inline]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn entry_dir(&self, key: &CacheKey) -> PathBuf {
+        let name = key.to_string();
+        self.dir.join(&name[..2]).join(&name)
+    }
+
+    fn entry_path(&self, key: &CacheKey, includes: &[IncludedFile]) -> PathBuf {
+        let mut hasher = ContentHasher::new();
+        hasher.update(&(includes.len() as u64).to_le_bytes());
+        for include in includes {
+            hasher.update_str(&include.path.to_string_lossy());
+            hasher.update(&include.hash.to_bytes());
+        }
+        self.entry_dir(key)
+            .join(hasher.finish().to_string())
+            .with_extension(ENTRY_EXTENSION)
+    }
+
+    pub fn get(&self, key: &CacheKey, handler: &dyn IncludeHandler) -> Option<CachedShader> {
+        for entry in std::fs::read_dir(self.entry_dir(key)).ok()?.flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != ENTRY_EXTENSION) {
+                continue;
+            }
+            let Ok(data) = std::fs::read(&path) else {
+                continue;
+            };
+            let Some(shader) = CachedShader::decode(&data) else {
+                std::fs::remove_file(&path).ok();
+                continue;
+            };
+            if !shader.is_up_to_date(handler) {
+                continue;
+            }
+            if let Ok(file) = std::fs::File::options().append(true).open(&path) {
+                file.set_modified(SystemTime::now()).ok();
+            }
+            return Some(shader);
+        }
+        None
+    }
+
+    pub fn insert(&self, key: &CacheKey, shader: &CachedShader) -> std::io::Result<()> {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let path = self.entry_path(key, &shader.includes);
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        let tmp = path.with_extension(format!(
+            "tmp{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let written = std::fs::File::create(&tmp).and_then(|mut file| {
+            file.write_all(&shader.encode())?;
+            file.sync_all()
+        });
+        if let Err(e) = written.and_then(|_| std::fs::rename(&tmp, &path)) {
+            std::fs::remove_file(&tmp).ok();
+            return Err(e);
+        }
+        if let Some(max_size) = self.max_size {
+            self.evict(max_size).ok();
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub fn remove(&self, key: &CacheKey) -> std::io::Result<()> {
+        match std::fs::remove_dir_all(self.entry_dir(key)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn clear(&self) -> std::io::Result<()> {
+        std::fs::remove_dir_all(&self.dir)?;
+        self.create_dir()
+    }
+
+    fn entries(&self) -> std::io::Result<Vec<(PathBuf, u64, SystemTime)>> {
+        let mut entries = vec![];
+        for bucket in std::fs::read_dir(&self.dir)? {
+            let bucket = bucket?.path();
+            if !bucket.is_dir() {
+                continue;
+            }
+            for key in std::fs::read_dir(&bucket)? {
+                let key = key?.path();
+                if !key.is_dir() {
+                    continue;
+                }
+                for entry in std::fs::read_dir(&key)? {
+                    let path = entry?.path();
+                    if path.extension().is_none_or(|ext| ext != ENTRY_EXTENSION) {
+                        continue;
+                    }
+                    let Ok(metadata) = path.metadata() else {
+                        continue;
+                    };
+                    let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                    entries.push((path, metadata.len(), modified));
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    #[inline]
+    pub fn size(&self) -> std::io::Result<u64> {
+        Ok(self.entries()?.iter().map(|(_, len, _)| len).sum())
+    }
+
+    pub fn evict(&self, max_size: u64) -> std::io::Result<()> {
+        let mut entries = self.entries()?;
+        let mut size: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        entries.sort_by_key(|(_, _, modified)| *modified);
+        for (path, len, _) in entries {
+            if size <= max_size {
+                break;
+            }
+            match std::fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+            if let Some(parent) = path.parent() {
+                std::fs::remove_dir(parent).ok();
+            }
+            size -= len;
+        }
+        Ok(())
+    }
+
+    pub fn compile<T>(
+        &self,
+        compiler: &Compiler,
+        src: &[u8],
+        args: &Arguments,
+        handler: T,
+    ) -> windows::core::Result<CachedShader>
+    where
+        T: IncludeHandler + 'static,
+    {
+        let key = CacheKey::new(src, &args.to_args(), &compiler.version()?.to_string());
+        if let Some(shader) = self.get(&key, &handler) {
+            return Ok(shader);
+        }
+        let result = compiler.compile_with_include_handler(src, args, handler)?;
+        result.status()?;
+        let shader = CachedShader::from_compile_result(&result)?;
+        self.insert(&key, &shader).ok();
+        Ok(shader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dxc::MemoryIncludeHandler;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("dxwr-shader-cache-{}-{}", name, std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        dir
+    }
+
+    fn shader(object: &[u8], includes: &[(&str, &[u8])]) -> CachedShader {
+        CachedShader {
+            object: object.to_vec(),
+            reflection: Some(vec![1, 2, 3]),
+            pdb: None,
+            shader_hash: Some(ShaderHash {
+                flags: 1,
+                digest: [7; 16],
+            }),
+            includes: includes
+                .iter()
+                .map(|(path, data)| IncludedFile {
+                    path: PathBuf::from(path),
+                    hash: ContentHash::new(data),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn shader_cache_test() {
+        let dir = temp_dir("roundtrip");
+        std::fs::create_dir_all(dir.join("v0")).unwrap();
+        std::fs::write(dir.join("v0").join(MARKER), []).unwrap();
+        std::fs::create_dir_all(dir.join("v7")).unwrap();
+        let cache = ShaderCache::new(&dir).unwrap();
+        assert!(!dir.join("v0").exists());
+        assert!(dir.join("v7").exists());
+        let key = CacheKey::new(b"float4 main()", &["-T", "ps_6_6"], "1.8");
+        assert_ne!(
+            key,
+            CacheKey::new(b"float4 main()", &["-T", "ps_6_7"], "1.8")
+        );
+        let handler = MemoryIncludeHandler::new().file("common.hlsli", "a");
+        let entry = shader(b"DXBC", &[("common.hlsli", b"a")]);
+        assert!(cache.get(&key, &handler).is_none());
+        cache.insert(&key, &entry).unwrap();
+        assert_eq!(cache.get(&key, &handler).unwrap(), entry);
+        let changed = MemoryIncludeHandler::new().file("common.hlsli", "b");
+        assert!(cache.get(&key, &changed).is_none());
+        let other = shader(b"DXIL", &[("common.hlsli", b"b")]);
+        cache.insert(&key, &other).unwrap();
+        assert_eq!(cache.get(&key, &changed).unwrap(), other);
+        assert_eq!(cache.get(&key, &handler).unwrap(), entry);
+
+        cache.remove(&key).unwrap();
+        assert!(cache.get(&key, &changed).is_none());
+        cache.insert(&key, &entry).unwrap();
+        let path = cache.entry_path(&key, &entry.includes);
+        let mut data = std::fs::read(&path).unwrap();
+        *data.last_mut().unwrap() ^= 0xff;
+        std::fs::write(&path, &data).unwrap();
+        assert!(cache.get(&key, &handler).is_none());
+        assert!(!path.exists());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn shader_cache_evict_test() {
+        let dir = temp_dir("evict");
+        let cache = ShaderCache::new(&dir).unwrap();
+        let handler = MemoryIncludeHandler::new();
+        let keys = (0..4u8)
+            .map(|i| {
+                let key = CacheKey::new(&[i], &[""; 0], "1.8");
+                cache.insert(&key, &shader(&[i; 100], &[])).unwrap();
+                let t = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(i as u64 + 1);
+                let file = std::fs::File::options()
+                    .append(true)
+                    .open(cache.entry_path(&key, &[]))
+                    .unwrap();
+                file.set_modified(t).unwrap();
+                key
+            })
+            .collect::<Vec<_>>();
+        let entry_size = cache.size().unwrap() / 4;
+        cache.evict(entry_size * 2).unwrap();
+        assert!(cache.get(&keys[0], &handler).is_none());
+        assert!(cache.get(&keys[1], &handler).is_none());
+        assert!(cache.get(&keys[2], &handler).is_some());
+        assert!(cache.get(&keys[3], &handler).is_some());
+        assert!(!cache.entry_dir(&keys[0]).exists());
+
+        let cache = cache.max_size(entry_size);
+        cache.insert(&keys[0], &shader(&[0; 100], &[])).unwrap();
+        assert_eq!(cache.size().unwrap(), entry_size);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,6 +1,7 @@
 use super::*;
 use std::mem::ManuallyDrop;
 use windows::Win32::Graphics::Direct3D12::*;
+use windows::Win32::Graphics::Dxgi::Common::{DXGI_FORMAT, DXGI_FORMAT_UNKNOWN};
 use windows::core::Interface;
 use windows::core::{HSTRING, PCWSTR};
 
@@ -465,11 +466,500 @@ impl StateSubobject for HitGroupDesc {
     }
 }
 
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct NodeId {
+    name: HSTRING,
+    array_index: u32,
+}
+
+impl NodeId {
+    #[inline]
+    pub fn new(name: impl AsRef<str>, array_index: u32) -> Self {
+        Self {
+            name: HSTRING::from(name.as_ref()),
+            array_index,
+        }
+    }
+
+    #[inline]
+    pub fn name(&self) -> String {
+        self.name.to_string_lossy()
+    }
+
+    #[inline]
+    pub fn array_index(&self) -> u32 {
+        self.array_index
+    }
+
+    fn as_raw(&self) -> D3D12_NODE_ID {
+        D3D12_NODE_ID {
+            Name: PCWSTR(self.name.as_ptr()),
+            ArrayIndex: self.array_index,
+        }
+    }
+
+    fn from_raw(id: &D3D12_NODE_ID) -> Self {
+        Self {
+            name: unsafe { HSTRING::from_wide(id.Name.as_wide()) },
+            array_index: id.ArrayIndex,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ShaderNode {
+    shader: HSTRING,
+    overrides_type: D3D12_NODE_OVERRIDES_TYPE,
+    local_root_arguments_table_index: Option<u32>,
+    program_entry: Option<windows::core::BOOL>,
+    new_name: Option<NodeId>,
+    share_input_of: Option<NodeId>,
+    dispatch_grid: Option<[u32; 3]>,
+    max_dispatch_grid: Option<[u32; 3]>,
+}
+
+impl ShaderNode {
+    #[inline]
+    pub fn new(shader: impl AsRef<str>) -> Self {
+        Self {
+            shader: HSTRING::from(shader.as_ref()),
+            overrides_type: D3D12_NODE_OVERRIDES_TYPE_NONE,
+            local_root_arguments_table_index: None,
+            program_entry: None,
+            new_name: None,
+            share_input_of: None,
+            dispatch_grid: None,
+            max_dispatch_grid: None,
+        }
+    }
+
+    #[inline]
+    pub fn overrides_type(mut self, ty: D3D12_NODE_OVERRIDES_TYPE) -> Self {
+        self.overrides_type = ty;
+        self
+    }
+
+    #[inline]
+    pub fn local_root_arguments_table_index(mut self, index: u32) -> Self {
+        self.local_root_arguments_table_index = Some(index);
+        self
+    }
+
+    #[inline]
+    pub fn program_entry(mut self, value: bool) -> Self {
+        self.program_entry = Some(value.into());
+        self
+    }
+
+    #[inline]
+    pub fn new_name(mut self, id: NodeId) -> Self {
+        self.new_name = Some(id);
+        self
+    }
+
+    #[inline]
+    pub fn share_input_of(mut self, id: NodeId) -> Self {
+        self.share_input_of = Some(id);
+        self
+    }
+
+    #[inline]
+    pub fn dispatch_grid(mut self, grid: [u32; 3]) -> Self {
+        self.dispatch_grid = Some(grid);
+        self
+    }
+
+    #[inline]
+    pub fn max_dispatch_grid(mut self, grid: [u32; 3]) -> Self {
+        self.max_dispatch_grid = Some(grid);
+        self
+    }
+}
+
+struct ShaderNodeRaw {
+    new_name: Option<D3D12_NODE_ID>,
+    share_input_of: Option<D3D12_NODE_ID>,
+    broadcasting: D3D12_BROADCASTING_LAUNCH_OVERRIDES,
+    common: D3D12_COMMON_COMPUTE_NODE_OVERRIDES,
+}
+
+pub struct WorkGraphDesc {
+    desc: D3D12_WORK_GRAPH_DESC,
+    program_name: HSTRING,
+    entrypoints: Vec<NodeId>,
+    entrypoint_descs: Vec<D3D12_NODE_ID>,
+    nodes: Vec<ShaderNode>,
+    node_raws: Vec<ShaderNodeRaw>,
+    node_descs: Vec<D3D12_NODE>,
+}
+
+impl WorkGraphDesc {
+    #[inline]
+    pub fn new(program_name: impl AsRef<str>) -> Self {
+        let program_name = HSTRING::from(program_name.as_ref());
+        Self {
+            desc: D3D12_WORK_GRAPH_DESC {
+                ProgramName: PCWSTR(program_name.as_ptr()),
+                Flags: D3D12_WORK_GRAPH_FLAG_NONE,
+                ..Default::default()
+            },
+            program_name,
+            entrypoints: vec![],
+            entrypoint_descs: vec![],
+            nodes: vec![],
+            node_raws: vec![],
+            node_descs: vec![],
+        }
+    }
+
+    #[inline]
+    pub fn flags(mut self, flags: D3D12_WORK_GRAPH_FLAGS) -> Self {
+        self.desc.Flags = flags;
+        self
+    }
+
+    #[inline]
+    pub fn include_all_available_nodes(mut self) -> Self {
+        self.desc.Flags |= D3D12_WORK_GRAPH_FLAG_INCLUDE_ALL_AVAILABLE_NODES;
+        self
+    }
+
+    #[inline]
+    pub fn entrypoints(mut self, entrypoints: &[NodeId]) -> Self {
+        self.entrypoints = entrypoints.to_vec();
+        self.entrypoint_descs = self.entrypoints.iter().map(|id| id.as_raw()).collect();
+        self.desc.NumEntrypoints = self.entrypoint_descs.len() as u32;
+        self.desc.pEntrypoints = self.entrypoint_descs.as_ptr();
+        self
+    }
+
+    #[inline]
+    pub fn explicitly_defined_nodes(mut self, nodes: &[ShaderNode]) -> Self {
+        self.nodes = nodes.to_vec();
+        self.node_raws = self
+            .nodes
+            .iter()
+            .map(|node| ShaderNodeRaw {
+                new_name: node.new_name.as_ref().map(|id| id.as_raw()),
+                share_input_of: node.share_input_of.as_ref().map(|id| id.as_raw()),
+                broadcasting: D3D12_BROADCASTING_LAUNCH_OVERRIDES::default(),
+                common: D3D12_COMMON_COMPUTE_NODE_OVERRIDES::default(),
+            })
+            .collect();
+        for (node, raw) in self.nodes.iter().zip(self.node_raws.iter_mut()) {
+            let local_root_arguments_table_index = node
+                .local_root_arguments_table_index
+                .as_ref()
+                .map_or(std::ptr::null(), |v| v as *const u32);
+            let program_entry = node
+                .program_entry
+                .as_ref()
+                .map_or(std::ptr::null(), |v| v as *const _);
+            let new_name = raw
+                .new_name
+                .as_ref()
+                .map_or(std::ptr::null(), |v| v as *const D3D12_NODE_ID);
+            let share_input_of = raw
+                .share_input_of
+                .as_ref()
+                .map_or(std::ptr::null(), |v| v as *const D3D12_NODE_ID);
+            raw.broadcasting = D3D12_BROADCASTING_LAUNCH_OVERRIDES {
+                pLocalRootArgumentsTableIndex: local_root_arguments_table_index,
+                pProgramEntry: program_entry,
+                pNewName: new_name,
+                pShareInputOf: share_input_of,
+                pDispatchGrid: node
+                    .dispatch_grid
+                    .as_ref()
+                    .map_or(std::ptr::null(), |v| v.as_ptr()),
+                pMaxDispatchGrid: node
+                    .max_dispatch_grid
+                    .as_ref()
+                    .map_or(std::ptr::null(), |v| v.as_ptr()),
+                NumOutputOverrides: 0,
+                pOutputOverrides: std::ptr::null(),
+            };
+            raw.common = D3D12_COMMON_COMPUTE_NODE_OVERRIDES {
+                pLocalRootArgumentsTableIndex: local_root_arguments_table_index,
+                pProgramEntry: program_entry,
+                pNewName: new_name,
+                pShareInputOf: share_input_of,
+                NumOutputOverrides: 0,
+                pOutputOverrides: std::ptr::null(),
+            };
+        }
+        self.node_descs = self
+            .nodes
+            .iter()
+            .zip(self.node_raws.iter())
+            .map(|(node, raw)| {
+                // coalescing, thread and common compute overrides have the same layout.
+                let common = &raw.common as *const D3D12_COMMON_COMPUTE_NODE_OVERRIDES;
+                let overrides = match node.overrides_type {
+                    D3D12_NODE_OVERRIDES_TYPE_BROADCASTING_LAUNCH => D3D12_SHADER_NODE_0 {
+                        pBroadcastingLaunchOverrides: &raw.broadcasting,
+                    },
+                    D3D12_NODE_OVERRIDES_TYPE_COALESCING_LAUNCH => D3D12_SHADER_NODE_0 {
+                        pCoalescingLaunchOverrides: common as *const _,
+                    },
+                    D3D12_NODE_OVERRIDES_TYPE_THREAD_LAUNCH => D3D12_SHADER_NODE_0 {
+                        pThreadLaunchOverrides: common as *const _,
+                    },
+                    D3D12_NODE_OVERRIDES_TYPE_COMMON_COMPUTE => D3D12_SHADER_NODE_0 {
+                        pCommonComputeNodeOverrides: common,
+                    },
+                    _ => D3D12_SHADER_NODE_0::default(),
+                };
+                D3D12_NODE {
+                    NodeType: D3D12_NODE_TYPE_SHADER,
+                    Anonymous: D3D12_NODE_0 {
+                        Shader: D3D12_SHADER_NODE {
+                            Shader: PCWSTR(node.shader.as_ptr()),
+                            OverridesType: node.overrides_type,
+                            Anonymous: overrides,
+                        },
+                    },
+                }
+            })
+            .collect();
+        self.desc.NumExplicitlyDefinedNodes = self.node_descs.len() as u32;
+        self.desc.pExplicitlyDefinedNodes = self.node_descs.as_ptr();
+        self
+    }
+
+    #[inline]
+    pub fn program_name(&self) -> String {
+        self.program_name.to_string_lossy()
+    }
+}
+
+impl StateSubobject for WorkGraphDesc {
+    fn type_value(&self) -> D3D12_STATE_SUBOBJECT_TYPE {
+        D3D12_STATE_SUBOBJECT_TYPE_WORK_GRAPH
+    }
+
+    fn desc(&self) -> *const std::ffi::c_void {
+        &self.desc as *const D3D12_WORK_GRAPH_DESC as *const _
+    }
+}
+
+impl StateSubobject for BlendDesc {
+    fn type_value(&self) -> D3D12_STATE_SUBOBJECT_TYPE {
+        D3D12_STATE_SUBOBJECT_TYPE_BLEND
+    }
+}
+
+impl StateSubobject for SampleMask {
+    fn type_value(&self) -> D3D12_STATE_SUBOBJECT_TYPE {
+        D3D12_STATE_SUBOBJECT_TYPE_SAMPLE_MASK
+    }
+}
+
+impl StateSubobject for DepthStencilDesc {
+    fn type_value(&self) -> D3D12_STATE_SUBOBJECT_TYPE {
+        D3D12_STATE_SUBOBJECT_TYPE_DEPTH_STENCIL
+    }
+}
+
+impl StateSubobject for IbStripCutValue {
+    fn type_value(&self) -> D3D12_STATE_SUBOBJECT_TYPE {
+        D3D12_STATE_SUBOBJECT_TYPE_IB_STRIP_CUT_VALUE
+    }
+}
+
+impl StateSubobject for PrimitiveTopologyType {
+    fn type_value(&self) -> D3D12_STATE_SUBOBJECT_TYPE {
+        D3D12_STATE_SUBOBJECT_TYPE_PRIMITIVE_TOPOLOGY
+    }
+}
+
+impl StateSubobject for DepthStencilFormat {
+    fn type_value(&self) -> D3D12_STATE_SUBOBJECT_TYPE {
+        D3D12_STATE_SUBOBJECT_TYPE_DEPTH_STENCIL_FORMAT
+    }
+}
+
+impl StateSubobject for SampleDesc {
+    fn type_value(&self) -> D3D12_STATE_SUBOBJECT_TYPE {
+        D3D12_STATE_SUBOBJECT_TYPE_SAMPLE_DESC
+    }
+}
+
+impl StateSubobject for PipelineFlags {
+    fn type_value(&self) -> D3D12_STATE_SUBOBJECT_TYPE {
+        D3D12_STATE_SUBOBJECT_TYPE_FLAGS
+    }
+}
+
+#[derive(Clone, Debug)]
+#[repr(transparent)]
+pub struct RasterizerSubobject(D3D12_RASTERIZER_DESC2);
+
+impl RasterizerSubobject {
+    #[inline]
+    pub fn new(desc: RasterizerDesc) -> Self {
+        let desc = desc.0;
+        let line_rasterization_mode = if desc.MultisampleEnable.as_bool() {
+            D3D12_LINE_RASTERIZATION_MODE_QUADRILATERAL_WIDE
+        } else if desc.AntialiasedLineEnable.as_bool() {
+            D3D12_LINE_RASTERIZATION_MODE_ALPHA_ANTIALIASED
+        } else {
+            D3D12_LINE_RASTERIZATION_MODE_ALIASED
+        };
+        Self(D3D12_RASTERIZER_DESC2 {
+            FillMode: desc.FillMode,
+            CullMode: desc.CullMode,
+            FrontCounterClockwise: desc.FrontCounterClockwise,
+            DepthBias: desc.DepthBias as f32,
+            DepthBiasClamp: desc.DepthBiasClamp,
+            SlopeScaledDepthBias: desc.SlopeScaledDepthBias,
+            DepthClipEnable: desc.DepthClipEnable,
+            LineRasterizationMode: line_rasterization_mode,
+            ForcedSampleCount: desc.ForcedSampleCount,
+            ConservativeRaster: desc.ConservativeRaster,
+        })
+    }
+
+    #[inline]
+    pub fn depth_bias(mut self, value: f32) -> Self {
+        self.0.DepthBias = value;
+        self
+    }
+
+    #[inline]
+    pub fn line_rasterization_mode(mut self, mode: D3D12_LINE_RASTERIZATION_MODE) -> Self {
+        self.0.LineRasterizationMode = mode;
+        self
+    }
+}
+
+impl Default for RasterizerSubobject {
+    fn default() -> Self {
+        Self::new(RasterizerDesc::new())
+    }
+}
+
+impl From<RasterizerDesc> for RasterizerSubobject {
+    fn from(value: RasterizerDesc) -> Self {
+        Self::new(value)
+    }
+}
+
+impl StateSubobject for RasterizerSubobject {
+    fn type_value(&self) -> D3D12_STATE_SUBOBJECT_TYPE {
+        D3D12_STATE_SUBOBJECT_TYPE_RASTERIZER
+    }
+}
+
+#[derive(Clone, Debug)]
+#[repr(transparent)]
+pub struct InputLayoutSubobject<'a> {
+    desc: D3D12_INPUT_LAYOUT_DESC,
+    _a: std::marker::PhantomData<&'a ()>,
+}
+
+impl<'a> InputLayoutSubobject<'a> {
+    #[inline]
+    pub fn new(elements: &'a [InputElementDesc]) -> Self {
+        Self {
+            desc: D3D12_INPUT_LAYOUT_DESC {
+                pInputElementDescs: elements.as_ptr() as *const D3D12_INPUT_ELEMENT_DESC,
+                NumElements: elements.len() as u32,
+            },
+            _a: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a> From<InputLayout<'a, '_>> for InputLayoutSubobject<'a> {
+    fn from(value: InputLayout<'a, '_>) -> Self {
+        Self::new(value.0)
+    }
+}
+
+impl StateSubobject for InputLayoutSubobject<'_> {
+    fn type_value(&self) -> D3D12_STATE_SUBOBJECT_TYPE {
+        D3D12_STATE_SUBOBJECT_TYPE_INPUT_LAYOUT
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+#[repr(transparent)]
+pub struct RenderTargetFormatsSubobject(D3D12_RT_FORMAT_ARRAY);
+
+impl RenderTargetFormatsSubobject {
+    #[inline]
+    pub fn new(formats: &[DXGI_FORMAT]) -> Self {
+        assert!(formats.len() <= 8);
+        let mut inner = D3D12_RT_FORMAT_ARRAY {
+            RTFormats: [DXGI_FORMAT_UNKNOWN; 8],
+            NumRenderTargets: formats.len() as u32,
+        };
+        inner.RTFormats[..formats.len()].copy_from_slice(formats);
+        Self(inner)
+    }
+}
+
+impl From<RenderTargetFormats<'_>> for RenderTargetFormatsSubobject {
+    fn from(value: RenderTargetFormats<'_>) -> Self {
+        Self::new(value.0)
+    }
+}
+
+impl StateSubobject for RenderTargetFormatsSubobject {
+    fn type_value(&self) -> D3D12_STATE_SUBOBJECT_TYPE {
+        D3D12_STATE_SUBOBJECT_TYPE_RENDER_TARGET_FORMATS
+    }
+}
+
+pub struct GenericProgramDesc<'a> {
+    program_name: HSTRING,
+    exports: Vec<HSTRING>,
+    export_ptrs: Vec<PCWSTR>,
+    subobjects: Vec<&'a dyn StateSubobject>,
+}
+
+impl<'a> GenericProgramDesc<'a> {
+    #[inline]
+    pub fn new(program_name: impl AsRef<str>) -> Self {
+        Self {
+            program_name: HSTRING::from(program_name.as_ref()),
+            exports: vec![],
+            export_ptrs: vec![],
+            subobjects: vec![],
+        }
+    }
+
+    #[inline]
+    pub fn exports(mut self, exports: &[&str]) -> Self {
+        self.exports = exports.iter().map(|e| HSTRING::from(*e)).collect();
+        self.export_ptrs = self
+            .exports
+            .iter()
+            .map(|e| PCWSTR(e.as_ptr()))
+            .collect::<Vec<_>>();
+        self
+    }
+
+    #[inline]
+    pub fn subobject(mut self, subobject: &'a impl StateSubobject) -> Self {
+        self.subobjects.push(subobject);
+        self
+    }
+
+    #[inline]
+    pub fn program_name(&self) -> String {
+        self.program_name.to_string_lossy()
+    }
+}
+
 pub struct Builder<'a> {
     device: ID3D12Device5,
     ty: D3D12_STATE_OBJECT_TYPE,
     subobjects: Vec<&'a dyn StateSubobject>,
     associations: Vec<&'a SubobjectToExportsAssociation<'a>>,
+    programs: Vec<&'a GenericProgramDesc<'a>>,
+    existing: Option<ID3D12StateObject>,
     name: Option<String>,
 }
 
@@ -483,6 +973,8 @@ impl<'a> Builder<'a> {
             ty,
             subobjects: vec![],
             associations: vec![],
+            programs: vec![],
+            existing: None,
             name: None,
         }
     }
@@ -499,16 +991,31 @@ impl<'a> Builder<'a> {
         self
     }
 
+    #[inline]
+    pub fn program(mut self, program: &'a GenericProgramDesc<'a>) -> Self {
+        for subobject in program.subobjects.iter() {
+            if !self
+                .subobjects
+                .iter()
+                .any(|sub| sub.desc() == subobject.desc())
+            {
+                self.subobjects.push(*subobject);
+            }
+        }
+        self.programs.push(program);
+        self
+    }
+
     #[inline]
     pub fn name(mut self, name: impl AsRef<str>) -> Self {
         self.name = Some(name.as_ref().to_string());
         self
     }
 
-    #[inline]
-    pub fn build(self) -> windows::core::Result<StateObject> {
-        let mut subobjects: Vec<D3D12_STATE_SUBOBJECT> =
-            Vec::with_capacity(self.subobjects.len() + self.associations.len());
+    fn with_raw_desc<R>(&self, f: impl FnOnce(&D3D12_STATE_OBJECT_DESC) -> R) -> R {
+        let mut subobjects: Vec<D3D12_STATE_SUBOBJECT> = Vec::with_capacity(
+            self.subobjects.len() + self.associations.len() + self.programs.len(),
+        );
         for subobject in self.subobjects.iter() {
             subobjects.push(D3D12_STATE_SUBOBJECT {
                 Type: subobject.type_value(),
@@ -519,13 +1026,13 @@ impl<'a> Builder<'a> {
             .associations
             .iter()
             .map(|i| {
-                let index = self
+                let subobject = self
                     .subobjects
                     .iter()
                     .position(|sub| sub.desc() == i.assoc.desc())
-                    .unwrap();
+                    .map_or(std::ptr::null(), |index| &subobjects[index] as *const _);
                 D3D12_SUBOBJECT_TO_EXPORTS_ASSOCIATION {
-                    pSubobjectToAssociate: &subobjects[index],
+                    pSubobjectToAssociate: subobject,
                     NumExports: i.export_ptrs.len() as u32,
                     pExports: i.export_ptrs.as_ptr(),
                 }
@@ -537,21 +1044,574 @@ impl<'a> Builder<'a> {
                 pDesc: a as *const D3D12_SUBOBJECT_TO_EXPORTS_ASSOCIATION as *const _,
             });
         }
-        let handle: ID3D12StateObject = unsafe {
-            self.device.CreateStateObject(&D3D12_STATE_OBJECT_DESC {
-                Type: self.ty,
-                NumSubobjects: subobjects.len() as u32,
-                pSubobjects: subobjects.as_ptr(),
-            })?
-        };
+        let program_subobjects = self
+            .programs
+            .iter()
+            .map(|p| {
+                p.subobjects
+                    .iter()
+                    .map(|s| {
+                        self.subobjects
+                            .iter()
+                            .position(|sub| sub.desc() == s.desc())
+                            .map_or(std::ptr::null(), |index| {
+                                &subobjects[index] as *const D3D12_STATE_SUBOBJECT
+                            })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let programs = self
+            .programs
+            .iter()
+            .zip(program_subobjects.iter())
+            .map(|(p, subs)| D3D12_GENERIC_PROGRAM_DESC {
+                ProgramName: PCWSTR(p.program_name.as_ptr()),
+                NumExports: p.export_ptrs.len() as u32,
+                pExports: p.export_ptrs.as_ptr(),
+                NumSubobjects: subs.len() as u32,
+                ppSubobjects: subs.as_ptr(),
+            })
+            .collect::<Vec<_>>();
+        for p in programs.iter() {
+            subobjects.push(D3D12_STATE_SUBOBJECT {
+                Type: D3D12_STATE_SUBOBJECT_TYPE_GENERIC_PROGRAM,
+                pDesc: p as *const D3D12_GENERIC_PROGRAM_DESC as *const _,
+            });
+        }
+        f(&D3D12_STATE_OBJECT_DESC {
+            Type: self.ty,
+            NumSubobjects: subobjects.len() as u32,
+            pSubobjects: subobjects.as_ptr(),
+        })
+    }
+
+    #[inline]
+    pub fn validate(&self) -> Result<(), Vec<StateObjectDescError>> {
+        let errors = self.with_raw_desc(|desc| unsafe {
+            validate_state_object_desc(desc, self.existing.is_some())
+        });
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    #[inline]
+    pub fn print_desc(&self) -> String {
+        self.with_raw_desc(|desc| unsafe { print_state_object_desc(desc) })
+    }
+
+    #[inline]
+    pub fn build(self) -> windows::core::Result<StateObject> {
+        let handle: ID3D12StateObject = self.with_raw_desc(|desc| unsafe {
+            match self.existing.as_ref() {
+                Some(existing) => {
+                    let device: ID3D12Device7 = self.device.cast()?;
+                    device.AddToStateObject(desc, existing)
+                }
+                None => self.device.CreateStateObject(desc),
+            }
+        })?;
         let name = self.name.as_ref().map(|n| Name::new(&handle, n));
-        Ok(StateObject { handle, name })
+        Ok(StateObject {
+            handle,
+            ty: self.ty,
+            name,
+        })
     }
 }
 
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum StateObjectDescError {
+    UnresolvedHitGroupImport {
+        subobject_index: usize,
+        hit_group: String,
+        import: String,
+    },
+    MissingAssociationExport {
+        subobject_index: usize,
+        export: String,
+    },
+    InvalidAssociationSubobject {
+        subobject_index: usize,
+    },
+    InvalidProgramSubobject {
+        subobject_index: usize,
+    },
+    DuplicateExport {
+        name: String,
+    },
+    AttributeSizeTooLarge {
+        subobject_index: usize,
+        size: u32,
+    },
+    UnalignedShaderConfigSize {
+        subobject_index: usize,
+        max_payload_size: u32,
+        max_attribute_size: u32,
+    },
+    MissingGlobalRootSignature,
+}
+
+impl std::fmt::Display for StateObjectDescError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnresolvedHitGroupImport {
+                subobject_index,
+                hit_group,
+                import,
+            } => write!(
+                f,
+                "[{subobject_index}]: hit group \"{hit_group}\" imports \"{import}\" which is not exported"
+            ),
+            Self::MissingAssociationExport {
+                subobject_index,
+                export,
+            } => write!(
+                f,
+                "[{subobject_index}]: association refers to \"{export}\" which is not exported"
+            ),
+            Self::InvalidAssociationSubobject { subobject_index } => write!(
+                f,
+                "[{subobject_index}]: association refers to a subobject outside of the state object"
+            ),
+            Self::InvalidProgramSubobject { subobject_index } => write!(
+                f,
+                "[{subobject_index}]: program refers to a subobject outside of the state object"
+            ),
+            Self::DuplicateExport { name } => write!(f, "\"{name}\" is exported more than once"),
+            Self::AttributeSizeTooLarge {
+                subobject_index,
+                size,
+            } => write!(
+                f,
+                "[{subobject_index}]: max attribute size {size} exceeds {D3D12_RAYTRACING_MAX_ATTRIBUTE_SIZE_IN_BYTES} bytes"
+            ),
+            Self::UnalignedShaderConfigSize {
+                subobject_index,
+                max_payload_size,
+                max_attribute_size,
+            } => write!(
+                f,
+                "[{subobject_index}]: max payload size {max_payload_size} and max attribute size {max_attribute_size} must be multiples of 4"
+            ),
+            Self::MissingGlobalRootSignature => {
+                write!(f, "raytracing pipeline has no global root signature")
+            }
+        }
+    }
+}
+
+impl std::error::Error for StateObjectDescError {}
+
+unsafe fn raw_slice<'a, T>(ptr: *const T, len: u32) -> &'a [T] {
+    if ptr.is_null() || len == 0 {
+        &[]
+    } else {
+        unsafe { std::slice::from_raw_parts(ptr, len as usize) }
+    }
+}
+
+unsafe fn raw_string(s: PCWSTR) -> Option<String> {
+    if s.is_null() {
+        None
+    } else {
+        Some(unsafe { s.to_string() }.unwrap_or_default())
+    }
+}
+
+unsafe fn raw_subobject_desc<T>(subobject: &D3D12_STATE_SUBOBJECT) -> &T {
+    unsafe { &*(subobject.pDesc as *const T) }
+}
+
+unsafe fn validate_state_object_desc(
+    desc: &D3D12_STATE_OBJECT_DESC,
+    existing: bool,
+) -> Vec<StateObjectDescError> {
+    let subobjects = unsafe { raw_slice(desc.pSubobjects, desc.NumSubobjects) };
+    let mut errors = vec![];
+    let mut exports: Vec<String> = vec![];
+    let mut exports_complete = !existing;
+    let mut has_global_root_signature = false;
+    let mut has_existing_collection = existing;
+    let mut has_dxil_library = false;
+    for (index, subobject) in subobjects.iter().enumerate() {
+        match subobject.Type {
+            D3D12_STATE_SUBOBJECT_TYPE_DXIL_LIBRARY => {
+                let lib: &D3D12_DXIL_LIBRARY_DESC = unsafe { raw_subobject_desc(subobject) };
+                has_dxil_library = true;
+                if lib.NumExports == 0 {
+                    exports_complete = false;
+                }
+                for export in unsafe { raw_slice(lib.pExports, lib.NumExports) } {
+                    exports.extend(unsafe { raw_string(export.Name) });
+                }
+            }
+            D3D12_STATE_SUBOBJECT_TYPE_EXISTING_COLLECTION => {
+                let collection: &D3D12_EXISTING_COLLECTION_DESC =
+                    unsafe { raw_subobject_desc(subobject) };
+                has_existing_collection = true;
+                if collection.NumExports == 0 {
+                    exports_complete = false;
+                }
+                for export in unsafe { raw_slice(collection.pExports, collection.NumExports) } {
+                    exports.extend(unsafe { raw_string(export.Name) });
+                }
+            }
+            D3D12_STATE_SUBOBJECT_TYPE_HIT_GROUP => {
+                let hit_group: &D3D12_HIT_GROUP_DESC = unsafe { raw_subobject_desc(subobject) };
+                exports.extend(unsafe { raw_string(hit_group.HitGroupExport) });
+            }
+            D3D12_STATE_SUBOBJECT_TYPE_GENERIC_PROGRAM => {
+                let program: &D3D12_GENERIC_PROGRAM_DESC = unsafe { raw_subobject_desc(subobject) };
+                exports.extend(unsafe { raw_string(program.ProgramName) });
+            }
+            D3D12_STATE_SUBOBJECT_TYPE_WORK_GRAPH => {
+                let work_graph: &D3D12_WORK_GRAPH_DESC = unsafe { raw_subobject_desc(subobject) };
+                exports.extend(unsafe { raw_string(work_graph.ProgramName) });
+            }
+            D3D12_STATE_SUBOBJECT_TYPE_GLOBAL_ROOT_SIGNATURE => {
+                has_global_root_signature = true;
+            }
+            D3D12_STATE_SUBOBJECT_TYPE_RAYTRACING_SHADER_CONFIG => {
+                let config: &D3D12_RAYTRACING_SHADER_CONFIG =
+                    unsafe { raw_subobject_desc(subobject) };
+                if config.MaxAttributeSizeInBytes > D3D12_RAYTRACING_MAX_ATTRIBUTE_SIZE_IN_BYTES {
+                    errors.push(StateObjectDescError::AttributeSizeTooLarge {
+                        subobject_index: index,
+                        size: config.MaxAttributeSizeInBytes,
+                    });
+                }
+                if !config.MaxPayloadSizeInBytes.is_multiple_of(4)
+                    || !config.MaxAttributeSizeInBytes.is_multiple_of(4)
+                {
+                    errors.push(StateObjectDescError::UnalignedShaderConfigSize {
+                        subobject_index: index,
+                        max_payload_size: config.MaxPayloadSizeInBytes,
+                        max_attribute_size: config.MaxAttributeSizeInBytes,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut sorted = exports.clone();
+    sorted.sort();
+    let mut prev: Option<&String> = None;
+    for name in sorted.iter() {
+        if prev == Some(name)
+            && !errors.iter().any(
+                |e| matches!(e, StateObjectDescError::DuplicateExport { name: n } if n == name),
+            )
+        {
+            errors.push(StateObjectDescError::DuplicateExport { name: name.clone() });
+        }
+        prev = Some(name);
+    }
+    let subobjects_range = subobjects.as_ptr_range();
+    for (index, subobject) in subobjects.iter().enumerate() {
+        let association_exports = match subobject.Type {
+            D3D12_STATE_SUBOBJECT_TYPE_HIT_GROUP => {
+                if !exports_complete {
+                    continue;
+                }
+                let hit_group: &D3D12_HIT_GROUP_DESC = unsafe { raw_subobject_desc(subobject) };
+                let name = unsafe { raw_string(hit_group.HitGroupExport) }.unwrap_or_default();
+                let imports = [
+                    hit_group.AnyHitShaderImport,
+                    hit_group.ClosestHitShaderImport,
+                    hit_group.IntersectionShaderImport,
+                ];
+                for import in imports.into_iter().filter_map(|i| unsafe { raw_string(i) }) {
+                    if !exports.contains(&import) {
+                        errors.push(StateObjectDescError::UnresolvedHitGroupImport {
+                            subobject_index: index,
+                            hit_group: name.clone(),
+                            import,
+                        });
+                    }
+                }
+                continue;
+            }
+            D3D12_STATE_SUBOBJECT_TYPE_SUBOBJECT_TO_EXPORTS_ASSOCIATION => {
+                let association: &D3D12_SUBOBJECT_TO_EXPORTS_ASSOCIATION =
+                    unsafe { raw_subobject_desc(subobject) };
+                if !subobjects_range.contains(&association.pSubobjectToAssociate) {
+                    errors.push(StateObjectDescError::InvalidAssociationSubobject {
+                        subobject_index: index,
+                    });
+                }
+                unsafe { raw_slice(association.pExports, association.NumExports) }
+            }
+            D3D12_STATE_SUBOBJECT_TYPE_DXIL_SUBOBJECT_TO_EXPORTS_ASSOCIATION => {
+                let association: &D3D12_DXIL_SUBOBJECT_TO_EXPORTS_ASSOCIATION =
+                    unsafe { raw_subobject_desc(subobject) };
+                unsafe { raw_slice(association.pExports, association.NumExports) }
+            }
+            D3D12_STATE_SUBOBJECT_TYPE_GENERIC_PROGRAM => {
+                let program: &D3D12_GENERIC_PROGRAM_DESC = unsafe { raw_subobject_desc(subobject) };
+                if unsafe { raw_slice(program.ppSubobjects, program.NumSubobjects) }
+                    .iter()
+                    .any(|sub| !subobjects_range.contains(sub))
+                {
+                    errors.push(StateObjectDescError::InvalidProgramSubobject {
+                        subobject_index: index,
+                    });
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        if !exports_complete {
+            continue;
+        }
+        for export in association_exports
+            .iter()
+            .filter_map(|e| unsafe { raw_string(*e) })
+        {
+            if !exports.contains(&export) {
+                errors.push(StateObjectDescError::MissingAssociationExport {
+                    subobject_index: index,
+                    export,
+                });
+            }
+        }
+    }
+    if desc.Type == D3D12_STATE_OBJECT_TYPE_RAYTRACING_PIPELINE
+        && !has_global_root_signature
+        && !has_existing_collection
+        && !has_dxil_library
+    {
+        errors.push(StateObjectDescError::MissingGlobalRootSignature);
+    }
+    errors
+}
+
+unsafe fn print_state_object_desc(desc: &D3D12_STATE_OBJECT_DESC) -> String {
+    use std::fmt::Write;
+
+    fn export_tree(s: &mut String, depth: usize, exports: &[D3D12_EXPORT_DESC]) {
+        for (i, export) in exports.iter().enumerate() {
+            s.push('|');
+            if depth > 0 {
+                s.push_str(&" ".repeat(2 * depth - 1));
+            }
+            write!(s, " [{i}]: ").unwrap();
+            if let Some(rename) = unsafe { raw_string(export.ExportToRename) } {
+                write!(s, "{rename} --> ").unwrap();
+            }
+            writeln!(
+                s,
+                "{}",
+                unsafe { raw_string(export.Name) }.unwrap_or_default()
+            )
+            .unwrap();
+        }
+    }
+
+    fn string_list(s: &mut String, strings: &[PCWSTR]) {
+        for (j, string) in strings.iter().enumerate() {
+            writeln!(
+                s,
+                "|  [{j}]: {}",
+                unsafe { raw_string(*string) }.unwrap_or_default()
+            )
+            .unwrap();
+        }
+    }
+
+    fn or_none(s: PCWSTR) -> String {
+        unsafe { raw_string(s) }.unwrap_or_else(|| "[none]".to_string())
+    }
+
+    let subobjects = unsafe { raw_slice(desc.pSubobjects, desc.NumSubobjects) };
+    let mut s = String::new();
+    s.push('\n');
+    s.push_str("--------------------------------------------------------------------\n");
+    write!(
+        s,
+        "| D3D12 State Object 0x{:016X}: ",
+        desc as *const D3D12_STATE_OBJECT_DESC as usize
+    )
+    .unwrap();
+    match desc.Type {
+        D3D12_STATE_OBJECT_TYPE_COLLECTION => s.push_str("Collection\n"),
+        D3D12_STATE_OBJECT_TYPE_RAYTRACING_PIPELINE => s.push_str("Raytracing Pipeline\n"),
+        D3D12_STATE_OBJECT_TYPE_EXECUTABLE => s.push_str("Executable\n"),
+        ty => writeln!(s, "Type {}", ty.0).unwrap(),
+    }
+    for (i, subobject) in subobjects.iter().enumerate() {
+        write!(s, "| [{i}]: ").unwrap();
+        match subobject.Type {
+            D3D12_STATE_SUBOBJECT_TYPE_STATE_OBJECT_CONFIG => {
+                let config: &D3D12_STATE_OBJECT_CONFIG = unsafe { raw_subobject_desc(subobject) };
+                writeln!(s, "State Object Config: 0x{:08X}", config.Flags.0).unwrap();
+            }
+            D3D12_STATE_SUBOBJECT_TYPE_GLOBAL_ROOT_SIGNATURE => {
+                writeln!(
+                    s,
+                    "Global Root Signature 0x{:016X}",
+                    subobject.pDesc as usize
+                )
+                .unwrap();
+            }
+            D3D12_STATE_SUBOBJECT_TYPE_LOCAL_ROOT_SIGNATURE => {
+                writeln!(
+                    s,
+                    "Local Root Signature 0x{:016X}",
+                    subobject.pDesc as usize
+                )
+                .unwrap();
+            }
+            D3D12_STATE_SUBOBJECT_TYPE_NODE_MASK => {
+                let mask: &u32 = unsafe { raw_subobject_desc(subobject) };
+                writeln!(s, "Node Mask: 0x{mask:08x}").unwrap();
+            }
+            D3D12_STATE_SUBOBJECT_TYPE_DXIL_LIBRARY => {
+                let lib: &D3D12_DXIL_LIBRARY_DESC = unsafe { raw_subobject_desc(subobject) };
+                writeln!(
+                    s,
+                    "DXIL Library 0x{:016X}, {} bytes",
+                    lib.DXILLibrary.pShaderBytecode as usize, lib.DXILLibrary.BytecodeLength
+                )
+                .unwrap();
+                export_tree(&mut s, 1, unsafe {
+                    raw_slice(lib.pExports, lib.NumExports)
+                });
+            }
+            D3D12_STATE_SUBOBJECT_TYPE_EXISTING_COLLECTION => {
+                let collection: &D3D12_EXISTING_COLLECTION_DESC =
+                    unsafe { raw_subobject_desc(subobject) };
+                writeln!(
+                    s,
+                    "Existing Library 0x{:016X}",
+                    collection
+                        .pExistingCollection
+                        .as_ref()
+                        .map_or(0, |c| c.as_raw() as usize)
+                )
+                .unwrap();
+                export_tree(&mut s, 1, unsafe {
+                    raw_slice(collection.pExports, collection.NumExports)
+                });
+            }
+            D3D12_STATE_SUBOBJECT_TYPE_SUBOBJECT_TO_EXPORTS_ASSOCIATION => {
+                let association: &D3D12_SUBOBJECT_TO_EXPORTS_ASSOCIATION =
+                    unsafe { raw_subobject_desc(subobject) };
+                if association.pSubobjectToAssociate.is_null() {
+                    writeln!(s, "Subobject to Exports Association ([none])").unwrap();
+                } else {
+                    let index = (association.pSubobjectToAssociate as usize)
+                        .wrapping_sub(desc.pSubobjects as usize)
+                        / std::mem::size_of::<D3D12_STATE_SUBOBJECT>();
+                    writeln!(s, "Subobject to Exports Association (Subobject [{index}])").unwrap();
+                }
+                string_list(&mut s, unsafe {
+                    raw_slice(association.pExports, association.NumExports)
+                });
+            }
+            D3D12_STATE_SUBOBJECT_TYPE_DXIL_SUBOBJECT_TO_EXPORTS_ASSOCIATION => {
+                let association: &D3D12_DXIL_SUBOBJECT_TO_EXPORTS_ASSOCIATION =
+                    unsafe { raw_subobject_desc(subobject) };
+                writeln!(
+                    s,
+                    "DXIL Subobjects to Exports Association ({})",
+                    or_none(association.SubobjectToAssociate)
+                )
+                .unwrap();
+                string_list(&mut s, unsafe {
+                    raw_slice(association.pExports, association.NumExports)
+                });
+            }
+            D3D12_STATE_SUBOBJECT_TYPE_RAYTRACING_SHADER_CONFIG => {
+                let config: &D3D12_RAYTRACING_SHADER_CONFIG =
+                    unsafe { raw_subobject_desc(subobject) };
+                s.push_str("Raytracing Shader Config\n");
+                writeln!(
+                    s,
+                    "|  [0]: Max Payload Size: {} bytes",
+                    config.MaxPayloadSizeInBytes
+                )
+                .unwrap();
+                writeln!(
+                    s,
+                    "|  [1]: Max Attribute Size: {} bytes",
+                    config.MaxAttributeSizeInBytes
+                )
+                .unwrap();
+            }
+            D3D12_STATE_SUBOBJECT_TYPE_RAYTRACING_PIPELINE_CONFIG => {
+                let config: &D3D12_RAYTRACING_PIPELINE_CONFIG =
+                    unsafe { raw_subobject_desc(subobject) };
+                s.push_str("Raytracing Pipeline Config\n");
+                writeln!(
+                    s,
+                    "|  [0]: Max Recursion Depth: {}",
+                    config.MaxTraceRecursionDepth
+                )
+                .unwrap();
+            }
+            D3D12_STATE_SUBOBJECT_TYPE_RAYTRACING_PIPELINE_CONFIG1 => {
+                let config: &D3D12_RAYTRACING_PIPELINE_CONFIG1 =
+                    unsafe { raw_subobject_desc(subobject) };
+                s.push_str("Raytracing Pipeline Config1\n");
+                writeln!(
+                    s,
+                    "|  [0]: Max Recursion Depth: {}",
+                    config.MaxTraceRecursionDepth
+                )
+                .unwrap();
+                writeln!(s, "|  [1]: Flags: 0x{:08X}", config.Flags.0).unwrap();
+            }
+            D3D12_STATE_SUBOBJECT_TYPE_HIT_GROUP => {
+                let hit_group: &D3D12_HIT_GROUP_DESC = unsafe { raw_subobject_desc(subobject) };
+                writeln!(s, "Hit Group ({})", or_none(hit_group.HitGroupExport)).unwrap();
+                writeln!(
+                    s,
+                    "|  [0]: Any Hit Import: {}",
+                    or_none(hit_group.AnyHitShaderImport)
+                )
+                .unwrap();
+                writeln!(
+                    s,
+                    "|  [1]: Closest Hit Import: {}",
+                    or_none(hit_group.ClosestHitShaderImport)
+                )
+                .unwrap();
+                writeln!(
+                    s,
+                    "|  [2]: Intersection Import: {}",
+                    or_none(hit_group.IntersectionShaderImport)
+                )
+                .unwrap();
+            }
+            D3D12_STATE_SUBOBJECT_TYPE_WORK_GRAPH => {
+                let work_graph: &D3D12_WORK_GRAPH_DESC = unsafe { raw_subobject_desc(subobject) };
+                writeln!(s, "Work Graph ({})", or_none(work_graph.ProgramName)).unwrap();
+            }
+            D3D12_STATE_SUBOBJECT_TYPE_GENERIC_PROGRAM => {
+                let program: &D3D12_GENERIC_PROGRAM_DESC = unsafe { raw_subobject_desc(subobject) };
+                writeln!(s, "Generic Program ({})", or_none(program.ProgramName)).unwrap();
+                string_list(&mut s, unsafe {
+                    raw_slice(program.pExports, program.NumExports)
+                });
+            }
+            ty => {
+                writeln!(s, "Subobject Type {}", ty.0).unwrap();
+            }
+        }
+        s.push_str("|--------------------------------------------------------------------\n");
+    }
+    s.push('\n');
+    s
+}
+
 #[derive(Clone, Debug)]
 pub struct StateObject {
     handle: ID3D12StateObject,
+    ty: D3D12_STATE_OBJECT_TYPE,
     name: Option<Name>,
 }
 
@@ -562,11 +1622,23 @@ impl StateObject {
         Builder::new(device.handle(), ty)
     }
 
+    #[inline]
+    pub fn add_to_state_object<'a>(&self, device: &'a Device) -> Builder<'a> {
+        let mut builder = Builder::new(device.handle(), self.ty);
+        builder.existing = Some(self.handle.clone());
+        builder
+    }
+
     #[inline]
     pub fn handle(&self) -> &ID3D12StateObject {
         &self.handle
     }
 
+    #[inline]
+    pub fn state_object_type(&self) -> D3D12_STATE_OBJECT_TYPE {
+        self.ty
+    }
+
     #[inline]
     pub fn name(&self) -> Option<&str> {
         self.name.as_ref().map(|n| n.as_str())
@@ -627,8 +1699,547 @@ impl StateObjectProperties {
         }
     }
 
+    #[inline]
+    pub fn get_program_identifier(&self, program_name: impl AsRef<str>) -> ProgramIdentifier {
+        let handle: ID3D12StateObjectProperties1 = self.handle.cast().unwrap();
+        unsafe {
+            ProgramIdentifier(handle.GetProgramIdentifier(&HSTRING::from(program_name.as_ref())))
+        }
+    }
+
     #[inline]
     pub fn handle(&self) -> &ID3D12StateObjectProperties {
         &self.handle
     }
 }
+
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+#[repr(transparent)]
+pub struct ProgramIdentifier(pub D3D12_PROGRAM_IDENTIFIER);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct WorkGraphMemoryRequirements {
+    pub min_size_in_bytes: u64,
+    pub max_size_in_bytes: u64,
+    pub size_granularity_in_bytes: u32,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum NodeInputError {
+    InvalidEntrypointIndex {
+        index: u32,
+        num_entrypoints: u32,
+    },
+    UnknownNode {
+        name: String,
+        array_index: u32,
+    },
+    PartialRecord {
+        entrypoint_index: u32,
+        len: usize,
+        stride: u64,
+    },
+    RecordStrideTooSmall {
+        entrypoint_index: u32,
+        stride: u64,
+        record_size: u32,
+    },
+    MisalignedRecords {
+        entrypoint_index: u32,
+        stride: u64,
+        alignment: u32,
+    },
+}
+
+impl std::fmt::Display for NodeInputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidEntrypointIndex {
+                index,
+                num_entrypoints,
+            } => write!(
+                f,
+                "entrypoint index {index} is out of range (entrypoints: {num_entrypoints})"
+            ),
+            Self::UnknownNode { name, array_index } => {
+                write!(f, "node {name}[{array_index}] is not in the runtime data")
+            }
+            Self::PartialRecord {
+                entrypoint_index,
+                len,
+                stride,
+            } => write!(
+                f,
+                "entrypoint {entrypoint_index}: {len} bytes is not a whole number of {stride}-byte records"
+            ),
+            Self::RecordStrideTooSmall {
+                entrypoint_index,
+                stride,
+                record_size,
+            } => write!(
+                f,
+                "entrypoint {entrypoint_index}: record stride {stride} is smaller than the record size {record_size}"
+            ),
+            Self::MisalignedRecords {
+                entrypoint_index,
+                stride,
+                alignment,
+            } => write!(
+                f,
+                "entrypoint {entrypoint_index}: records with stride {stride} are not aligned to {alignment} bytes"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for NodeInputError {}
+
+#[derive(Clone, Debug)]
+pub struct WorkGraphProperties {
+    handle: ID3D12WorkGraphProperties,
+}
+
+impl WorkGraphProperties {
+    #[inline]
+    pub fn new(state_object: &StateObject) -> Self {
+        Self {
+            handle: state_object.handle.cast().unwrap(),
+        }
+    }
+
+    #[inline]
+    pub fn get_num_work_graphs(&self) -> u32 {
+        unsafe { self.handle.GetNumWorkGraphs() }
+    }
+
+    #[inline]
+    pub fn get_program_name(&self, work_graph_index: u32) -> String {
+        unsafe {
+            self.handle
+                .GetProgramName(work_graph_index)
+                .to_string()
+                .unwrap_or_default()
+        }
+    }
+
+    #[inline]
+    pub fn get_work_graph_index(&self, program_name: impl AsRef<str>) -> u32 {
+        unsafe {
+            self.handle
+                .GetWorkGraphIndex(&HSTRING::from(program_name.as_ref()))
+        }
+    }
+
+    #[inline]
+    pub fn get_num_nodes(&self, work_graph_index: u32) -> u32 {
+        unsafe { self.handle.GetNumNodes(work_graph_index) }
+    }
+
+    #[inline]
+    pub fn get_node_id(&self, work_graph_index: u32, node_index: u32) -> NodeId {
+        unsafe { NodeId::from_raw(&self.handle.GetNodeID(work_graph_index, node_index)) }
+    }
+
+    #[inline]
+    pub fn get_node_index(&self, work_graph_index: u32, node_id: &NodeId) -> u32 {
+        unsafe { self.handle.GetNodeIndex(work_graph_index, node_id.as_raw()) }
+    }
+
+    #[inline]
+    pub fn get_node_local_root_arguments_table_index(
+        &self,
+        work_graph_index: u32,
+        node_index: u32,
+    ) -> u32 {
+        unsafe {
+            self.handle
+                .GetNodeLocalRootArgumentsTableIndex(work_graph_index, node_index)
+        }
+    }
+
+    #[inline]
+    pub fn get_num_entrypoints(&self, work_graph_index: u32) -> u32 {
+        unsafe { self.handle.GetNumEntrypoints(work_graph_index) }
+    }
+
+    #[inline]
+    pub fn get_entrypoint_id(&self, work_graph_index: u32, entrypoint_index: u32) -> NodeId {
+        unsafe {
+            NodeId::from_raw(
+                &self
+                    .handle
+                    .GetEntrypointID(work_graph_index, entrypoint_index),
+            )
+        }
+    }
+
+    #[inline]
+    pub fn get_entrypoint_index(&self, work_graph_index: u32, node_id: &NodeId) -> u32 {
+        unsafe {
+            self.handle
+                .GetEntrypointIndex(work_graph_index, node_id.as_raw())
+        }
+    }
+
+    #[inline]
+    pub fn get_entrypoint_record_size_in_bytes(
+        &self,
+        work_graph_index: u32,
+        entrypoint_index: u32,
+    ) -> u32 {
+        unsafe {
+            self.handle
+                .GetEntrypointRecordSizeInBytes(work_graph_index, entrypoint_index)
+        }
+    }
+
+    #[inline]
+    pub fn get_entrypoint_record_alignment_in_bytes(
+        &self,
+        work_graph_index: u32,
+        entrypoint_index: u32,
+    ) -> u32 {
+        unsafe {
+            self.handle
+                .GetEntrypointRecordAlignmentInBytes(work_graph_index, entrypoint_index)
+        }
+    }
+
+    #[inline]
+    pub fn get_work_graph_memory_requirements(
+        &self,
+        work_graph_index: u32,
+    ) -> WorkGraphMemoryRequirements {
+        let mut reqs = D3D12_WORK_GRAPH_MEMORY_REQUIREMENTS::default();
+        unsafe {
+            self.handle
+                .GetWorkGraphMemoryRequirements(work_graph_index, &mut reqs);
+        }
+        WorkGraphMemoryRequirements {
+            min_size_in_bytes: reqs.MinSizeInBytes,
+            max_size_in_bytes: reqs.MaxSizeInBytes,
+            size_granularity_in_bytes: reqs.SizeGranularityInBytes,
+        }
+    }
+
+    pub fn validate_node_cpu_input(
+        &self,
+        work_graph_index: u32,
+        input: &NodeCpuInput,
+        runtime_data: &reflection::RuntimeData,
+    ) -> Result<(), NodeInputError> {
+        let entrypoint_index = input.entrypoint_index();
+        let num_entrypoints = self.get_num_entrypoints(work_graph_index);
+        if entrypoint_index >= num_entrypoints {
+            return Err(NodeInputError::InvalidEntrypointIndex {
+                index: entrypoint_index,
+                num_entrypoints,
+            });
+        }
+        let id = self.get_entrypoint_id(work_graph_index, entrypoint_index);
+        let node = runtime_data
+            .node(&id.name(), id.array_index())
+            .ok_or_else(|| NodeInputError::UnknownNode {
+                name: id.name(),
+                array_index: id.array_index(),
+            })?;
+        validate_node_records(input, &node.input.unwrap_or_default())
+    }
+}
+
+fn validate_node_records(
+    input: &NodeCpuInput,
+    layout: &reflection::NodeRecordLayout,
+) -> Result<(), NodeInputError> {
+    let entrypoint_index = input.entrypoint_index();
+    let stride = input.record_stride_in_bytes();
+    let record_size = layout.size_in_bytes;
+    if record_size == 0 {
+        return Ok(());
+    }
+    if stride < record_size as u64 {
+        return Err(NodeInputError::RecordStrideTooSmall {
+            entrypoint_index,
+            stride,
+            record_size,
+        });
+    }
+    let alignment = layout.alignment_in_bytes.max(1);
+    if !stride.is_multiple_of(alignment as u64)
+        || !(input.records_ptr() as usize).is_multiple_of(alignment as usize)
+    {
+        return Err(NodeInputError::MisalignedRecords {
+            entrypoint_index,
+            stride,
+            alignment,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subobject<T>(ty: D3D12_STATE_SUBOBJECT_TYPE, desc: &T) -> D3D12_STATE_SUBOBJECT {
+        D3D12_STATE_SUBOBJECT {
+            Type: ty,
+            pDesc: desc as *const T as *const _,
+        }
+    }
+
+    #[test]
+    fn validate_exports_test() {
+        let ray_gen = HSTRING::from("RayGen");
+        let closest_hit = HSTRING::from("ClosestHit");
+        let missing = HSTRING::from("Missing");
+        let hit_group_name = HSTRING::from("HitGroup");
+        let exports = [
+            D3D12_EXPORT_DESC {
+                Name: PCWSTR(ray_gen.as_ptr()),
+                ..Default::default()
+            },
+            D3D12_EXPORT_DESC {
+                Name: PCWSTR(closest_hit.as_ptr()),
+                ..Default::default()
+            },
+        ];
+        let lib = D3D12_DXIL_LIBRARY_DESC {
+            NumExports: exports.len() as u32,
+            pExports: exports.as_ptr() as *mut _,
+            ..Default::default()
+        };
+        let hit_group = D3D12_HIT_GROUP_DESC {
+            HitGroupExport: PCWSTR(hit_group_name.as_ptr()),
+            Type: D3D12_HIT_GROUP_TYPE_TRIANGLES,
+            ClosestHitShaderImport: PCWSTR(closest_hit.as_ptr()),
+            AnyHitShaderImport: PCWSTR(missing.as_ptr()),
+            ..Default::default()
+        };
+        let duplicate = D3D12_HIT_GROUP_DESC {
+            HitGroupExport: PCWSTR(ray_gen.as_ptr()),
+            Type: D3D12_HIT_GROUP_TYPE_TRIANGLES,
+            ..Default::default()
+        };
+        let config = D3D12_RAYTRACING_SHADER_CONFIG {
+            MaxPayloadSizeInBytes: 16,
+            MaxAttributeSizeInBytes: 8,
+        };
+        let mut subobjects = Vec::with_capacity(5);
+        subobjects.extend([
+            subobject(D3D12_STATE_SUBOBJECT_TYPE_DXIL_LIBRARY, &lib),
+            subobject(D3D12_STATE_SUBOBJECT_TYPE_HIT_GROUP, &hit_group),
+            subobject(D3D12_STATE_SUBOBJECT_TYPE_HIT_GROUP, &duplicate),
+            subobject(D3D12_STATE_SUBOBJECT_TYPE_RAYTRACING_SHADER_CONFIG, &config),
+        ]);
+        let association_exports = [PCWSTR(hit_group_name.as_ptr()), PCWSTR(missing.as_ptr())];
+        let association = D3D12_SUBOBJECT_TO_EXPORTS_ASSOCIATION {
+            pSubobjectToAssociate: &subobjects[3],
+            NumExports: association_exports.len() as u32,
+            pExports: association_exports.as_ptr(),
+        };
+        subobjects.push(subobject(
+            D3D12_STATE_SUBOBJECT_TYPE_SUBOBJECT_TO_EXPORTS_ASSOCIATION,
+            &association,
+        ));
+        let desc = D3D12_STATE_OBJECT_DESC {
+            Type: D3D12_STATE_OBJECT_TYPE_COLLECTION,
+            NumSubobjects: subobjects.len() as u32,
+            pSubobjects: subobjects.as_ptr(),
+        };
+        let errors = unsafe { validate_state_object_desc(&desc, false) };
+        assert_eq!(
+            errors,
+            vec![
+                StateObjectDescError::DuplicateExport {
+                    name: "RayGen".to_string()
+                },
+                StateObjectDescError::UnresolvedHitGroupImport {
+                    subobject_index: 1,
+                    hit_group: "HitGroup".to_string(),
+                    import: "Missing".to_string(),
+                },
+                StateObjectDescError::MissingAssociationExport {
+                    subobject_index: 4,
+                    export: "Missing".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            unsafe { validate_state_object_desc(&desc, true) },
+            vec![StateObjectDescError::DuplicateExport {
+                name: "RayGen".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn validate_shader_config_test() {
+        let config = D3D12_RAYTRACING_SHADER_CONFIG {
+            MaxPayloadSizeInBytes: 18,
+            MaxAttributeSizeInBytes: 36,
+        };
+        let subobjects = [subobject(
+            D3D12_STATE_SUBOBJECT_TYPE_RAYTRACING_SHADER_CONFIG,
+            &config,
+        )];
+        let desc = D3D12_STATE_OBJECT_DESC {
+            Type: D3D12_STATE_OBJECT_TYPE_RAYTRACING_PIPELINE,
+            NumSubobjects: subobjects.len() as u32,
+            pSubobjects: subobjects.as_ptr(),
+        };
+        let errors = unsafe { validate_state_object_desc(&desc, false) };
+        assert_eq!(
+            errors,
+            vec![
+                StateObjectDescError::AttributeSizeTooLarge {
+                    subobject_index: 0,
+                    size: 36
+                },
+                StateObjectDescError::UnalignedShaderConfigSize {
+                    subobject_index: 0,
+                    max_payload_size: 18,
+                    max_attribute_size: 36,
+                },
+                StateObjectDescError::MissingGlobalRootSignature,
+            ]
+        );
+        let errors = unsafe { validate_state_object_desc(&desc, true) };
+        assert_eq!(errors.len(), 2);
+        assert!(!errors.contains(&StateObjectDescError::MissingGlobalRootSignature));
+        let lib = D3D12_DXIL_LIBRARY_DESC::default();
+        let subobjects = [
+            subobject(D3D12_STATE_SUBOBJECT_TYPE_DXIL_LIBRARY, &lib),
+            subobject(D3D12_STATE_SUBOBJECT_TYPE_RAYTRACING_SHADER_CONFIG, &config),
+        ];
+        let desc = D3D12_STATE_OBJECT_DESC {
+            Type: D3D12_STATE_OBJECT_TYPE_RAYTRACING_PIPELINE,
+            NumSubobjects: subobjects.len() as u32,
+            pSubobjects: subobjects.as_ptr(),
+        };
+        let errors = unsafe { validate_state_object_desc(&desc, false) };
+        assert_eq!(errors.len(), 2);
+        assert!(!errors.contains(&StateObjectDescError::MissingGlobalRootSignature));
+    }
+
+    #[test]
+    fn validate_unknown_association_subobject_test() {
+        let adapter = crate::enum_warp_adapter().unwrap();
+        let device = crate::Device::new()
+            .adapter(&adapter)
+            .min_feature_level(windows::Win32::Graphics::Direct3D::D3D_FEATURE_LEVEL_12_1)
+            .build()
+            .unwrap();
+        let config = RaytracingShaderConfig::new()
+            .max_payload_size_in_bytes(16)
+            .max_attribute_size_in_bytes(8);
+        let association = SubobjectToExportsAssociation::new(&config).exports(&["RayGen"]);
+        let builder =
+            StateObject::new(&device, D3D12_STATE_OBJECT_TYPE_COLLECTION).associate(&association);
+        assert_eq!(
+            builder.validate(),
+            Err(vec![StateObjectDescError::InvalidAssociationSubobject {
+                subobject_index: 0
+            }])
+        );
+        assert!(
+            builder
+                .print_desc()
+                .contains("Subobject to Exports Association ([none])")
+        );
+    }
+
+    #[test]
+    fn print_desc_test() {
+        let hit_group_name = HSTRING::from("HitGroup");
+        let closest_hit = HSTRING::from("ClosestHit");
+        let hit_group = D3D12_HIT_GROUP_DESC {
+            HitGroupExport: PCWSTR(hit_group_name.as_ptr()),
+            Type: D3D12_HIT_GROUP_TYPE_TRIANGLES,
+            ClosestHitShaderImport: PCWSTR(closest_hit.as_ptr()),
+            ..Default::default()
+        };
+        let config = D3D12_RAYTRACING_SHADER_CONFIG {
+            MaxPayloadSizeInBytes: 16,
+            MaxAttributeSizeInBytes: 8,
+        };
+        let subobjects = [
+            subobject(D3D12_STATE_SUBOBJECT_TYPE_HIT_GROUP, &hit_group),
+            subobject(D3D12_STATE_SUBOBJECT_TYPE_RAYTRACING_SHADER_CONFIG, &config),
+        ];
+        let desc = D3D12_STATE_OBJECT_DESC {
+            Type: D3D12_STATE_OBJECT_TYPE_RAYTRACING_PIPELINE,
+            NumSubobjects: subobjects.len() as u32,
+            pSubobjects: subobjects.as_ptr(),
+        };
+        let s = unsafe { print_state_object_desc(&desc) };
+        let lines = s.lines().skip(3).take(9).collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [
+                "| [0]: Hit Group (HitGroup)",
+                "|  [0]: Any Hit Import: [none]",
+                "|  [1]: Closest Hit Import: ClosestHit",
+                "|  [2]: Intersection Import: [none]",
+                "|--------------------------------------------------------------------",
+                "| [1]: Raytracing Shader Config",
+                "|  [0]: Max Payload Size: 16 bytes",
+                "|  [1]: Max Attribute Size: 8 bytes",
+                "|--------------------------------------------------------------------",
+            ]
+        );
+        assert!(s.contains(": Raytracing Pipeline\n"));
+    }
+
+    #[test]
+    fn validate_node_records_test() {
+        use crate::reflection::NodeRecordLayout;
+
+        let layout = |size_in_bytes, alignment_in_bytes| NodeRecordLayout {
+            size_in_bytes,
+            alignment_in_bytes,
+        };
+        let records = [[0u32; 4]; 2];
+        let input = NodeCpuInput::new(1, &records);
+        assert_eq!(validate_node_records(&input, &layout(16, 4)), Ok(()));
+        assert_eq!(validate_node_records(&input, &layout(0, 0)), Ok(()));
+        assert_eq!(
+            validate_node_records(&input, &layout(20, 4)),
+            Err(NodeInputError::RecordStrideTooSmall {
+                entrypoint_index: 1,
+                stride: 16,
+                record_size: 20,
+            })
+        );
+        let packed = [[0u32; 3]; 2];
+        assert_eq!(
+            validate_node_records(&NodeCpuInput::new(0, &packed), &layout(12, 8)),
+            Err(NodeInputError::MisalignedRecords {
+                entrypoint_index: 0,
+                stride: 12,
+                alignment: 8,
+            })
+        );
+
+        let buffer = [0u8; 40];
+        let bytes = &buffer[buffer.as_ptr().align_offset(4)..];
+        let input = NodeCpuInput::from_bytes(2, &bytes[1..33], 16).unwrap();
+        assert_eq!(input.num_records(), 2);
+        assert_eq!(
+            validate_node_records(&input, &layout(16, 4)),
+            Err(NodeInputError::MisalignedRecords {
+                entrypoint_index: 2,
+                stride: 16,
+                alignment: 4,
+            })
+        );
+        assert_eq!(
+            NodeCpuInput::from_bytes(2, &bytes[..20], 16).err(),
+            Some(NodeInputError::PartialRecord {
+                entrypoint_index: 2,
+                len: 20,
+                stride: 16,
+            })
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use crate::reflection::{
+    Array, Matrix, MatrixMajor, Member, ShaderBufferDesc, ShaderInputBindDesc, Struct, Type, Vector,
+};
+use windows::Win32::Graphics::Direct3D::*;
+
+pub(crate) fn bind(
+    name: &str,
+    ty: D3D_SHADER_INPUT_TYPE,
+    bind_point: u32,
+    bind_count: u32,
+    space: u32,
+) -> ShaderInputBindDesc {
+    ShaderInputBindDesc {
+        name: name.into(),
+        ty,
+        bind_point,
+        bind_count,
+        flags: D3D_SHADER_INPUT_FLAGS(0),
+        return_type: D3D_RESOURCE_RETURN_TYPE(0),
+        dimension: D3D_SRV_DIMENSION_UNKNOWN,
+        num_samples: 0,
+        space,
+        id: 0,
+    }
+}
+
+pub(crate) fn member(name: &str, ty: Type, offset: usize) -> Member {
+    Member {
+        name: name.into(),
+        ty,
+        offset,
+    }
+}
+
+pub(crate) fn float(len: usize) -> Type {
+    match len {
+        1 => Type::Float,
+        _ => Type::Vector(Vector {
+            ty: Box::new(Type::Float),
+            len,
+        }),
+    }
+}
+
+pub(crate) fn array(ty: Type, len: usize) -> Type {
+    Type::Array(Array {
+        ty: Box::new(ty),
+        len,
+    })
+}
+
+pub(crate) fn per_frame(offsets: [usize; 9], light: [usize; 3], size: usize) -> ShaderBufferDesc {
+    let light = Type::Struct(Struct {
+        name: "Light".into(),
+        members: vec![
+            member("pos", float(3), light[0]),
+            member("radius", Type::Float, light[1]),
+            member("color", float(3), light[2]),
+        ],
+    });
+    let view_proj = Type::Matrix(Matrix {
+        ty: Box::new(Type::Float),
+        columns: 4,
+        rows: 4,
+        major: MatrixMajor::Columns,
+    });
+    let types = [
+        view_proj,
+        float(3),
+        Type::Float,
+        float(2),
+        float(3),
+        array(Type::Float, 3),
+        Type::Float,
+        array(light, 2),
+        Type::Uint,
+    ];
+    let names = [
+        "view_proj",
+        "light_dir",
+        "intensity",
+        "uv_scale",
+        "tint",
+        "weights",
+        "bias",
+        "lights",
+        "count",
+    ];
+    let variables = names
+        .into_iter()
+        .zip(types)
+        .zip(offsets)
+        .map(|((name, ty), offset)| member(name, ty, offset))
+        .collect::<Vec<_>>();
+    ShaderBufferDesc {
+        name: "PerFrame".into(),
+        ty: D3D_CT_CBUFFER,
+        size,
+        members: variables.iter().map(|v| v.ty.clone()).collect(),
+        variables,
+    }
+}
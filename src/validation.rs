@@ -0,0 +1,1103 @@
+use crate::codegen::{Layout, type_size};
+use crate::layout::{InputLayoutMismatch, LayoutElement, component_kind, input_layout_mismatches};
+use crate::reflection::{
+    Function, ShaderBufferDesc, ShaderInputBindDesc, ShaderKind, ShaderReflection,
+    SignatureParameterDesc,
+};
+use crate::{InputElementDesc, RootSignatureDesc};
+use windows::Win32::Graphics::Direct3D::*;
+use windows::Win32::Graphics::Direct3D12::*;
+use windows::Win32::Graphics::Dxgi::Common::*;
+
+#[derive(Clone, Default, Debug)]
+pub struct ShaderSignature {
+    pub inputs: Vec<SignatureParameterDesc>,
+    pub outputs: Vec<SignatureParameterDesc>,
+    pub patch_constants: Vec<SignatureParameterDesc>,
+}
+
+impl ShaderSignature {
+    #[inline]
+    pub fn new(reflection: &ShaderReflection) -> windows::core::Result<Self> {
+        Ok(Self {
+            inputs: reflection.input_parameters()?,
+            outputs: reflection.output_parameter_descs()?,
+            patch_constants: reflection.patch_constant_parameter_descs()?,
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Stage {
+    Vertex,
+    Hull,
+    Domain,
+    Geometry,
+    Pixel,
+    Mesh,
+}
+
+impl std::fmt::Display for Stage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Vertex => "VS",
+            Self::Hull => "HS",
+            Self::Domain => "DS",
+            Self::Geometry => "GS",
+            Self::Pixel => "PS",
+            Self::Mesh => "MS",
+        };
+        f.write_str(s)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum LinkageError {
+    MissingOutput {
+        from: Stage,
+        to: Stage,
+        semantic_name: String,
+        semantic_index: u32,
+    },
+    Register {
+        from: Stage,
+        to: Stage,
+        semantic_name: String,
+        semantic_index: u32,
+        output: u32,
+        input: u32,
+    },
+    Mask {
+        from: Stage,
+        to: Stage,
+        semantic_name: String,
+        semantic_index: u32,
+        output: u8,
+        input: u8,
+    },
+    ComponentType {
+        from: Stage,
+        to: Stage,
+        semantic_name: String,
+        semantic_index: u32,
+        output: D3D_REGISTER_COMPONENT_TYPE,
+        input: D3D_REGISTER_COMPONENT_TYPE,
+    },
+    InputLayout(InputLayoutMismatch),
+    MissingTarget {
+        index: u32,
+    },
+    TargetComponentType {
+        index: u32,
+        format: DXGI_FORMAT,
+        output: D3D_REGISTER_COMPONENT_TYPE,
+    },
+}
+
+impl std::fmt::Display for LinkageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingOutput {
+                from,
+                to,
+                semantic_name,
+                semantic_index,
+            } => write!(
+                f,
+                "{to} reads `{semantic_name}{semantic_index}` but {from} does not write it"
+            ),
+            Self::Register {
+                from,
+                to,
+                semantic_name,
+                semantic_index,
+                output,
+                input,
+            } => write!(
+                f,
+                "`{semantic_name}{semantic_index}` is in register {output} in {from} but {input} in {to}"
+            ),
+            Self::Mask {
+                from,
+                to,
+                semantic_name,
+                semantic_index,
+                output,
+                input,
+            } => write!(
+                f,
+                "{to} reads components {input:#06b} of `{semantic_name}{semantic_index}` but {from} writes {output:#06b}"
+            ),
+            Self::ComponentType {
+                from,
+                to,
+                semantic_name,
+                semantic_index,
+                output,
+                input,
+            } => write!(
+                f,
+                "`{semantic_name}{semantic_index}` is {output:?} in {from} but {input:?} in {to}"
+            ),
+            Self::InputLayout(e) => e.fmt(f),
+            Self::MissingTarget { index } => {
+                write!(f, "render target {index} is not written by PS")
+            }
+            Self::TargetComponentType {
+                index,
+                format,
+                output,
+            } => write!(
+                f,
+                "render target {index} ({format:?}) does not match the PS output type {output:?}"
+            ),
+        }
+    }
+}
+
+macro_rules! format_table {
+    ($($ty:ident => [$($format:ident),* $(,)?];)*) => {
+        fn format_component_type(format: DXGI_FORMAT) -> Option<D3D_REGISTER_COMPONENT_TYPE> {
+            match format {
+                $($($format)|* => Some($ty),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+format_table! {
+    D3D_REGISTER_COMPONENT_FLOAT32 => [
+        DXGI_FORMAT_R32G32B32A32_FLOAT,
+        DXGI_FORMAT_R16G16B16A16_FLOAT,
+        DXGI_FORMAT_R16G16B16A16_UNORM,
+        DXGI_FORMAT_R16G16B16A16_SNORM,
+        DXGI_FORMAT_R10G10B10A2_UNORM,
+        DXGI_FORMAT_R8G8B8A8_UNORM,
+        DXGI_FORMAT_R8G8B8A8_UNORM_SRGB,
+        DXGI_FORMAT_R8G8B8A8_SNORM,
+        DXGI_FORMAT_B8G8R8A8_UNORM,
+        DXGI_FORMAT_B8G8R8A8_UNORM_SRGB,
+        DXGI_FORMAT_B5G5R5A1_UNORM,
+        DXGI_FORMAT_B4G4R4A4_UNORM,
+        DXGI_FORMAT_R32G32B32_FLOAT,
+        DXGI_FORMAT_R11G11B10_FLOAT,
+        DXGI_FORMAT_R9G9B9E5_SHAREDEXP,
+        DXGI_FORMAT_B5G6R5_UNORM,
+        DXGI_FORMAT_B8G8R8X8_UNORM,
+        DXGI_FORMAT_B8G8R8X8_UNORM_SRGB,
+        DXGI_FORMAT_R32G32_FLOAT,
+        DXGI_FORMAT_R16G16_FLOAT,
+        DXGI_FORMAT_R16G16_UNORM,
+        DXGI_FORMAT_R16G16_SNORM,
+        DXGI_FORMAT_R8G8_UNORM,
+        DXGI_FORMAT_R8G8_SNORM,
+        DXGI_FORMAT_R32_FLOAT,
+        DXGI_FORMAT_R16_FLOAT,
+        DXGI_FORMAT_R16_UNORM,
+        DXGI_FORMAT_R16_SNORM,
+        DXGI_FORMAT_R8_UNORM,
+        DXGI_FORMAT_R8_SNORM,
+    ];
+    D3D_REGISTER_COMPONENT_UINT32 => [
+        DXGI_FORMAT_R32G32B32A32_UINT,
+        DXGI_FORMAT_R16G16B16A16_UINT,
+        DXGI_FORMAT_R10G10B10A2_UINT,
+        DXGI_FORMAT_R8G8B8A8_UINT,
+        DXGI_FORMAT_R32G32B32_UINT,
+        DXGI_FORMAT_R32G32_UINT,
+        DXGI_FORMAT_R16G16_UINT,
+        DXGI_FORMAT_R8G8_UINT,
+        DXGI_FORMAT_R32_UINT,
+        DXGI_FORMAT_R16_UINT,
+        DXGI_FORMAT_R8_UINT,
+    ];
+    D3D_REGISTER_COMPONENT_SINT32 => [
+        DXGI_FORMAT_R32G32B32A32_SINT,
+        DXGI_FORMAT_R16G16B16A16_SINT,
+        DXGI_FORMAT_R8G8B8A8_SINT,
+        DXGI_FORMAT_R32G32B32_SINT,
+        DXGI_FORMAT_R32G32_SINT,
+        DXGI_FORMAT_R16G16_SINT,
+        DXGI_FORMAT_R8G8_SINT,
+        DXGI_FORMAT_R32_SINT,
+        DXGI_FORMAT_R16_SINT,
+        DXGI_FORMAT_R8_SINT,
+    ];
+}
+
+fn is_system_generated(input: &SignatureParameterDesc) -> bool {
+    input.register == u32::MAX
+        || matches!(
+            input.system_value_type,
+            D3D_NAME_VERTEX_ID
+                | D3D_NAME_INSTANCE_ID
+                | D3D_NAME_PRIMITIVE_ID
+                | D3D_NAME_IS_FRONT_FACE
+                | D3D_NAME_SAMPLE_INDEX
+                | D3D_NAME_COVERAGE
+                | D3D_NAME_INNER_COVERAGE
+                | D3D_NAME_BARYCENTRICS
+                | D3D_NAME_SHADINGRATE
+        )
+}
+
+fn link(from: (Stage, &ShaderSignature), to: (Stage, &ShaderSignature)) -> Vec<LinkageError> {
+    let mut errors = vec![];
+    let per_primitive: &[_] = match from.0 {
+        Stage::Mesh => &from.1.patch_constants,
+        _ => &[],
+    };
+    let outputs = from.1.outputs.iter().chain(per_primitive);
+    let (from, to, inputs) = (from.0, to.0, &to.1.inputs);
+    for input in inputs.iter().filter(|input| !is_system_generated(input)) {
+        let semantic_name = input.semantic_name.clone();
+        let semantic_index = input.semantic_index;
+        let Some(output) = outputs.clone().find(|output| {
+            output.semantic_index == semantic_index
+                && output.semantic_name.eq_ignore_ascii_case(&semantic_name)
+        }) else {
+            errors.push(LinkageError::MissingOutput {
+                from,
+                to,
+                semantic_name,
+                semantic_index,
+            });
+            continue;
+        };
+        if output.register != input.register {
+            errors.push(LinkageError::Register {
+                from,
+                to,
+                semantic_name: semantic_name.clone(),
+                semantic_index,
+                output: output.register,
+                input: input.register,
+            });
+        }
+        if input.mask & !output.mask != 0 {
+            errors.push(LinkageError::Mask {
+                from,
+                to,
+                semantic_name: semantic_name.clone(),
+                semantic_index,
+                output: output.mask,
+                input: input.mask,
+            });
+        }
+        if component_kind(output.component_type) != component_kind(input.component_type) {
+            errors.push(LinkageError::ComponentType {
+                from,
+                to,
+                semantic_name,
+                semantic_index,
+                output: output.component_type,
+                input: input.component_type,
+            });
+        }
+    }
+    errors
+}
+
+fn semantic_name(element: &InputElementDesc) -> String {
+    let name = element.desc().SemanticName;
+    if name.is_null() {
+        return String::new();
+    }
+    unsafe { name.to_string().unwrap_or_default() }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct PipelineLinkage<'a> {
+    vs: Option<&'a ShaderSignature>,
+    hs: Option<&'a ShaderSignature>,
+    ds: Option<&'a ShaderSignature>,
+    gs: Option<&'a ShaderSignature>,
+    ps: Option<&'a ShaderSignature>,
+    ms: Option<&'a ShaderSignature>,
+    input_layout: Option<&'a [InputElementDesc<'a>]>,
+    render_target_formats: &'a [DXGI_FORMAT],
+}
+
+impl<'a> PipelineLinkage<'a> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn vs(mut self, signature: &'a ShaderSignature) -> Self {
+        self.vs = Some(signature);
+        self
+    }
+
+    #[inline]
+    pub fn hs(mut self, signature: &'a ShaderSignature) -> Self {
+        self.hs = Some(signature);
+        self
+    }
+
+    #[inline]
+    pub fn ds(mut self, signature: &'a ShaderSignature) -> Self {
+        self.ds = Some(signature);
+        self
+    }
+
+    #[inline]
+    pub fn gs(mut self, signature: &'a ShaderSignature) -> Self {
+        self.gs = Some(signature);
+        self
+    }
+
+    #[inline]
+    pub fn ps(mut self, signature: &'a ShaderSignature) -> Self {
+        self.ps = Some(signature);
+        self
+    }
+
+    #[inline]
+    pub fn ms(mut self, signature: &'a ShaderSignature) -> Self {
+        self.ms = Some(signature);
+        self
+    }
+
+    #[inline]
+    pub fn input_layout(mut self, elements: &'a [InputElementDesc<'a>]) -> Self {
+        self.input_layout = Some(elements);
+        self
+    }
+
+    #[inline]
+    pub fn render_target_formats(mut self, formats: &'a [DXGI_FORMAT]) -> Self {
+        self.render_target_formats = formats;
+        self
+    }
+
+    pub fn validate(&self) -> Result<(), Vec<LinkageError>> {
+        let mut errors = vec![];
+        let stages = match self.ms {
+            Some(ms) => vec![(Stage::Mesh, ms)],
+            None => [
+                (Stage::Vertex, self.vs),
+                (Stage::Hull, self.hs),
+                (Stage::Domain, self.ds),
+                (Stage::Geometry, self.gs),
+            ]
+            .into_iter()
+            .filter_map(|(stage, signature)| signature.map(|signature| (stage, signature)))
+            .collect(),
+        };
+        if let (None, Some(vs), Some(elements)) = (self.ms, self.vs, self.input_layout) {
+            let names = elements.iter().map(semantic_name).collect::<Vec<_>>();
+            let elements = elements
+                .iter()
+                .zip(&names)
+                .map(|(element, name)| {
+                    let desc = element.desc();
+                    let component_type = format_component_type(desc.Format)
+                        .unwrap_or(D3D_REGISTER_COMPONENT_UNKNOWN);
+                    LayoutElement {
+                        semantic_name: name,
+                        semantic_index: desc.SemanticIndex,
+                        component_type,
+                    }
+                })
+                .collect::<Vec<_>>();
+            errors.extend(
+                input_layout_mismatches(&elements, &vs.inputs)
+                    .into_iter()
+                    .map(LinkageError::InputLayout),
+            );
+        }
+        for pair in stages.windows(2) {
+            errors.extend(link(pair[0], pair[1]));
+        }
+        if let Some(ps) = self.ps {
+            if let Some(&last) = stages.last() {
+                errors.extend(link(last, (Stage::Pixel, ps)));
+            }
+            for (index, &format) in self.render_target_formats.iter().enumerate() {
+                let index = index as u32;
+                if format == DXGI_FORMAT_UNKNOWN {
+                    continue;
+                }
+                let Some(output) = ps.outputs.iter().find(|output| {
+                    output.system_value_type == D3D_NAME_TARGET && output.semantic_index == index
+                }) else {
+                    errors.push(LinkageError::MissingTarget { index });
+                    continue;
+                };
+                let Some(ty) = format_component_type(format) else {
+                    continue;
+                };
+                if component_kind(output.component_type) != ty {
+                    errors.push(LinkageError::TargetComponentType {
+                        index,
+                        format,
+                        output: output.component_type,
+                    });
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ShaderBindings {
+    pub visibility: D3D12_SHADER_VISIBILITY,
+    pub resources: Vec<ShaderInputBindDesc>,
+    pub constant_buffers: Vec<ShaderBufferDesc>,
+}
+
+fn shader_visibility(kind: ShaderKind) -> D3D12_SHADER_VISIBILITY {
+    match kind {
+        ShaderKind::Vertex => D3D12_SHADER_VISIBILITY_VERTEX,
+        ShaderKind::Hull => D3D12_SHADER_VISIBILITY_HULL,
+        ShaderKind::Domain => D3D12_SHADER_VISIBILITY_DOMAIN,
+        ShaderKind::Geometry => D3D12_SHADER_VISIBILITY_GEOMETRY,
+        ShaderKind::Pixel => D3D12_SHADER_VISIBILITY_PIXEL,
+        ShaderKind::Amplification => D3D12_SHADER_VISIBILITY_AMPLIFICATION,
+        ShaderKind::Mesh => D3D12_SHADER_VISIBILITY_MESH,
+        _ => D3D12_SHADER_VISIBILITY_ALL,
+    }
+}
+
+impl ShaderBindings {
+    #[inline]
+    pub fn new(reflection: &ShaderReflection) -> windows::core::Result<Self> {
+        Ok(Self {
+            visibility: shader_visibility(reflection.shader_kind()?),
+            resources: reflection.resource_bind_desc()?,
+            constant_buffers: reflection.constant_buffer_descs()?,
+        })
+    }
+
+    #[inline]
+    pub fn from_function(function: &Function) -> Self {
+        Self {
+            visibility: shader_visibility(function.shader_kind()),
+            resources: function.resource_bind_descs().collect(),
+            constant_buffers: function.constant_buffer_descs().collect(),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum BindingError {
+    Unbound {
+        name: String,
+        range_type: D3D12_DESCRIPTOR_RANGE_TYPE,
+        register: u32,
+        space: u32,
+        count: u32,
+    },
+    Visibility {
+        name: String,
+        visibility: D3D12_SHADER_VISIBILITY,
+        stage: D3D12_SHADER_VISIBILITY,
+    },
+    Denied {
+        stage: D3D12_SHADER_VISIBILITY,
+    },
+    RootDescriptorType {
+        name: String,
+        parameter: u32,
+    },
+    RootConstantsSize {
+        name: String,
+        parameter: u32,
+        num_32bit_values: u32,
+        required: u32,
+    },
+}
+
+fn register_class(range_type: D3D12_DESCRIPTOR_RANGE_TYPE) -> char {
+    match range_type {
+        D3D12_DESCRIPTOR_RANGE_TYPE_CBV => 'b',
+        D3D12_DESCRIPTOR_RANGE_TYPE_UAV => 'u',
+        D3D12_DESCRIPTOR_RANGE_TYPE_SAMPLER => 's',
+        _ => 't',
+    }
+}
+
+impl std::fmt::Display for BindingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unbound {
+                name,
+                range_type,
+                register,
+                space,
+                count,
+            } => {
+                write!(
+                    f,
+                    "`{name}` ({}{register}, space{space}",
+                    register_class(*range_type)
+                )?;
+                match count {
+                    0 => write!(f, ", unbounded")?,
+                    1 => {}
+                    n => write!(f, ", {n} descriptors")?,
+                }
+                write!(f, ") is not bound by the root signature")
+            }
+            Self::Visibility {
+                name,
+                visibility,
+                stage,
+            } => write!(
+                f,
+                "`{name}` is bound with {visibility:?} but used from {stage:?}"
+            ),
+            Self::Denied { stage } => {
+                write!(f, "the root signature denies root access to {stage:?}")
+            }
+            Self::RootDescriptorType { name, parameter } => write!(
+                f,
+                "`{name}` cannot be bound by the root descriptor at parameter {parameter}"
+            ),
+            Self::RootConstantsSize {
+                name,
+                parameter,
+                num_32bit_values,
+                required,
+            } => write!(
+                f,
+                "`{name}` needs {required} 32-bit values but parameter {parameter} has {num_32bit_values}"
+            ),
+        }
+    }
+}
+
+pub(crate) fn range_type(ty: D3D_SHADER_INPUT_TYPE) -> D3D12_DESCRIPTOR_RANGE_TYPE {
+    match ty {
+        D3D_SIT_CBUFFER => D3D12_DESCRIPTOR_RANGE_TYPE_CBV,
+        D3D_SIT_SAMPLER => D3D12_DESCRIPTOR_RANGE_TYPE_SAMPLER,
+        D3D_SIT_UAV_RWTYPED
+        | D3D_SIT_UAV_RWSTRUCTURED
+        | D3D_SIT_UAV_RWBYTEADDRESS
+        | D3D_SIT_UAV_APPEND_STRUCTURED
+        | D3D_SIT_UAV_CONSUME_STRUCTURED
+        | D3D_SIT_UAV_RWSTRUCTURED_WITH_COUNTER
+        | D3D_SIT_UAV_FEEDBACKTEXTURE => D3D12_DESCRIPTOR_RANGE_TYPE_UAV,
+        _ => D3D12_DESCRIPTOR_RANGE_TYPE_SRV,
+    }
+}
+
+fn allows_root_descriptor(ty: D3D_SHADER_INPUT_TYPE) -> bool {
+    matches!(
+        ty,
+        D3D_SIT_CBUFFER
+            | D3D_SIT_STRUCTURED
+            | D3D_SIT_BYTEADDRESS
+            | D3D_SIT_RTACCELERATIONSTRUCTURE
+            | D3D_SIT_UAV_RWSTRUCTURED
+            | D3D_SIT_UAV_RWBYTEADDRESS
+    )
+}
+
+fn deny_flag(stage: D3D12_SHADER_VISIBILITY) -> D3D12_ROOT_SIGNATURE_FLAGS {
+    match stage {
+        D3D12_SHADER_VISIBILITY_VERTEX => D3D12_ROOT_SIGNATURE_FLAG_DENY_VERTEX_SHADER_ROOT_ACCESS,
+        D3D12_SHADER_VISIBILITY_HULL => D3D12_ROOT_SIGNATURE_FLAG_DENY_HULL_SHADER_ROOT_ACCESS,
+        D3D12_SHADER_VISIBILITY_DOMAIN => D3D12_ROOT_SIGNATURE_FLAG_DENY_DOMAIN_SHADER_ROOT_ACCESS,
+        D3D12_SHADER_VISIBILITY_GEOMETRY => {
+            D3D12_ROOT_SIGNATURE_FLAG_DENY_GEOMETRY_SHADER_ROOT_ACCESS
+        }
+        D3D12_SHADER_VISIBILITY_PIXEL => D3D12_ROOT_SIGNATURE_FLAG_DENY_PIXEL_SHADER_ROOT_ACCESS,
+        D3D12_SHADER_VISIBILITY_AMPLIFICATION => {
+            D3D12_ROOT_SIGNATURE_FLAG_DENY_AMPLIFICATION_SHADER_ROOT_ACCESS
+        }
+        D3D12_SHADER_VISIBILITY_MESH => D3D12_ROOT_SIGNATURE_FLAG_DENY_MESH_SHADER_ROOT_ACCESS,
+        _ => D3D12_ROOT_SIGNATURE_FLAG_NONE,
+    }
+}
+
+fn covers(base: u32, num: u32, space: u32, resource: &ShaderInputBindDesc) -> bool {
+    if space != resource.space || resource.bind_point < base {
+        return false;
+    }
+    match (num, resource.bind_count) {
+        (u32::MAX, _) => true,
+        (_, 0) => false,
+        (num, count) => resource.bind_point as u64 + count as u64 <= base as u64 + num as u64,
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum RootBinding {
+    Table { offset: u32 },
+    Descriptor,
+    Constants(u32),
+    StaticSampler,
+}
+
+fn root_bindings(
+    desc: &RootSignatureDesc,
+    resource: &ShaderInputBindDesc,
+) -> Vec<(u32, D3D12_SHADER_VISIBILITY, RootBinding)> {
+    let ty = range_type(resource.ty);
+    let mut ret = vec![];
+    for (index, param) in desc.root_parameters().iter().enumerate() {
+        let raw = param.param();
+        let binding = match raw.ParameterType {
+            D3D12_ROOT_PARAMETER_TYPE_DESCRIPTOR_TABLE => {
+                let mut next = 0u32;
+                let mut binding = None;
+                for range in param.descriptor_ranges() {
+                    let start = match range.OffsetInDescriptorsFromTableStart {
+                        D3D12_DESCRIPTOR_RANGE_OFFSET_APPEND => next,
+                        offset => offset,
+                    };
+                    next = start.saturating_add(range.NumDescriptors);
+                    if binding.is_none()
+                        && range.RangeType == ty
+                        && covers(
+                            range.BaseShaderRegister,
+                            range.NumDescriptors,
+                            range.RegisterSpace,
+                            resource,
+                        )
+                    {
+                        binding = Some(RootBinding::Table {
+                            offset: start + (resource.bind_point - range.BaseShaderRegister),
+                        });
+                    }
+                }
+                binding
+            }
+            D3D12_ROOT_PARAMETER_TYPE_32BIT_CONSTANTS => {
+                let constants = unsafe { raw.Anonymous.Constants };
+                (ty == D3D12_DESCRIPTOR_RANGE_TYPE_CBV
+                    && covers(
+                        constants.ShaderRegister,
+                        1,
+                        constants.RegisterSpace,
+                        resource,
+                    ))
+                .then_some(RootBinding::Constants(constants.Num32BitValues))
+            }
+            parameter_type => {
+                let descriptor = unsafe { raw.Anonymous.Descriptor };
+                let descriptor_type = match parameter_type {
+                    D3D12_ROOT_PARAMETER_TYPE_CBV => D3D12_DESCRIPTOR_RANGE_TYPE_CBV,
+                    D3D12_ROOT_PARAMETER_TYPE_SRV => D3D12_DESCRIPTOR_RANGE_TYPE_SRV,
+                    _ => D3D12_DESCRIPTOR_RANGE_TYPE_UAV,
+                };
+                (ty == descriptor_type
+                    && covers(
+                        descriptor.ShaderRegister,
+                        1,
+                        descriptor.RegisterSpace,
+                        resource,
+                    ))
+                .then_some(RootBinding::Descriptor)
+            }
+        };
+        if let Some(binding) = binding {
+            ret.push((index as u32, raw.ShaderVisibility, binding));
+        }
+    }
+    if ty == D3D12_DESCRIPTOR_RANGE_TYPE_SAMPLER {
+        for sampler in desc.samplers().iter().map(|sampler| sampler.desc()) {
+            if covers(sampler.ShaderRegister, 1, sampler.RegisterSpace, resource) {
+                ret.push((
+                    u32::MAX,
+                    sampler.ShaderVisibility,
+                    RootBinding::StaticSampler,
+                ));
+            }
+        }
+    }
+    ret
+}
+
+pub(crate) fn resolve_binding(
+    desc: &RootSignatureDesc,
+    stage: D3D12_SHADER_VISIBILITY,
+    resource: &ShaderInputBindDesc,
+) -> Result<(u32, RootBinding), BindingError> {
+    let bindings = root_bindings(desc, resource);
+    if let Some(&(parameter, _, binding)) = bindings.iter().find(|(_, visibility, _)| {
+        *visibility == D3D12_SHADER_VISIBILITY_ALL || *visibility == stage
+    }) {
+        return Ok((parameter, binding));
+    }
+    Err(match bindings.first() {
+        Some(&(_, visibility, _)) => BindingError::Visibility {
+            name: resource.name.clone(),
+            visibility,
+            stage,
+        },
+        None => BindingError::Unbound {
+            name: resource.name.clone(),
+            range_type: range_type(resource.ty),
+            register: resource.bind_point,
+            space: resource.space,
+            count: resource.bind_count,
+        },
+    })
+}
+
+pub(crate) fn required_32bit_values(shader: &ShaderBindings, name: &str) -> Option<u32> {
+    let buffer = shader.constant_buffers.iter().find(|cb| cb.name == name)?;
+    let mut end = 0;
+    for variable in &buffer.variables {
+        let size = type_size(&variable.ty, Layout::ConstantBuffer)
+            .unwrap_or_else(|_| buffer.size.saturating_sub(variable.offset));
+        end = end.max(variable.offset + size);
+    }
+    Some(end.div_ceil(4) as u32)
+}
+
+pub fn validate_bindings(
+    desc: &RootSignatureDesc,
+    shader: &ShaderBindings,
+) -> Result<(), Vec<BindingError>> {
+    let stage = shader.visibility;
+    let deny = deny_flag(stage);
+    if deny != D3D12_ROOT_SIGNATURE_FLAG_NONE
+        && desc.root_signature_flags().contains(deny)
+        && !shader.resources.is_empty()
+    {
+        return Err(vec![BindingError::Denied { stage }]);
+    }
+    let mut errors = vec![];
+    for resource in &shader.resources {
+        let (parameter, binding) = match resolve_binding(desc, stage, resource) {
+            Ok(ret) => ret,
+            Err(e) => {
+                errors.push(e);
+                continue;
+            }
+        };
+        match binding {
+            RootBinding::Descriptor if !allows_root_descriptor(resource.ty) => {
+                errors.push(BindingError::RootDescriptorType {
+                    name: resource.name.clone(),
+                    parameter,
+                });
+            }
+            RootBinding::Constants(num_32bit_values) => {
+                if let Some(required) = required_32bit_values(shader, &resource.name)
+                    && required > num_32bit_values
+                {
+                    errors.push(BindingError::RootConstantsSize {
+                        name: resource.name.clone(),
+                        parameter,
+                        num_32bit_values,
+                        required,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::bind;
+
+    fn param(
+        name: &str,
+        index: u32,
+        register: u32,
+        mask: u8,
+        component_type: D3D_REGISTER_COMPONENT_TYPE,
+    ) -> SignatureParameterDesc {
+        let system_value_type = match name {
+            "SV_Position" => D3D_NAME_POSITION,
+            "SV_Target" => D3D_NAME_TARGET,
+            "SV_VertexID" => D3D_NAME_VERTEX_ID,
+            _ => D3D_NAME_UNDEFINED,
+        };
+        SignatureParameterDesc {
+            semantic_name: name.into(),
+            semantic_index: index,
+            register,
+            system_value_type,
+            component_type,
+            mask,
+            read_write_mask: mask,
+            stream: 0,
+            min_precision: D3D_MIN_PRECISION_DEFAULT,
+        }
+    }
+
+    #[test]
+    fn linkage_test() {
+        const F32: D3D_REGISTER_COMPONENT_TYPE = D3D_REGISTER_COMPONENT_FLOAT32;
+        const U32: D3D_REGISTER_COMPONENT_TYPE = D3D_REGISTER_COMPONENT_UINT32;
+        let vs = ShaderSignature {
+            inputs: vec![
+                param("POSITION", 0, 0, 0xf, F32),
+                param("TEXCOORD", 0, 1, 0x3, F32),
+                param("SV_VertexID", 0, u32::MAX, 0x1, U32),
+            ],
+            outputs: vec![
+                param("SV_Position", 0, 0, 0xf, F32),
+                param("TEXCOORD", 0, 1, 0x3, F32),
+                param("MATERIAL", 0, 2, 0x1, U32),
+            ],
+            patch_constants: vec![],
+        };
+        let mut ps = ShaderSignature {
+            inputs: vec![
+                param("SV_Position", 0, 0, 0xf, F32),
+                param("texcoord", 0, 1, 0x3, F32),
+                param("MATERIAL", 0, 2, 0x1, U32),
+            ],
+            outputs: vec![param("SV_Target", 0, 0, 0xf, F32)],
+            patch_constants: vec![],
+        };
+        let input_layout = [
+            InputElementDesc::new()
+                .semantic_name(b"POSITION\0")
+                .format(DXGI_FORMAT_R32G32B32_FLOAT),
+            InputElementDesc::new()
+                .semantic_name(b"TEXCOORD\0")
+                .format(DXGI_FORMAT_R16G16_UINT),
+        ];
+        let formats = [DXGI_FORMAT_R8G8B8A8_UNORM];
+        assert_eq!(
+            PipelineLinkage::new()
+                .vs(&vs)
+                .ps(&ps)
+                .input_layout(&input_layout[..1])
+                .render_target_formats(&formats)
+                .validate()
+                .unwrap_err(),
+            [LinkageError::InputLayout(
+                InputLayoutMismatch::MissingElement {
+                    semantic_name: "TEXCOORD".into(),
+                    semantic_index: 0,
+                }
+            )]
+        );
+
+        ps.inputs[1].register = 3;
+        ps.inputs[2].mask = 0x3;
+        ps.inputs.push(param("NORMAL", 0, 4, 0x7, F32));
+        ps.outputs.push(param("SV_Target", 1, 1, 0x3, U32));
+        let formats = [DXGI_FORMAT_R8G8B8A8_UNORM, DXGI_FORMAT_R32G32B32A32_FLOAT];
+        let errors = PipelineLinkage::new()
+            .vs(&vs)
+            .ps(&ps)
+            .input_layout(&input_layout)
+            .render_target_formats(&formats)
+            .validate()
+            .unwrap_err();
+        assert_eq!(
+            errors,
+            [
+                LinkageError::InputLayout(InputLayoutMismatch::ComponentType {
+                    semantic_name: "TEXCOORD".into(),
+                    semantic_index: 0,
+                    layout: U32,
+                    shader: F32,
+                }),
+                LinkageError::Register {
+                    from: Stage::Vertex,
+                    to: Stage::Pixel,
+                    semantic_name: "texcoord".into(),
+                    semantic_index: 0,
+                    output: 1,
+                    input: 3,
+                },
+                LinkageError::Mask {
+                    from: Stage::Vertex,
+                    to: Stage::Pixel,
+                    semantic_name: "MATERIAL".into(),
+                    semantic_index: 0,
+                    output: 0x1,
+                    input: 0x3,
+                },
+                LinkageError::MissingOutput {
+                    from: Stage::Vertex,
+                    to: Stage::Pixel,
+                    semantic_name: "NORMAL".into(),
+                    semantic_index: 0,
+                },
+                LinkageError::TargetComponentType {
+                    index: 1,
+                    format: DXGI_FORMAT_R32G32B32A32_FLOAT,
+                    output: U32,
+                },
+            ]
+        );
+        assert_eq!(
+            errors[3].to_string(),
+            "PS reads `NORMAL0` but VS does not write it"
+        );
+
+        let ms = ShaderSignature {
+            inputs: vec![],
+            outputs: vec![param("SV_Position", 0, 0, 0xf, F32)],
+            patch_constants: vec![param("MATERIAL", 0, 1, 0x1, U32)],
+        };
+        let ps = ShaderSignature {
+            inputs: vec![
+                param("SV_Position", 0, 0, 0xf, F32),
+                param("MATERIAL", 0, 1, 0x1, U32),
+            ],
+            outputs: vec![],
+            patch_constants: vec![],
+        };
+        assert_eq!(
+            PipelineLinkage::new()
+                .ms(&ms)
+                .ps(&ps)
+                .render_target_formats(&[DXGI_FORMAT_R8G8B8A8_UNORM])
+                .validate()
+                .unwrap_err(),
+            [LinkageError::MissingTarget { index: 0 }]
+        );
+    }
+
+    #[test]
+    fn bindings_test() {
+        use crate::reflection::{Member, Type, Vector};
+        use crate::{DescriptorRange, RootParameter, StaticSamplerDesc};
+
+        let params: [RootParameter; 3] = [
+            RootParameter::constants_32bit()
+                .shader_register(0)
+                .num_32bit_values(2)
+                .into(),
+            RootParameter::descriptor_table()
+                .ranges([
+                    DescriptorRange::srv().num_descriptors(4),
+                    DescriptorRange::uav().num_descriptors(u32::MAX),
+                ])
+                .shader_visibility(D3D12_SHADER_VISIBILITY_PIXEL)
+                .into(),
+            RootParameter::srv()
+                .shader_register(0)
+                .register_space(1)
+                .into(),
+        ];
+        let samplers = [StaticSamplerDesc::new()
+            .shader_register(0)
+            .shader_visibility(D3D12_SHADER_VISIBILITY_PIXEL)];
+        let desc = RootSignatureDesc::new()
+            .parameters(&params)
+            .static_samplers(&samplers);
+        let ps = ShaderBindings {
+            visibility: D3D12_SHADER_VISIBILITY_PIXEL,
+            resources: vec![
+                bind("gPush", D3D_SIT_CBUFFER, 0, 1, 0),
+                bind("gAlbedo", D3D_SIT_TEXTURE, 1, 1, 0),
+                bind("gTextures", D3D_SIT_TEXTURE, 2, 4, 0),
+                bind("gOutputs", D3D_SIT_UAV_RWTYPED, 8, 0, 0),
+                bind("gBuffer", D3D_SIT_TEXTURE, 0, 1, 1),
+                bind("gSampler", D3D_SIT_SAMPLER, 0, 1, 0),
+                bind("gMissing", D3D_SIT_STRUCTURED, 3, 1, 1),
+            ],
+            constant_buffers: vec![ShaderBufferDesc {
+                name: "gPush".into(),
+                ty: D3D_CT_CBUFFER,
+                size: 16,
+                members: vec![],
+                variables: vec![
+                    Member {
+                        name: "index".into(),
+                        ty: Type::Uint,
+                        offset: 0,
+                    },
+                    Member {
+                        name: "color".into(),
+                        ty: Type::Vector(Vector {
+                            ty: Box::new(Type::Float),
+                            len: 3,
+                        }),
+                        offset: 4,
+                    },
+                ],
+            }],
+        };
+        let errors = validate_bindings(&desc, &ps).unwrap_err();
+        assert_eq!(
+            errors,
+            [
+                BindingError::RootConstantsSize {
+                    name: "gPush".into(),
+                    parameter: 0,
+                    num_32bit_values: 2,
+                    required: 4,
+                },
+                BindingError::Unbound {
+                    name: "gTextures".into(),
+                    range_type: D3D12_DESCRIPTOR_RANGE_TYPE_SRV,
+                    register: 2,
+                    space: 0,
+                    count: 4,
+                },
+                BindingError::RootDescriptorType {
+                    name: "gBuffer".into(),
+                    parameter: 2,
+                },
+                BindingError::Unbound {
+                    name: "gMissing".into(),
+                    range_type: D3D12_DESCRIPTOR_RANGE_TYPE_SRV,
+                    register: 3,
+                    space: 1,
+                    count: 1,
+                },
+            ]
+        );
+        assert_eq!(
+            errors[1].to_string(),
+            "`gTextures` (t2, space0, 4 descriptors) is not bound by the root signature"
+        );
+
+        let vs = ShaderBindings {
+            visibility: D3D12_SHADER_VISIBILITY_VERTEX,
+            resources: vec![
+                bind("gAlbedo", D3D_SIT_TEXTURE, 1, 1, 0),
+                bind("gSampler", D3D_SIT_SAMPLER, 0, 1, 0),
+            ],
+            constant_buffers: vec![],
+        };
+        assert_eq!(
+            validate_bindings(&desc, &vs).unwrap_err(),
+            [
+                BindingError::Visibility {
+                    name: "gAlbedo".into(),
+                    visibility: D3D12_SHADER_VISIBILITY_PIXEL,
+                    stage: D3D12_SHADER_VISIBILITY_VERTEX,
+                },
+                BindingError::Visibility {
+                    name: "gSampler".into(),
+                    visibility: D3D12_SHADER_VISIBILITY_PIXEL,
+                    stage: D3D12_SHADER_VISIBILITY_VERTEX,
+                },
+            ]
+        );
+        let desc = desc.flags(D3D12_ROOT_SIGNATURE_FLAG_DENY_VERTEX_SHADER_ROOT_ACCESS);
+        assert_eq!(
+            validate_bindings(&desc, &vs).unwrap_err(),
+            [BindingError::Denied {
+                stage: D3D12_SHADER_VISIBILITY_VERTEX
+            }]
+        );
+
+        let mut ps = ps;
+        assert_eq!(required_32bit_values(&ps, "gPush"), Some(4));
+        ps.constant_buffers[0].size = 32;
+        ps.constant_buffers[0].variables[1].ty = Type::Unsupported;
+        assert_eq!(required_32bit_values(&ps, "gPush"), Some(8));
+    }
+}